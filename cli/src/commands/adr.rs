@@ -0,0 +1,160 @@
+use super::Context;
+use crate::output::ExitStatus;
+use clap::Subcommand;
+use colored::*;
+use memory_engine::{import_adr_directory, DecisionRecord, DecisionStatus};
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Subcommand)]
+pub enum AdrAction {
+    /// Import Markdown ADRs from a directory
+    Import {
+        /// Directory of ADRs (defaults to docs/adr in the project root)
+        dir: Option<String>,
+    },
+    /// Record a new decision
+    Add {
+        title: String,
+        /// Status: proposed, accepted, deprecated, superseded or rejected
+        #[arg(long, default_value = "proposed")]
+        status: String,
+        #[arg(long, default_value = "")]
+        context: String,
+        #[arg(long, default_value = "")]
+        decision: String,
+        #[arg(long, default_value = "")]
+        consequences: String,
+        /// Entity governed by the decision (repeatable)
+        #[arg(long = "entity")]
+        entities: Vec<String>,
+        /// File path or glob governed by the decision (repeatable)
+        #[arg(long = "file")]
+        files: Vec<String>,
+    },
+    /// List recorded decisions
+    List,
+    /// Show a decision by ADR number or id
+    Show { id: String },
+}
+
+#[derive(Serialize)]
+struct DecisionList {
+    decisions: Vec<DecisionRecord>,
+}
+
+/// Short human label for a decision: its ADR number, or an id prefix
+pub fn label(record: &DecisionRecord) -> String {
+    record.number
+        .map(|n| format!("ADR-{:03}", n))
+        .unwrap_or_else(|| record.id[..8].to_string())
+}
+
+pub fn run(ctx: &Context, action: AdrAction) -> anyhow::Result<()> {
+    let project = ctx.project()?;
+    let storage = ctx.storage(&project)?;
+
+    match action {
+        AdrAction::Import { dir } => {
+            // Without an explicit directory, look in the project's docs/adr
+            let dir = match dir {
+                Some(dir) => ctx.current_dir.join(dir),
+                None => project.root.join("docs/adr"),
+            };
+            ctx.out.line(format!("📐 Importing decisions from {}", dir.display()).cyan());
+            if !dir.is_dir() {
+                ctx.out.fail(ExitStatus::Failure, &format!("Directory not found: {}", dir.display()));
+            }
+
+            let mut records = import_adr_directory(&dir)?;
+            for record in &mut records {
+                record.source_path = record.source_path
+                    .as_ref()
+                    .map(|source| project.relative_path(&ctx.current_dir, Path::new(source)));
+                record.id = storage.save_decision(record)?;
+            }
+
+            let report = DecisionList { decisions: records };
+            if !ctx.out.is_text() {
+                return ctx.out.emit_list(&report, &report.decisions);
+            }
+            for record in &report.decisions {
+                print_decision(record, false);
+            }
+            println!("\n💾 {}", format!("Imported {} decisions", report.decisions.len()).green());
+        }
+        AdrAction::Add { title, status, context, decision, consequences, entities, files } => {
+            let Some(status) = DecisionStatus::from_str(&status) else {
+                ctx.out.fail(ExitStatus::Failure, &format!("Unknown status '{}'", status));
+            };
+
+            let mut record = DecisionRecord::new(title).with_status(status);
+            record.context = context;
+            record.decision = decision;
+            record.consequences = consequences;
+            for entity in entities {
+                record = record.with_entity(entity);
+            }
+            for file in files {
+                record = record.with_file(file);
+            }
+            storage.save_decision(&record)?;
+
+            if !ctx.out.is_text() {
+                return ctx.out.emit(&record);
+            }
+            println!("{}", "✅ Decision recorded".green());
+            println!("Decision id: {}", record.id);
+        }
+        AdrAction::List => {
+            let report = DecisionList { decisions: storage.load_decisions()? };
+            if !ctx.out.is_text() {
+                return ctx.out.emit_list(&report, &report.decisions);
+            }
+
+            if report.decisions.is_empty() {
+                println!("No decisions recorded yet");
+            } else {
+                println!("{}", "📐 Architecture decisions".blue().bold());
+                for record in &report.decisions {
+                    print_decision(record, false);
+                }
+            }
+        }
+        AdrAction::Show { id } => {
+            let Some(record) = storage.load_decision(&id)? else {
+                ctx.out.fail(ExitStatus::Failure, &format!("No decision found matching '{}'", id));
+            };
+
+            if !ctx.out.is_text() {
+                return ctx.out.emit(&record);
+            }
+            print_decision(&record, true);
+        }
+    }
+
+    Ok(())
+}
+
+fn print_decision(record: &DecisionRecord, show_body: bool) {
+    let status = if record.status.is_in_force() {
+        record.status.as_str().green()
+    } else {
+        record.status.as_str().red()
+    };
+    println!("  {} {} [{}]", label(record).yellow(), record.title.bold(), status);
+
+    if show_body {
+        for (heading, text) in [("Context", &record.context), ("Decision", &record.decision), ("Consequences", &record.consequences)] {
+            if !text.is_empty() {
+                println!("    {}: {}", heading.cyan(), text);
+            }
+        }
+        if !record.entities.is_empty() {
+            println!("    governs entities: {}", record.entities.join(", ").green());
+        }
+        if !record.files.is_empty() {
+            println!("    governs files: {}", record.files.join(", ").blue());
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use super::Context;
+use crate::output::ExitStatus;
+use colored::*;
+use memory_engine::{record_centrality, CodeEntity, CodeParser, Relationship};
+use serde::Serialize;
+
+/// Result of analyzing one file
+#[derive(Serialize)]
+struct FileAnalysis {
+    file: String,
+    language: Option<String>,
+    entities: Vec<CodeEntity>,
+    relationships: Vec<Relationship>,
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct AnalyzeReport {
+    path: String,
+    entity_count: usize,
+    relationship_count: usize,
+    references_resolved: usize,
+    files: Vec<FileAnalysis>,
+}
+
+pub fn run(ctx: &Context, path: String) -> anyhow::Result<()> {
+    ctx.out.line(format!("🔬 Analyzing: {}", path).cyan());
+
+    let project = ctx.project()?;
+    let storage = ctx.storage(&project)?;
+    let filter = project.config.file_filter()?;
+
+    // Load existing memory
+    let mut memory = storage.load_memory(&project.root.to_string_lossy())?;
+
+    let parser = match CodeParser::for_project(&project) {
+        Ok(parser) => parser,
+        Err(e) => ctx.out.fail(ExitStatus::Failure, &format!("Error creating parser: {}", e)),
+    };
+
+    let target = ctx.current_dir.join(&path);
+    let is_directory = target.is_dir();
+    let files = if is_directory {
+        filter.collect_files(&project.root, &target.canonicalize()?)?
+            .into_iter()
+            .filter(|file| is_parseable(&parser, file))
+            .collect()
+    } else if !target.is_file() {
+        ctx.out.fail(ExitStatus::Failure, &format!("File not found: {}", path));
+    } else if !filter.is_within_size_limit(&target) {
+        ctx.out.fail(
+            ExitStatus::Failure,
+            &format!("Skipping {}: larger than files.max_file_size ({} bytes)", path, project.config.files.max_file_size),
+        );
+    } else {
+        vec![target]
+    };
+
+    let mut analyses = Vec::new();
+    for file in &files {
+        // Store paths relative to the project root so queries work from anywhere
+        let relative = project.relative_path(&ctx.current_dir, file);
+        let content = std::fs::read_to_string(file);
+        let language = match &content {
+            Ok(content) => parser.detect_language(&relative, content),
+            Err(_) => parser.language_for_path(&relative),
+        };
+        let parsed = content
+            .map_err(anyhow::Error::from)
+            .and_then(|content| parser.parse_source(&relative, &content));
+
+        let mut analysis = FileAnalysis {
+            file: relative.clone(),
+            language: language.map(|l| l.to_string()),
+            entities: Vec::new(),
+            relationships: Vec::new(),
+            error: None,
+        };
+
+        match parsed {
+            Ok((entities, relationships)) => {
+                memory.remove_file(&relative);
+                for entity in &entities {
+                    memory.add_entity(entity.clone());
+                }
+                for relationship in &relationships {
+                    memory.add_relationship(relationship.clone());
+                }
+                analysis.entities = entities;
+                analysis.relationships = relationships;
+            }
+            Err(e) => analysis.error = Some(e.to_string()),
+        }
+
+        analyses.push(analysis);
+    }
+
+    // Link inheritance and other cross-file edges now that every file is in memory
+    let references_resolved = memory.resolve_references();
+    memory.merge_partial_types();
+    record_centrality(&mut memory);
+
+    // Save updated memory to database
+    storage.save_memory(&memory)?;
+
+    let report = AnalyzeReport {
+        path: path.clone(),
+        entity_count: analyses.iter().map(|a| a.entities.len()).sum(),
+        relationship_count: analyses.iter().map(|a| a.relationships.len()).sum(),
+        references_resolved,
+        files: analyses,
+    };
+    let failures = report.files.iter().filter(|a| a.error.is_some()).count();
+
+    if !ctx.out.is_text() {
+        ctx.out.emit_list(&report, &report.files)?;
+    } else {
+        print_report(&report, is_directory);
+    }
+
+    if failures > 0 {
+        // Machine-readable reports already carry the per-file errors
+        if !ctx.out.is_text() {
+            std::process::exit(ExitStatus::ParseError.code());
+        }
+
+        let message = match report.files.iter().find_map(|a| a.error.as_ref()) {
+            Some(error) if !is_directory => format!("Error parsing file: {}", error),
+            _ => format!("{} file(s) failed to parse", failures),
+        };
+        ctx.out.fail(ExitStatus::ParseError, &message);
+    }
+    Ok(())
+}
+
+fn print_report(report: &AnalyzeReport, is_directory: bool) {
+    if is_directory {
+        for analysis in &report.files {
+            match &analysis.error {
+                Some(error) => println!("  ❌ Error parsing {}: {}", analysis.file, error),
+                None => println!("  {} ({} entities)", analysis.file.blue(), analysis.entities.len()),
+            }
+        }
+    } else if report.files.iter().any(|a| a.error.is_some()) {
+        return;
+    }
+
+    println!("\n📊 Analysis Results:");
+    if is_directory {
+        println!("Files analyzed: {}", report.files.len());
+    }
+    println!("Entities found: {}", report.entity_count);
+    println!("Relationships found: {}", report.relationship_count);
+    if report.references_resolved > 0 {
+        println!("Cross-file references linked: {}", report.references_resolved);
+    }
+
+    if !is_directory && report.entity_count > 0 {
+        println!("\n🔍 Entities:");
+        for entity in report.files.iter().flat_map(|a| &a.entities) {
+            println!("  {} {} at line {}",
+                entity.entity_type.as_str(),
+                entity.qualified_name.green(),
+                entity.line_start
+            );
+        }
+    }
+
+    println!("\n💾 {}", "Memory updated and saved!".green());
+}
+
+
+// Files without an extension (scripts) are parsed when a parser recognizes their content
+fn is_parseable(parser: &CodeParser, file: &std::path::Path) -> bool {
+    let path = file.to_string_lossy();
+    if parser.language_for_path(&path).is_some() {
+        return true;
+    }
+    file.extension().is_none()
+        && std::fs::read_to_string(file).map(|content| parser.detect_language(&path, &content).is_some()).unwrap_or(false)
+}
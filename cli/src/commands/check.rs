@@ -0,0 +1,54 @@
+use super::Context;
+use crate::output::ExitStatus;
+use colored::*;
+use memory_engine::{RuleSet, RuleViolation};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct CheckReport {
+    rules: usize,
+    violations: Vec<RuleViolation>,
+}
+
+pub fn run(ctx: &Context) -> anyhow::Result<()> {
+    let project = ctx.project()?;
+    let storage = ctx.storage(&project)?;
+    let rules = RuleSet::new(&project.config.rules)?;
+    if rules.is_empty() {
+        ctx.out.line("No architecture rules configured. Add [[rules]] to .aimemoryengine/config.toml");
+    }
+
+    let memory = storage.load_memory(&project.root.to_string_lossy())?;
+    let report = CheckReport { rules: rules.len(), violations: rules.check(&memory) };
+    if !ctx.out.is_text() {
+        ctx.out.emit_list(&report, &report.violations)?;
+        if !report.violations.is_empty() {
+            std::process::exit(ExitStatus::RulesViolated.code());
+        }
+        return Ok(());
+    }
+
+    if report.violations.is_empty() {
+        if !rules.is_empty() {
+            println!("{}", format!("✅ {} architecture rules hold", report.rules).green());
+        }
+        return Ok(());
+    }
+
+    // Listed under each rule, in file and line order
+    let mut by_rule: Vec<&RuleViolation> = report.violations.iter().collect();
+    by_rule.sort_by(|a, b| a.rule.cmp(&b.rule));
+    let mut current_rule = None;
+    for violation in by_rule {
+        if current_rule != Some(&violation.rule) {
+            println!("{}", format!("🚫 {}", violation.rule).red().bold());
+            current_rule = Some(&violation.rule);
+        }
+        let target = match &violation.target_file {
+            Some(file) => format!("{} ({})", violation.target, file),
+            None => violation.target.clone(),
+        };
+        println!("  {}:{} {} {}", violation.file.blue(), violation.line, violation.kind, target.yellow());
+    }
+    ctx.out.fail(ExitStatus::RulesViolated, &format!("{} architecture rule violations", report.violations.len()));
+}
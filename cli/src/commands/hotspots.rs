@@ -0,0 +1,73 @@
+use super::Context;
+use colored::*;
+use memory_engine::{git_churn, rank_hotspots, CodeEntity, Hotspot};
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Serialize)]
+struct HotspotsReport {
+    /// False when no git history was available and only centrality was ranked
+    churn: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    since: Option<String>,
+    hotspots: Vec<Hotspot>,
+}
+
+pub fn run(ctx: &Context, path: Option<String>, since: Option<String>, limit: usize) -> anyhow::Result<()> {
+    let project = ctx.project()?;
+    let storage = ctx.storage(&project)?;
+
+    let scope = path.map(|path| project.relative_path(&ctx.current_dir, Path::new(&path))).filter(|scope| !scope.is_empty());
+    let entities: Vec<CodeEntity> = storage
+        .find_entities_with_centrality()?
+        .into_iter()
+        .filter(|entity| match &scope {
+            Some(scope) => entity.file_path == *scope || entity.file_path.starts_with(&format!("{}/", scope)),
+            None => true,
+        })
+        .collect();
+
+    let churn = match git_churn(&project.root, since.as_deref()) {
+        Ok(churn) => Some(churn),
+        Err(e) => {
+            ctx.out.line(format!("⚠️  No git history ({}); ranking by centrality alone", e).yellow());
+            None
+        }
+    };
+    let mut hotspots = rank_hotspots(entities, &churn.clone().unwrap_or_default());
+    hotspots.truncate(limit);
+
+    let report = HotspotsReport { churn: churn.is_some(), since, hotspots };
+    if !ctx.out.is_text() {
+        return ctx.out.emit_list(&report, &report.hotspots);
+    }
+
+    if report.hotspots.is_empty() {
+        println!("No hotspots found. Run 'aimemoryengine analyze' first to score the dependency graph.");
+        return Ok(());
+    }
+
+    println!("{}", "🔥 Hotspots: central entities in frequently changed files".blue().bold());
+    for (rank, hotspot) in report.hotspots.iter().enumerate() {
+        let entity = &hotspot.entity;
+        println!(
+            "  {:>2}. {} {} {}:{}",
+            rank + 1,
+            entity.entity_type.as_str(),
+            entity.qualified_name.green(),
+            entity.file_path.blue(),
+            entity.line_start
+        );
+        let centrality = entity.centrality.clone().unwrap_or_default();
+        println!(
+            "      score {:.2}  pagerank {:.4}  in-degree {}  betweenness {:.3}  commits {}  lines changed {}",
+            hotspot.score,
+            centrality.pagerank,
+            centrality.in_degree,
+            centrality.betweenness,
+            hotspot.churn.commits,
+            hotspot.churn.lines_changed
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use super::Context;
+use crate::output::ExitStatus;
+use chrono::{DateTime, Utc};
+use clap::Subcommand;
+use colored::*;
+use memory_engine::{LicenseManager, LicenseValidation};
+use serde::Serialize;
+
+#[derive(Subcommand)]
+pub enum LicenseAction {
+    /// Activate license with provided key
+    Activate { key: String },
+    /// Check current license status
+    Status,
+    /// Remove current license
+    Remove,
+}
+
+/// License details safe to print: the key is masked
+#[derive(Serialize)]
+struct LicenseReport {
+    key: Option<String>,
+    validation: Option<LicenseValidation>,
+    last_validated: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize)]
+struct RemoveReport {
+    removed: bool,
+}
+
+fn mask_key(key: &str) -> String {
+    format!("{}****", key.get(..8).unwrap_or(key))
+}
+
+fn license_manager(ctx: &Context) -> LicenseManager {
+    match LicenseManager::new() {
+        Ok(license_manager) => license_manager,
+        Err(e) => ctx.out.fail(
+            ExitStatus::Failure,
+            &format!("License manager error: {}. Make sure you have proper Keygen configuration.", e),
+        ),
+    }
+}
+
+pub async fn run(ctx: &Context, action: LicenseAction) -> anyhow::Result<()> {
+    match action {
+        LicenseAction::Activate { key } => {
+            ctx.out.line("🔐 Activating license...".cyan());
+            let license_manager = license_manager(ctx);
+
+            let validation = match license_manager.validate_license(&key).await {
+                Ok(validation) => validation,
+                Err(e) => ctx.out.fail(ExitStatus::LicenseInvalid, &format!("License validation failed: {}", e)),
+            };
+            if !validation.valid {
+                ctx.out.fail(ExitStatus::LicenseInvalid, "Invalid license key. Please check your key and try again.");
+            }
+            license_manager.save_license(&key, &validation)?;
+
+            let report = LicenseReport {
+                key: Some(mask_key(&key)),
+                validation: Some(validation),
+                last_validated: Some(Utc::now()),
+            };
+            if !ctx.out.is_text() {
+                return ctx.out.emit(&report);
+            }
+
+            println!("{}", "✅ License activated successfully!".green());
+            if let Some(validation) = &report.validation {
+                if let Some(expires_at) = validation.expires_at {
+                    println!("License expires: {}", expires_at.format("%Y-%m-%d %H:%M:%S UTC"));
+                }
+                print_usage(validation);
+            }
+        }
+
+        LicenseAction::Status => {
+            ctx.out.line("📋 License Status".blue().bold());
+            let license_manager = license_manager(ctx);
+
+            let cached_license = match license_manager.load_cached_license() {
+                Ok(Some(cached_license)) => cached_license,
+                Ok(None) => {
+                    ctx.out.line(format!("Use: {} to activate your license", "aimemoryengine license activate <your-key>".yellow()));
+                    ctx.out.fail(ExitStatus::LicenseInvalid, "No license found.");
+                }
+                Err(e) => ctx.out.fail(ExitStatus::Failure, &format!("Error reading license: {}", e)),
+            };
+
+            let report = LicenseReport {
+                key: Some(mask_key(&cached_license.key)),
+                validation: cached_license.cached_validation,
+                last_validated: cached_license.last_validated,
+            };
+            if !ctx.out.is_text() {
+                return ctx.out.emit(&report);
+            }
+
+            println!("License Key: {}", report.key.as_deref().unwrap_or(""));
+            if let Some(validation) = &report.validation {
+                if validation.valid {
+                    println!("Status: {}", "✅ Active".green());
+                } else {
+                    println!("Status: {}", "❌ Invalid".red());
+                }
+
+                if let Some(expires_at) = validation.expires_at {
+                    let days_until_expiry = (expires_at - Utc::now()).num_days();
+                    println!("Expires: {} ({} days)",
+                        expires_at.format("%Y-%m-%d %H:%M:%S UTC"),
+                        days_until_expiry);
+                }
+
+                print_usage(validation);
+            }
+
+            if let Some(last_validated) = report.last_validated {
+                println!("Last Validated: {}", last_validated.format("%Y-%m-%d %H:%M:%S UTC"));
+            }
+        }
+
+        LicenseAction::Remove => {
+            ctx.out.line("🗑️  Removing license...".red());
+            let license_manager = license_manager(ctx);
+
+            if let Err(e) = license_manager.remove_license() {
+                ctx.out.fail(ExitStatus::Failure, &format!("Error removing license: {}", e));
+            }
+
+            if !ctx.out.is_text() {
+                return ctx.out.emit(&RemoveReport { removed: true });
+            }
+            println!("{}", "✅ License removed successfully!".green());
+        }
+    }
+
+    Ok(())
+}
+
+fn print_usage(validation: &LicenseValidation) {
+    if let Some(usage_count) = validation.usage_count {
+        if let Some(usage_limit) = validation.usage_limit {
+            println!("Usage: {}/{}", usage_count, usage_limit);
+        } else {
+            println!("Usage: {} (unlimited)", usage_count);
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use super::Context;
+use crate::output::ExitStatus;
+use colored::*;
+use memory_engine::{summarize_metrics, CodeEntity, EntityMetrics, EntityType, MetricKind, MetricsGrouping, MetricsSummary};
+use serde::Serialize;
+use std::path::Path;
+
+/// Minimum values an entity or group must reach on at least one metric to be reported
+#[derive(Debug, Default)]
+pub struct Thresholds {
+    pub complexity: Option<u32>,
+    pub loc: Option<u32>,
+    pub nesting: Option<u32>,
+    pub params: Option<u32>,
+}
+
+impl Thresholds {
+    fn is_empty(&self) -> bool {
+        self.complexity.is_none() && self.loc.is_none() && self.nesting.is_none() && self.params.is_none()
+    }
+
+    fn reached_by(&self, value: impl Fn(MetricKind) -> f64) -> bool {
+        self.is_empty()
+            || [
+                (MetricKind::Complexity, self.complexity),
+                (MetricKind::Loc, self.loc),
+                (MetricKind::Nesting, self.nesting),
+                (MetricKind::Params, self.params),
+            ]
+            .iter()
+            .any(|(metric, min)| min.is_some_and(|min| value(*metric) >= min as f64))
+    }
+}
+
+#[derive(Serialize)]
+struct FunctionMetrics {
+    name: String,
+    qualified_name: String,
+    file: String,
+    line: u32,
+    #[serde(flatten)]
+    metrics: EntityMetrics,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum MetricsRow {
+    Function(FunctionMetrics),
+    Group(MetricsSummary),
+}
+
+#[derive(Serialize)]
+struct MetricsReport {
+    by: String,
+    sort: String,
+    rows: Vec<MetricsRow>,
+}
+
+pub fn run(ctx: &Context, path: Option<String>, sort: String, by: String, limit: usize, thresholds: Thresholds) -> anyhow::Result<()> {
+    let Some(sort_by) = MetricKind::from_str(&sort) else {
+        ctx.out.fail(ExitStatus::Failure, &format!("Unknown metric '{}' (use complexity, loc, nesting, params or comments)", sort));
+    };
+    let grouping = match by.as_str() {
+        "entity" => None,
+        other => match MetricsGrouping::from_str(other) {
+            Some(grouping) => Some(grouping),
+            None => ctx.out.fail(ExitStatus::Failure, &format!("Unknown grouping '{}' (use entity, file or module)", by)),
+        },
+    };
+
+    let project = ctx.project()?;
+    let storage = ctx.storage(&project)?;
+
+    // Only report what lies under the requested file or directory
+    let scope = path.map(|path| project.relative_path(&ctx.current_dir, Path::new(&path))).filter(|scope| !scope.is_empty());
+    let functions: Vec<CodeEntity> = storage
+        .find_entities_with_metrics()?
+        .into_iter()
+        .filter(|entity| entity.entity_type == EntityType::Function)
+        .filter(|entity| match &scope {
+            Some(scope) => entity.file_path == *scope || entity.file_path.starts_with(&format!("{}/", scope)),
+            None => true,
+        })
+        .collect();
+
+    let mut rows: Vec<(f64, MetricsRow)> = match grouping {
+        None => functions
+            .into_iter()
+            .filter_map(|entity| {
+                let metrics = entity.metrics?;
+                thresholds.reached_by(|metric| metrics.value(metric)).then(|| {
+                    let row = FunctionMetrics {
+                        name: entity.name,
+                        qualified_name: entity.qualified_name,
+                        file: entity.file_path,
+                        line: entity.line_start,
+                        metrics,
+                    };
+                    (row.metrics.value(sort_by), MetricsRow::Function(row))
+                })
+            })
+            .collect(),
+        Some(grouping) => summarize_metrics(&functions, grouping)
+            .into_iter()
+            .filter(|summary| thresholds.reached_by(|metric| summary.value(metric)))
+            .map(|summary| (summary.value(sort_by), MetricsRow::Group(summary)))
+            .collect(),
+    };
+    // Largest first; a stable sort keeps ties in file order
+    rows.sort_by(|a, b| b.0.total_cmp(&a.0));
+    rows.truncate(limit);
+
+    let report = MetricsReport {
+        by,
+        sort,
+        rows: rows.into_iter().map(|(_, row)| row).collect(),
+    };
+    if !ctx.out.is_text() {
+        return ctx.out.emit_list(&report, &report.rows);
+    }
+
+    if report.rows.is_empty() {
+        println!("No measured functions found. Run 'aimemoryengine analyze' first, or lower the thresholds.");
+        return Ok(());
+    }
+
+    println!("{}", format!("📏 Metrics by {}, sorted by {}", report.by, report.sort).blue().bold());
+    for row in &report.rows {
+        match row {
+            MetricsRow::Function(function) => {
+                let m = &function.metrics;
+                println!("  {} {}:{}", function.qualified_name.green(), function.file.blue(), function.line);
+                println!(
+                    "    complexity {}  loc {}  nesting {}  params {}  comments {:.0}%",
+                    highlight(m.cyclomatic_complexity, 10),
+                    m.lines_of_code,
+                    highlight(m.max_nesting, 4),
+                    m.param_count,
+                    m.comment_ratio * 100.0
+                );
+            }
+            MetricsRow::Group(summary) => {
+                println!("  {} ({} functions)", summary.path.blue(), summary.functions);
+                println!(
+                    "    complexity {} total, {:.1} avg, {} max  loc {}  nesting {}  comments {:.0}%",
+                    summary.total_complexity,
+                    summary.average_complexity,
+                    highlight(summary.max_complexity, 10),
+                    summary.lines_of_code,
+                    highlight(summary.max_nesting, 4),
+                    summary.comment_ratio * 100.0
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+// Values at or above a commonly used warning level stand out
+fn highlight(value: u32, warn_at: u32) -> ColoredString {
+    if value >= warn_at {
+        value.to_string().red().bold()
+    } else {
+        value.to_string().normal()
+    }
+}
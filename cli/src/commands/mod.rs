@@ -0,0 +1,39 @@
+pub mod project;
+pub mod query;
+pub mod analyze;
+pub mod metrics;
+pub mod unused;
+pub mod check;
+pub mod hotspots;
+pub mod overview;
+pub mod session;
+pub mod adr;
+pub mod license;
+
+use crate::output::{ExitStatus, Output};
+use memory_engine::{MemoryStorage, Project};
+use std::path::PathBuf;
+
+/// State shared by every command
+pub struct Context {
+    pub out: Output,
+    pub current_dir: PathBuf,
+    pub explicit_root: Option<PathBuf>,
+}
+
+impl Context {
+    /// Find the initialized project, exiting with `NotInitialized` when there is none
+    pub fn project(&self) -> anyhow::Result<Project> {
+        match Project::discover(&self.current_dir, self.explicit_root.as_deref())? {
+            Some(project) => Ok(project),
+            None => self.out.fail(
+                ExitStatus::NotInitialized,
+                "Memory engine not initialized. Run 'aimemoryengine init' first.",
+            ),
+        }
+    }
+
+    pub fn storage(&self, project: &Project) -> anyhow::Result<MemoryStorage> {
+        MemoryStorage::new(&project.database_path().to_string_lossy())
+    }
+}
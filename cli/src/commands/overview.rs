@@ -0,0 +1,35 @@
+use super::Context;
+use colored::*;
+use memory_engine::build_overview;
+use std::path::Path;
+
+pub fn run(ctx: &Context, output: Option<String>) -> anyhow::Result<()> {
+    let project = ctx.project()?;
+    let storage = ctx.storage(&project)?;
+    let memory = storage.load_memory(&project.root.to_string_lossy())?;
+    let overview = build_overview(&memory);
+
+    if !ctx.out.is_text() {
+        return ctx.out.emit_list(&overview, &overview.components);
+    }
+    if overview.components.is_empty() {
+        println!("No files analyzed yet. Run 'aimemoryengine analyze' first.");
+        return Ok(());
+    }
+
+    let markdown = overview.to_markdown();
+    match output {
+        Some(output) => {
+            let path = ctx.current_dir.join(Path::new(&output));
+            std::fs::write(&path, markdown)?;
+            println!(
+                "{} {} ({} components)",
+                "📝 Wrote architecture overview to".green(),
+                path.display(),
+                overview.components.len()
+            );
+        }
+        None => print!("{}", markdown),
+    }
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use super::Context;
+use colored::*;
+use memory_engine::{MemoryStats, MemoryStorage, Project, ProjectConfig, ProjectMemory, PROJECT_ENV_VAR};
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Serialize)]
+struct InitReport {
+    project_path: String,
+    database: String,
+    config: Option<String>, // Set when a new default config was written
+}
+
+#[derive(Serialize)]
+struct StatusReport {
+    #[serde(flatten)]
+    stats: MemoryStats,
+    database: String,
+}
+
+#[derive(Serialize)]
+struct ResetReport {
+    database: Option<String>,
+    deleted: bool,
+}
+
+pub fn init(ctx: &Context) -> anyhow::Result<()> {
+    ctx.out.line("🧠 Initializing AI Memory Engine...".green());
+
+    // Initialize in the current directory unless a root was given explicitly
+    let root = ctx.explicit_root
+        .clone()
+        .or_else(|| std::env::var_os(PROJECT_ENV_VAR).map(PathBuf::from))
+        .map(|p| ctx.current_dir.join(p))
+        .unwrap_or_else(|| ctx.current_dir.clone());
+
+    let config = if ProjectConfig::write_default(&root)? {
+        Some(ProjectConfig::path_for(&root).to_string_lossy().to_string())
+    } else {
+        None
+    };
+    let project = Project::open(&root)?;
+    let db_path = project.database_path();
+    if let Some(db_dir) = db_path.parent() {
+        std::fs::create_dir_all(db_dir)?;
+    }
+
+    // Create storage and initialize empty memory
+    let storage = MemoryStorage::new(&db_path.to_string_lossy())?;
+    let memory = ProjectMemory::new(project.root.to_string_lossy().to_string());
+    storage.save_memory(&memory)?;
+
+    let report = InitReport {
+        project_path: project.root.to_string_lossy().to_string(),
+        database: db_path.to_string_lossy().to_string(),
+        config,
+    };
+
+    if !ctx.out.is_text() {
+        return ctx.out.emit(&report);
+    }
+
+    if let Some(config) = &report.config {
+        println!("Config: {}", config);
+    }
+    println!("{}", "✅ Memory engine initialized!".green());
+    println!("Project path: {}", report.project_path);
+    println!("Database: {}", report.database);
+    Ok(())
+}
+
+pub fn status(ctx: &Context) -> anyhow::Result<()> {
+    ctx.out.line("📊 Memory Engine Status".blue().bold());
+    let project = ctx.project()?;
+    let db_path = project.database_path();
+
+    let storage = ctx.storage(&project)?;
+    let (entity_count, relationship_count, file_count) = storage.get_stats()?;
+
+    let report = StatusReport {
+        stats: MemoryStats {
+            entity_count,
+            relationship_count,
+            file_count,
+            project_path: project.root.to_string_lossy().to_string(),
+        },
+        database: db_path.to_string_lossy().to_string(),
+    };
+
+    if !ctx.out.is_text() {
+        return ctx.out.emit(&report);
+    }
+
+    println!("Project: {}", report.stats.project_path);
+    println!("Database: {}", report.database);
+    println!("Entities: {}", report.stats.entity_count);
+    println!("Relationships: {}", report.stats.relationship_count);
+    println!("Files tracked: {}", report.stats.file_count);
+    Ok(())
+}
+
+pub fn reset(ctx: &Context) -> anyhow::Result<()> {
+    ctx.out.line("🗑️  Resetting project memory...".red());
+
+    let database = Project::discover(&ctx.current_dir, ctx.explicit_root.as_deref())?
+        .map(|project| project.database_path());
+
+    let mut report = ResetReport {
+        database: database.as_ref().map(|p| p.to_string_lossy().to_string()),
+        deleted: false,
+    };
+
+    if let Some(db_path) = database.filter(|p| p.exists()) {
+        std::fs::remove_file(&db_path)?;
+        report.deleted = true;
+    }
+
+    if !ctx.out.is_text() {
+        return ctx.out.emit(&report);
+    }
+
+    if report.deleted {
+        println!("{}", "✅ Memory database deleted successfully!".green());
+    } else {
+        println!("{}", "ℹ️  No memory database found to reset.".yellow());
+    }
+    Ok(())
+}
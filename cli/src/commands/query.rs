@@ -0,0 +1,94 @@
+use super::Context;
+use crate::output::ExitStatus;
+use colored::*;
+use memory_engine::{CodeEntity, DecisionRecord};
+use serde::Serialize;
+
+/// An entity matching the query, with the decisions that govern it
+#[derive(Serialize)]
+struct QueryMatch {
+    #[serde(flatten)]
+    entity: CodeEntity,
+    decisions: Vec<DecisionRecord>,
+}
+
+#[derive(Serialize)]
+struct QueryReport {
+    pattern: String,
+    matches: Vec<QueryMatch>,
+}
+
+pub fn run(ctx: &Context, pattern: String, docs: bool, qualified: bool, scope: Option<String>) -> anyhow::Result<()> {
+    ctx.out.line(format!("🔍 Searching for: {}", pattern).yellow());
+    let project = ctx.project()?;
+    let storage = ctx.storage(&project)?;
+
+    let by_name = if qualified { storage.find_entities_by_suffix(&pattern) } else { storage.find_entities_by_name(&pattern) };
+    let found = by_name.and_then(|mut entities| {
+        if docs {
+            for entity in storage.find_entities_by_doc(&pattern)? {
+                if !entities.iter().any(|e| e.id == entity.id) {
+                    entities.push(entity);
+                }
+            }
+        }
+        if let Some(scope) = &scope {
+            let in_scope = storage.find_entities_in_scope(scope)?;
+            entities.retain(|entity| in_scope.iter().any(|e| e.id == entity.id));
+        }
+        Ok(entities)
+    });
+    let mut entities = match found {
+        Ok(entities) => entities,
+        Err(e) => ctx.out.fail(ExitStatus::Failure, &format!("Error querying database: {}", e)),
+    };
+    // Most central entities first; a stable sort keeps the rest in name order
+    let pagerank = |entity: &CodeEntity| entity.centrality.as_ref().map(|c| c.pagerank).unwrap_or(0.0);
+    entities.sort_by(|a, b| pagerank(b).total_cmp(&pagerank(a)));
+
+    // Surface the architecture decisions that apply to each entity
+    let decisions = storage.load_decisions()?;
+    let matches: Vec<QueryMatch> = entities
+        .into_iter()
+        .map(|entity| {
+            let governing = decisions
+                .iter()
+                .filter(|r| r.status.is_in_force() && r.governs(&entity))
+                .cloned()
+                .collect();
+            QueryMatch { entity, decisions: governing }
+        })
+        .collect();
+
+    let report = QueryReport { pattern, matches };
+    if !ctx.out.is_text() {
+        return ctx.out.emit_list(&report, &report.matches);
+    }
+
+    if report.matches.is_empty() {
+        println!("No entities found matching '{}'", report.pattern);
+        return Ok(());
+    }
+
+    println!("\n📋 Found {} entities:", report.matches.len());
+    for found in &report.matches {
+        let entity = &found.entity;
+        println!("  {} {} in {} at line {}",
+            entity.entity_type.as_str(),
+            entity.qualified_name.green(),
+            entity.file_path.blue(),
+            entity.line_start
+        );
+        if let Some(signature) = &entity.signature {
+            println!("    {}", signature.render(&entity.name).cyan());
+        }
+        if let Some(summary) = entity.metadata.get("doc").and_then(|doc| doc.lines().next()) {
+            println!("    📝 {}", summary.dimmed());
+        }
+
+        for record in &found.decisions {
+            println!("    📐 governed by {} {}", super::adr::label(record).yellow(), record.title);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,187 @@
+use super::Context;
+use crate::output::ExitStatus;
+use clap::Subcommand;
+use colored::*;
+use memory_engine::{Session, SessionEntry, SessionEntryKind};
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Subcommand)]
+pub enum SessionAction {
+    /// Start a new session and print its id
+    Start {
+        title: String,
+        /// Name of the assistant taking part in the session
+        #[arg(long)]
+        assistant: Option<String>,
+    },
+    /// Record a decision, question, answer or note in a session
+    Record {
+        session_id: String,
+        /// Entry kind: decision, question, answer or note
+        #[arg(long, default_value = "note")]
+        kind: String,
+        content: String,
+        /// Entity the entry is about (repeatable)
+        #[arg(long = "entity")]
+        entities: Vec<String>,
+        /// File the entry is about (repeatable)
+        #[arg(long = "file")]
+        files: Vec<String>,
+    },
+    /// Mark a session as finished
+    End { session_id: String },
+    /// List recent sessions
+    List {
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// Show every entry of a session
+    Show { session_id: String },
+    /// Find prior sessions by entity, file or keyword
+    Search {
+        keyword: Option<String>,
+        #[arg(long)]
+        entity: Option<String>,
+        #[arg(long)]
+        file: Option<String>,
+    },
+}
+
+#[derive(Serialize)]
+struct SessionList {
+    sessions: Vec<Session>,
+}
+
+pub fn run(ctx: &Context, action: SessionAction) -> anyhow::Result<()> {
+    let project = ctx.project()?;
+    let storage = ctx.storage(&project)?;
+
+    match action {
+        SessionAction::Start { title, assistant } => {
+            let mut session = Session::new(title);
+            if let Some(assistant) = assistant {
+                session = session.with_assistant(assistant);
+            }
+            storage.save_session(&session)?;
+
+            if !ctx.out.is_text() {
+                return ctx.out.emit(&session);
+            }
+            println!("{}", "🗒️  Session started".green());
+            println!("Session id: {}", session.id);
+        }
+        SessionAction::Record { session_id, kind, content, entities, files } => {
+            let Some(entry_kind) = SessionEntryKind::from_str(&kind) else {
+                ctx.out.fail(
+                    ExitStatus::Failure,
+                    &format!("Unknown entry kind '{}'. Use decision, question, answer or note.", kind),
+                );
+            };
+            let Some(session) = storage.load_session(&session_id)? else {
+                ctx.out.fail(ExitStatus::Failure, &format!("No session found with id '{}'", session_id));
+            };
+
+            let mut entry = SessionEntry::new(session.id.clone(), entry_kind, content);
+            for entity in entities {
+                entry = entry.with_entity(entity);
+            }
+            for file in files {
+                entry = entry.with_file(project.relative_path(&ctx.current_dir, Path::new(&file)));
+            }
+            storage.add_session_entry(&entry)?;
+
+            if !ctx.out.is_text() {
+                return ctx.out.emit(&entry);
+            }
+            println!("{}", format!("✅ Recorded {} in '{}'", entry.kind.as_str(), session.title).green());
+        }
+        SessionAction::End { session_id } => {
+            let Some(mut session) = storage.load_session(&session_id)? else {
+                ctx.out.fail(ExitStatus::Failure, &format!("No session found with id '{}'", session_id));
+            };
+            session.end();
+            storage.save_session(&session)?;
+
+            if !ctx.out.is_text() {
+                return ctx.out.emit(&session);
+            }
+            println!("{}", format!("✅ Session '{}' ended", session.title).green());
+        }
+        SessionAction::List { limit } => {
+            let report = SessionList { sessions: storage.list_sessions(limit)? };
+            if !ctx.out.is_text() {
+                return ctx.out.emit_list(&report, &report.sessions);
+            }
+
+            if report.sessions.is_empty() {
+                println!("No sessions recorded yet");
+            } else {
+                println!("{}", "🗒️  Recent sessions".blue().bold());
+                for session in &report.sessions {
+                    print_session(session, false);
+                }
+            }
+        }
+        SessionAction::Show { session_id } => {
+            let Some(session) = storage.load_session(&session_id)? else {
+                ctx.out.fail(ExitStatus::Failure, &format!("No session found with id '{}'", session_id));
+            };
+
+            if !ctx.out.is_text() {
+                return ctx.out.emit(&session);
+            }
+            print_session(&session, true);
+        }
+        SessionAction::Search { keyword, entity, file } => {
+            let sessions = if let Some(entity) = &entity {
+                storage.find_sessions_by_entity(entity)?
+            } else if let Some(file) = &file {
+                storage.find_sessions_by_file(&project.relative_path(&ctx.current_dir, Path::new(file)))?
+            } else if let Some(keyword) = &keyword {
+                storage.search_sessions(keyword)?
+            } else {
+                ctx.out.fail(ExitStatus::Failure, "Provide a keyword, --entity or --file to search by.");
+            };
+
+            let report = SessionList { sessions };
+            if !ctx.out.is_text() {
+                return ctx.out.emit_list(&report, &report.sessions);
+            }
+
+            if report.sessions.is_empty() {
+                println!("No matching sessions found");
+            } else {
+                println!("\n📋 Found {} sessions:", report.sessions.len());
+                for session in &report.sessions {
+                    print_session(session, true);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn print_session(session: &Session, show_entries: bool) {
+    let status = if session.is_active() { "active".green() } else { "ended".normal() };
+    println!("  {} {} ({}, {}, {} entries)",
+        session.id[..8].yellow(),
+        session.title.bold(),
+        session.started_at.format("%Y-%m-%d %H:%M"),
+        status,
+        session.entries.len()
+    );
+
+    if show_entries {
+        for entry in &session.entries {
+            println!("    [{}] {}", entry.kind.as_str().cyan(), entry.content);
+            if !entry.entities.is_empty() {
+                println!("      entities: {}", entry.entities.join(", ").green());
+            }
+            if !entry.files.is_empty() {
+                println!("      files: {}", entry.files.join(", ").blue());
+            }
+        }
+    }
+}
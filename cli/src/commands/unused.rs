@@ -0,0 +1,64 @@
+use super::Context;
+use crate::output::ExitStatus;
+use colored::*;
+use memory_engine::{find_unused, Confidence, EntryPoints, UnusedEntity};
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Serialize)]
+struct UnusedReport {
+    min_confidence: Confidence,
+    unused: Vec<UnusedEntity>,
+}
+
+pub fn run(ctx: &Context, path: Option<String>, min_confidence: String) -> anyhow::Result<()> {
+    let Some(min_confidence) = Confidence::from_str(&min_confidence) else {
+        ctx.out.fail(ExitStatus::Failure, &format!("Unknown confidence '{}' (use low, medium or high)", min_confidence));
+    };
+
+    let project = ctx.project()?;
+    let storage = ctx.storage(&project)?;
+    let memory = storage.load_memory(&project.root.to_string_lossy())?;
+    let entry_points = EntryPoints::new(&project.config.unused)?;
+
+    let scope = path.map(|path| project.relative_path(&ctx.current_dir, Path::new(&path))).filter(|scope| !scope.is_empty());
+    let unused: Vec<UnusedEntity> = find_unused(&memory, &entry_points)
+        .into_iter()
+        .filter(|u| u.confidence >= min_confidence)
+        .filter(|u| match &scope {
+            Some(scope) => u.entity.file_path == *scope || u.entity.file_path.starts_with(&format!("{}/", scope)),
+            None => true,
+        })
+        .collect();
+
+    let report = UnusedReport { min_confidence, unused };
+    if !ctx.out.is_text() {
+        return ctx.out.emit_list(&report, &report.unused);
+    }
+
+    if report.unused.is_empty() {
+        println!("{}", "✅ No unused code found".green());
+        return Ok(());
+    }
+
+    println!("{}", format!("🧹 {} possibly unused entities", report.unused.len()).blue().bold());
+    for found in &report.unused {
+        let confidence = match found.confidence {
+            Confidence::High => found.confidence.as_str().red(),
+            Confidence::Medium => found.confidence.as_str().yellow(),
+            Confidence::Low => found.confidence.as_str().dimmed(),
+        };
+        let entity = &found.entity;
+        println!(
+            "  [{}] {} {} in {} at line {}",
+            confidence,
+            entity.entity_type.as_str(),
+            entity.qualified_name.green(),
+            entity.file_path.blue(),
+            entity.line_start
+        );
+        println!("    {}", found.reason.dimmed());
+    }
+    println!("\nEntry points and test files are configured under [unused] in .aimemoryengine/config.toml");
+    Ok(())
+}
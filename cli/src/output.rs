@@ -0,0 +1,124 @@
+use clap::ValueEnum;
+use colored::*;
+use serde::Serialize;
+use std::fmt::Display;
+
+/// How command results are written to stdout
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    /// Colored, human-readable text
+    Text,
+    /// A single pretty-printed JSON document
+    Json,
+    /// One compact JSON object per line, one line per record
+    Ndjson,
+}
+
+/// Exit codes scripts can rely on. Clap itself exits with 2 on usage errors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExitStatus {
+    Failure,        // Any error without a more specific code
+    NotInitialized, // No initialized project was found
+    LicenseInvalid, // License missing, invalid or expired
+    ParseError,     // One or more files failed to parse
+    RulesViolated,  // The code breaks a configured architecture rule
+}
+
+impl ExitStatus {
+    pub fn code(&self) -> i32 {
+        match self {
+            ExitStatus::Failure => 1,
+            ExitStatus::NotInitialized => 3,
+            ExitStatus::LicenseInvalid => 4,
+            ExitStatus::ParseError => 5,
+            ExitStatus::RulesViolated => 6,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExitStatus::Failure => "failure",
+            ExitStatus::NotInitialized => "not_initialized",
+            ExitStatus::LicenseInvalid => "license_invalid",
+            ExitStatus::ParseError => "parse_error",
+            ExitStatus::RulesViolated => "rules_violated",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorReport<'a> {
+    error: ErrorBody<'a>,
+}
+
+#[derive(Serialize)]
+struct ErrorBody<'a> {
+    code: &'static str,
+    exit_code: i32,
+    message: &'a str,
+}
+
+/// Writes command results in the format selected on the command line
+#[derive(Debug, Clone, Copy)]
+pub struct Output {
+    format: OutputFormat,
+}
+
+impl Output {
+    pub fn new(format: OutputFormat) -> Self {
+        Self { format }
+    }
+
+    pub fn is_text(&self) -> bool {
+        self.format == OutputFormat::Text
+    }
+
+    /// Print a line of human-readable output; suppressed in machine-readable formats
+    pub fn line(&self, line: impl Display) {
+        if self.is_text() {
+            println!("{}", line);
+        }
+    }
+
+    /// Write a structured result. Does nothing in text mode.
+    pub fn emit<T: Serialize>(&self, value: &T) -> anyhow::Result<()> {
+        match self.format {
+            OutputFormat::Text => {}
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(value)?),
+            OutputFormat::Ndjson => println!("{}", serde_json::to_string(value)?),
+        }
+        Ok(())
+    }
+
+    /// Write a structured result whose records are `items`: the whole value as
+    /// JSON, or one line per item as NDJSON. Does nothing in text mode.
+    pub fn emit_list<T: Serialize, I: Serialize>(&self, value: &T, items: &[I]) -> anyhow::Result<()> {
+        match self.format {
+            OutputFormat::Ndjson => {
+                for item in items {
+                    println!("{}", serde_json::to_string(item)?);
+                }
+                Ok(())
+            }
+            _ => self.emit(value),
+        }
+    }
+
+    /// Report a failure and exit with its stable status code
+    pub fn fail(&self, status: ExitStatus, message: &str) -> ! {
+        if self.is_text() {
+            println!("{}", format!("❌ {}", message).red());
+        } else {
+            let report = ErrorReport {
+                error: ErrorBody {
+                    code: status.as_str(),
+                    exit_code: status.code(),
+                    message,
+                },
+            };
+            // Serializing a struct of strings cannot fail
+            let _ = self.emit(&report);
+        }
+        std::process::exit(status.code())
+    }
+}
@@ -0,0 +1,185 @@
+use crate::{EntityType, ProjectMemory, RelationType};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+const DAMPING: f64 = 0.85;
+const MAX_ITERATIONS: usize = 100;
+const TOLERANCE: f64 = 1e-9;
+/// Betweenness is estimated from this many source entities on larger graphs
+const MAX_BETWEENNESS_SOURCES: usize = 500;
+
+/// How central an entity is in the call, use and reference graph
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Centrality {
+    /// Share of a random walk along dependencies spent on the entity; sums to 1 over the project
+    pub pagerank: f64,
+    /// Distinct entities that call, use, reference or extend it
+    pub in_degree: u32,
+    /// Share of shortest dependency paths passing through it, from 0 to 1
+    pub betweenness: f64,
+}
+
+/// Compute centrality over resolved dependency edges and store it on every
+/// entity except imports and exports
+pub fn record_centrality(memory: &mut ProjectMemory) {
+    let scores = compute_centrality(memory);
+    for entity in memory.entities.values_mut() {
+        entity.centrality = scores.get(&entity.id).cloned();
+    }
+}
+
+pub fn compute_centrality(memory: &ProjectMemory) -> HashMap<String, Centrality> {
+    let mut ids: Vec<&str> = memory
+        .entities
+        .values()
+        .filter(|e| !matches!(e.entity_type, EntityType::Import | EntityType::Export))
+        .map(|e| e.id.as_str())
+        .collect();
+    // A fixed order keeps sampled betweenness stable between runs
+    ids.sort_unstable();
+    let index: HashMap<&str, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+
+    let mut edges: HashSet<(usize, usize)> = HashSet::new();
+    for rel in &memory.relationships {
+        let depends = matches!(
+            rel.relationship_type,
+            RelationType::Calls | RelationType::Uses | RelationType::References | RelationType::Extends | RelationType::Implements | RelationType::Imports
+        );
+        if !depends || !rel.is_resolved() {
+            continue;
+        }
+        if let (Some(&from), Some(&to)) = (index.get(rel.from_entity.as_str()), index.get(rel.to_entity.as_str())) {
+            if from != to {
+                edges.insert((from, to));
+            }
+        }
+    }
+    let mut outgoing: Vec<Vec<usize>> = vec![Vec::new(); ids.len()];
+    let mut in_degree = vec![0u32; ids.len()];
+    for &(from, to) in &edges {
+        outgoing[from].push(to);
+        in_degree[to] += 1;
+    }
+    outgoing.iter_mut().for_each(|targets| targets.sort_unstable());
+
+    let pagerank = pagerank(&outgoing);
+    let betweenness = betweenness(&outgoing);
+    ids.iter()
+        .enumerate()
+        .map(|(i, id)| {
+            let scores = Centrality { pagerank: pagerank[i], in_degree: in_degree[i], betweenness: betweenness[i] };
+            (id.to_string(), scores)
+        })
+        .collect()
+}
+
+// Power iteration; entities that depend on nothing spread their rank evenly
+fn pagerank(outgoing: &[Vec<usize>]) -> Vec<f64> {
+    let n = outgoing.len();
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut rank = vec![1.0 / n as f64; n];
+    for _ in 0..MAX_ITERATIONS {
+        let dangling: f64 = (0..n).filter(|&v| outgoing[v].is_empty()).map(|v| rank[v]).sum();
+        let mut next = vec![(1.0 - DAMPING) / n as f64 + DAMPING * dangling / n as f64; n];
+        for (v, targets) in outgoing.iter().enumerate() {
+            for &target in targets {
+                next[target] += DAMPING * rank[v] / targets.len() as f64;
+            }
+        }
+        let change: f64 = next.iter().zip(&rank).map(|(a, b)| (a - b).abs()).sum();
+        rank = next;
+        if change < TOLERANCE {
+            break;
+        }
+    }
+    rank
+}
+
+// Brandes' algorithm on the unweighted directed graph, normalized by (n-1)(n-2)
+fn betweenness(outgoing: &[Vec<usize>]) -> Vec<f64> {
+    let n = outgoing.len();
+    let mut centrality = vec![0.0; n];
+    if n < 3 {
+        return centrality;
+    }
+    let step = n.div_ceil(MAX_BETWEENNESS_SOURCES);
+    let sources: Vec<usize> = (0..n).step_by(step).collect();
+
+    let mut sigma = vec![0.0f64; n];
+    let mut distance = vec![-1i64; n];
+    let mut delta = vec![0.0f64; n];
+    let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for &source in &sources {
+        let mut order = Vec::new();
+        sigma.iter_mut().for_each(|s| *s = 0.0);
+        distance.iter_mut().for_each(|d| *d = -1);
+        delta.iter_mut().for_each(|d| *d = 0.0);
+        predecessors.iter_mut().for_each(Vec::clear);
+        sigma[source] = 1.0;
+        distance[source] = 0;
+
+        let mut queue = VecDeque::from([source]);
+        while let Some(v) = queue.pop_front() {
+            order.push(v);
+            for &w in &outgoing[v] {
+                if distance[w] < 0 {
+                    distance[w] = distance[v] + 1;
+                    queue.push_back(w);
+                }
+                if distance[w] == distance[v] + 1 {
+                    sigma[w] += sigma[v];
+                    predecessors[w].push(v);
+                }
+            }
+        }
+        while let Some(w) = order.pop() {
+            for &v in &predecessors[w] {
+                delta[v] += sigma[v] / sigma[w] * (1.0 + delta[w]);
+            }
+            if w != source {
+                centrality[w] += delta[w];
+            }
+        }
+    }
+
+    let scale = (n as f64 / sources.len() as f64) / ((n - 1) * (n - 2)) as f64;
+    centrality.iter_mut().for_each(|c| *c *= scale);
+    centrality
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CodeEntity, Relationship};
+
+    #[test]
+    fn test_centrality_of_a_call_chain() {
+        // handler -> service -> repo, cli -> service, and an unused helper
+        let mut memory = ProjectMemory::new("/test".to_string());
+        let mut ids = HashMap::new();
+        for name in ["handler", "cli", "service", "repo", "helper"] {
+            let entity = CodeEntity::new(name.to_string(), EntityType::Function, "src/app.rs".to_string(), 1, 1, 0, 0);
+            ids.insert(name, entity.id.clone());
+            memory.add_entity(entity);
+        }
+        for (from, to) in [("handler", "service"), ("cli", "service"), ("service", "repo"), ("service", "repo")] {
+            memory.add_relationship(Relationship::new(ids[from].clone(), ids[to].clone(), RelationType::Calls));
+        }
+        memory.add_relationship(Relationship::to_symbol(ids["helper"].clone(), "missing", RelationType::Calls));
+
+        record_centrality(&mut memory);
+        let scores = |name: &str| memory.entities[&ids[name]].centrality.clone().unwrap();
+        assert_eq!(scores("service").in_degree, 2);
+        assert_eq!(scores("repo").in_degree, 1);
+        assert_eq!(scores("helper").in_degree, 0);
+        assert!(scores("repo").pagerank > scores("service").pagerank);
+        assert!(scores("service").pagerank > scores("handler").pagerank);
+        let total: f64 = ["handler", "cli", "service", "repo", "helper"].iter().map(|name| scores(name).pagerank).sum();
+        assert!((total - 1.0).abs() < 1e-6);
+        // Both paths to `repo` pass through `service`: 2 of the 4 * 3 ordered pairs
+        assert!((scores("service").betweenness - 2.0 / 12.0).abs() < 1e-9);
+        assert_eq!(scores("handler").betweenness, 0.0);
+    }
+}
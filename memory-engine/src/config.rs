@@ -0,0 +1,335 @@
+use anyhow::{Result, anyhow};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory holding the project's memory database and configuration
+pub const MEMORY_DIR: &str = ".aimemoryengine";
+pub const CONFIG_FILE: &str = "config.toml";
+
+/// Default configuration written by `aimemoryengine init`
+pub const DEFAULT_CONFIG_TOML: &str = r##"# AI Memory Engine project configuration
+
+[database]
+# Location of the memory database, relative to the project root
+path = ".aimemoryengine/memory.db"
+
+[files]
+# Glob patterns (relative to the project root) of files to analyze
+include = ["**/*"]
+# Glob patterns to skip, checked after `include`
+exclude = ["**/target/**", "**/node_modules/**", "**/dist/**", "**/build/**", "**/vendor/**"]
+# Skip everything matched by .gitignore, .ignore and the global git excludes
+respect_gitignore = true
+# Files larger than this many bytes are not parsed
+max_file_size = 1048576
+
+[languages]
+# Map extra file extensions to a supported language, or to one defined in
+# .aimemoryengine/languages/<name>.toml
+# mjs = "javascript"
+# cts = "typescript"
+# pyi = "python"
+
+[cpp]
+# Directories (relative to the project root) searched for C/C++ #include files,
+# after the including file's own directory for quoted includes
+include_dirs = []
+
+[unused]
+# Entities reached from outside the code graph, never reported by `aimemoryengine unused`.
+# A pattern matches a name or qualified name (`main`, `handle_*`), `#[name]` an
+# attribute (`#[test]`, `#[tokio::main]`, `#[HttpGet]`) and `@name` a decorator or
+# annotation (`@app.route`, `@*Mapping`)
+entry_points = ["main", "__*__", "#[test]", "#[bench]", "#[*::main]", "#[*::test]", "@Test", "@*Mapping", "@app.*", "@router.*", "@pytest.*"]
+# Files holding tests; nothing in them is reported
+test_files = ["**/tests/**", "**/test/**", "**/*_test.*", "**/test_*.py", "**/*.test.*", "**/*.spec.*"]
+
+# Architecture rules checked by `aimemoryengine check` against imports, calls and references.
+# `from`/`except` select the files a rule applies to; `deny_files` lists files they must
+# not depend on and `deny_imports` modules or symbols they must not import or use.
+# [[rules]]
+# name = "domain does not depend on infrastructure"
+# from = ["src/domain/**"]
+# deny_files = ["src/infra/**"]
+#
+# [[rules]]
+# name = "only storage talks to SQLite"
+# except = ["**/storage.rs"]
+# deny_imports = ["rusqlite"]
+"##;
+
+/// Project-level settings loaded from `.aimemoryengine/config.toml`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectConfig {
+    pub database: DatabaseConfig,
+    pub files: FilesConfig,
+    pub languages: HashMap<String, String>, // extension -> language name
+    pub cpp: CppConfig,
+    pub unused: UnusedConfig,
+    pub rules: Vec<ArchitectureRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DatabaseConfig {
+    pub path: String,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            path: format!("{}/memory.db", MEMORY_DIR),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FilesConfig {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub respect_gitignore: bool,
+    pub max_file_size: u64,
+}
+
+impl Default for FilesConfig {
+    fn default() -> Self {
+        Self {
+            include: vec!["**/*".to_string()],
+            exclude: ["**/target/**", "**/node_modules/**", "**/dist/**", "**/build/**", "**/vendor/**"]
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+            respect_gitignore: true,
+            max_file_size: 1024 * 1024,
+        }
+    }
+}
+
+/// Settings for the C and C++ parser
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CppConfig {
+    pub include_dirs: Vec<String>,
+}
+
+/// Settings for unused-code detection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UnusedConfig {
+    pub entry_points: Vec<String>,
+    pub test_files: Vec<String>,
+}
+
+impl Default for UnusedConfig {
+    fn default() -> Self {
+        let strings = |patterns: &[&str]| patterns.iter().map(|p| p.to_string()).collect();
+        Self {
+            entry_points: strings(&[
+                "main", "__*__", "#[test]", "#[bench]", "#[*::main]", "#[*::test]", "@Test", "@*Mapping", "@app.*", "@router.*",
+                "@pytest.*",
+            ]),
+            test_files: strings(&["**/tests/**", "**/test/**", "**/*_test.*", "**/test_*.py", "**/*.test.*", "**/*.spec.*"]),
+        }
+    }
+}
+
+/// A dependency boundary between parts of the project
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArchitectureRule {
+    pub name: String,
+    /// Files the rule applies to; every file when empty
+    pub from: Vec<String>,
+    /// Files exempt from the rule
+    pub except: Vec<String>,
+    /// Files the selected files must not import, call or reference
+    pub deny_files: Vec<String>,
+    /// Modules or symbols they must not import or use; `rusqlite` also covers `rusqlite::Connection`
+    pub deny_imports: Vec<String>,
+}
+
+impl ProjectConfig {
+    /// Path of the config file for a project root
+    pub fn path_for(project_root: &Path) -> PathBuf {
+        project_root.join(MEMORY_DIR).join(CONFIG_FILE)
+    }
+
+    /// Load the project config, falling back to defaults when no file exists
+    pub fn load(project_root: &Path) -> Result<Self> {
+        let config_path = Self::path_for(project_root);
+        if !config_path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(&config_path)?;
+        Self::from_toml_str(&content)
+            .map_err(|e| anyhow!("Invalid config {}: {}", config_path.display(), e))
+    }
+
+    pub fn from_toml_str(content: &str) -> Result<Self> {
+        let config: ProjectConfig = toml::from_str(content)?;
+        // Validate glob patterns up front so errors point at the config file
+        config.file_filter()?;
+        crate::EntryPoints::new(&config.unused)?;
+        crate::RuleSet::new(&config.rules)?;
+        Ok(config)
+    }
+
+    /// Write the commented default config if the project has none yet.
+    /// Returns true when a new file was written.
+    pub fn write_default(project_root: &Path) -> Result<bool> {
+        let config_path = Self::path_for(project_root);
+        if config_path.exists() {
+            return Ok(false);
+        }
+
+        if let Some(parent) = config_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&config_path, DEFAULT_CONFIG_TOML)?;
+        Ok(true)
+    }
+
+    /// Absolute path of the memory database
+    pub fn database_path(&self, project_root: &Path) -> PathBuf {
+        let path = Path::new(&self.database.path);
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            project_root.join(path)
+        }
+    }
+
+    pub fn file_filter(&self) -> Result<FileFilter> {
+        Ok(FileFilter {
+            include: build_globset(&self.files.include)?,
+            exclude: build_globset(&self.files.exclude)?,
+            respect_gitignore: self.files.respect_gitignore,
+            max_file_size: self.files.max_file_size,
+        })
+    }
+}
+
+/// Decides which files under a project root get analyzed
+#[derive(Debug, Clone)]
+pub struct FileFilter {
+    include: GlobSet,
+    exclude: GlobSet,
+    respect_gitignore: bool,
+    max_file_size: u64,
+}
+
+impl FileFilter {
+    /// Check a path relative to the project root against the include/exclude globs
+    pub fn is_included(&self, relative_path: &Path) -> bool {
+        self.include.is_match(relative_path) && !self.exclude.is_match(relative_path)
+    }
+
+    pub fn is_within_size_limit(&self, path: &Path) -> bool {
+        fs::metadata(path)
+            .map(|meta| meta.len() <= self.max_file_size)
+            .unwrap_or(false)
+    }
+
+    /// Walk `start` (inside `project_root`) and return every file to analyze
+    pub fn collect_files(&self, project_root: &Path, start: &Path) -> Result<Vec<PathBuf>> {
+        let mut builder = WalkBuilder::new(start);
+        builder
+            .git_ignore(self.respect_gitignore)
+            .git_global(self.respect_gitignore)
+            .git_exclude(self.respect_gitignore)
+            .ignore(self.respect_gitignore)
+            .parents(self.respect_gitignore)
+            .require_git(false)
+            .max_filesize(Some(self.max_file_size));
+
+        let mut files = Vec::new();
+        for entry in builder.build() {
+            let entry = entry?;
+            if !entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+                continue;
+            }
+
+            let path = entry.into_path();
+            let relative = path.strip_prefix(project_root).unwrap_or(&path);
+            if self.is_included(relative) {
+                files.push(path);
+            }
+        }
+
+        files.sort();
+        Ok(files)
+    }
+}
+
+pub(crate) fn build_globset(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = GlobBuilder::new(pattern)
+            .literal_separator(true)
+            .build()
+            .map_err(|e| anyhow!("Invalid glob pattern '{}': {}", pattern, e))?;
+        builder.add(glob);
+    }
+    Ok(builder.build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_default_config_round_trip() -> Result<()> {
+        let config = ProjectConfig::from_toml_str(DEFAULT_CONFIG_TOML)?;
+        assert_eq!(config.database.path, ".aimemoryengine/memory.db");
+        assert!(config.files.respect_gitignore);
+        assert_eq!(config.files.max_file_size, 1024 * 1024);
+        assert!(config.languages.is_empty());
+        assert!(config.cpp.include_dirs.is_empty());
+        assert_eq!(config.unused.entry_points, UnusedConfig::default().entry_points);
+        assert_eq!(config.unused.test_files, UnusedConfig::default().test_files);
+        assert!(config.rules.is_empty());
+
+        let partial = ProjectConfig::from_toml_str("[languages]\nmjs = \"javascript\"\n")?;
+        assert_eq!(partial.languages.get("mjs").map(String::as_str), Some("javascript"));
+        assert_eq!(partial.files.include, vec!["**/*".to_string()]);
+
+        assert!(ProjectConfig::from_toml_str("[files]\ninclude = [\"src/[\"]\n").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_collect_files_honours_globs_and_gitignore() -> Result<()> {
+        let dir = TempDir::new()?;
+        let root = dir.path();
+        fs::create_dir_all(root.join("src/generated"))?;
+        fs::create_dir_all(root.join("node_modules/lib"))?;
+        fs::write(root.join("src/main.rs"), "fn main() {}")?;
+        fs::write(root.join("src/generated/out.rs"), "fn generated() {}")?;
+        fs::write(root.join("src/big.rs"), "x".repeat(200))?;
+        fs::write(root.join("node_modules/lib/index.js"), "function lib() {}")?;
+        fs::write(root.join(".gitignore"), "generated/\n")?;
+
+        let mut config = ProjectConfig::default();
+        config.files.include = vec!["src/**".to_string()];
+        config.files.max_file_size = 100;
+
+        let files = config.file_filter()?.collect_files(root, root)?;
+        let relative: Vec<_> = files.iter().map(|f| f.strip_prefix(root).unwrap().to_path_buf()).collect();
+        assert_eq!(relative, vec![PathBuf::from("src/main.rs")]);
+
+        config.files.respect_gitignore = false;
+        config.files.max_file_size = 1024;
+        let files = config.file_filter()?.collect_files(root, root)?;
+        assert_eq!(files.len(), 3);
+
+        Ok(())
+    }
+}
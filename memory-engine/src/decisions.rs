@@ -0,0 +1,307 @@
+use crate::config::build_globset;
+use crate::CodeEntity;
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+
+/// Lifecycle status of an architecture decision record
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum DecisionStatus {
+    Proposed,
+    Accepted,
+    Deprecated,
+    Superseded,
+    Rejected,
+}
+
+impl DecisionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DecisionStatus::Proposed => "proposed",
+            DecisionStatus::Accepted => "accepted",
+            DecisionStatus::Deprecated => "deprecated",
+            DecisionStatus::Superseded => "superseded",
+            DecisionStatus::Rejected => "rejected",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        // ADRs often write "Superseded by ADR-7", so only the first word counts
+        let first_word = s.split_whitespace().next()?.trim_matches(|c: char| !c.is_alphabetic());
+        match first_word.to_lowercase().as_str() {
+            "proposed" | "draft" => Some(DecisionStatus::Proposed),
+            "accepted" | "approved" => Some(DecisionStatus::Accepted),
+            "deprecated" => Some(DecisionStatus::Deprecated),
+            "superseded" => Some(DecisionStatus::Superseded),
+            "rejected" => Some(DecisionStatus::Rejected),
+            _ => None,
+        }
+    }
+
+    /// Whether the decision still constrains the code it governs
+    pub fn is_in_force(&self) -> bool {
+        matches!(self, DecisionStatus::Proposed | DecisionStatus::Accepted)
+    }
+}
+
+/// An architecture decision record and the code it governs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionRecord {
+    pub id: String,
+    pub number: Option<u32>,
+    pub title: String,
+    pub status: DecisionStatus,
+    pub context: String,
+    pub decision: String,
+    pub consequences: String,
+    pub entities: Vec<String>,    // Entity names governed by this decision
+    pub files: Vec<String>,       // File paths or globs governed by this decision
+    pub source_path: Option<String>, // Markdown file the record was imported from
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl DecisionRecord {
+    pub fn new(title: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            number: None,
+            title,
+            status: DecisionStatus::Proposed,
+            context: String::new(),
+            decision: String::new(),
+            consequences: String::new(),
+            entities: Vec::new(),
+            files: Vec::new(),
+            source_path: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn with_status(mut self, status: DecisionStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn with_entity(mut self, entity_name: String) -> Self {
+        if !self.entities.contains(&entity_name) {
+            self.entities.push(entity_name);
+        }
+        self
+    }
+
+    pub fn with_file(mut self, file_pattern: String) -> Self {
+        if !self.files.contains(&file_pattern) {
+            self.files.push(file_pattern);
+        }
+        self
+    }
+
+    /// Parse a Markdown ADR in the common Nygard layout.
+    ///
+    /// The title comes from the first `#` heading, sections from `##` headings
+    /// (Status, Context, Decision, Consequences). A `## Governs` section lists the
+    /// code the decision applies to, one bullet per item; items containing `/` or
+    /// `*` are file paths or globs, anything else an entity name. Use an explicit
+    /// `file:` or `entity:` prefix to override.
+    pub fn from_markdown(content: &str, source_path: Option<&str>) -> Result<Self> {
+        let mut title = None;
+        let mut sections: Vec<(String, Vec<&str>)> = Vec::new();
+
+        for line in content.lines() {
+            let trimmed = line.trim_end();
+            if let Some(heading) = trimmed.strip_prefix("## ") {
+                sections.push((heading.trim().to_lowercase(), Vec::new()));
+            } else if let Some(heading) = trimmed.strip_prefix("# ") {
+                if title.is_none() {
+                    title = Some(heading.trim().to_string());
+                }
+            } else if let Some((_, body)) = sections.last_mut() {
+                body.push(trimmed);
+            }
+        }
+
+        let raw_title = title.ok_or_else(|| anyhow!("ADR has no '# Title' heading"))?;
+        let title_number_regex = Regex::new(r"^(?i:adr)?[-\s]*0*(\d+)[.:\s-]+\s*(.+)$")?;
+        let (title_number, title) = match title_number_regex.captures(&raw_title) {
+            Some(captures) => (captures[1].parse().ok(), captures[2].to_string()),
+            None => (None, raw_title),
+        };
+
+        let mut record = DecisionRecord::new(title);
+        record.number = title_number.or_else(|| source_path.and_then(number_from_file_name));
+        record.source_path = source_path.map(|p| p.to_string());
+
+        for (heading, body) in sections {
+            let text = body.join("\n").trim().to_string();
+            match heading.as_str() {
+                "status" => {
+                    record.status = DecisionStatus::from_str(&text).unwrap_or(DecisionStatus::Proposed);
+                }
+                "context" => record.context = text,
+                "decision" => record.decision = text,
+                "consequences" => record.consequences = text,
+                "governs" | "affects" | "links" | "scope" => {
+                    for item in body.iter().filter_map(|line| bullet_item(line)) {
+                        if let Some(file) = item.strip_prefix("file:") {
+                            record = record.with_file(file.trim().to_string());
+                        } else if let Some(entity) = item.strip_prefix("entity:") {
+                            record = record.with_entity(entity.trim().to_string());
+                        } else if item.contains('/') || item.contains('*') {
+                            record = record.with_file(item);
+                        } else {
+                            record = record.with_entity(item);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(record)
+    }
+
+    /// Check whether this decision applies to a code entity
+    pub fn governs(&self, entity: &CodeEntity) -> bool {
+        self.entities.contains(&entity.name) || self.governs_file(&entity.file_path)
+    }
+
+    /// Check whether this decision applies to a file path
+    pub fn governs_file(&self, file_path: &str) -> bool {
+        let file_path = file_path.trim_start_matches("./");
+        self.files.iter().any(|pattern| {
+            let pattern = pattern.trim_start_matches("./");
+            if pattern.contains('*') || pattern.contains('?') {
+                build_globset(&[pattern.to_string()]).map(|set| set.is_match(file_path)).unwrap_or(false)
+            } else {
+                file_path == pattern || file_path.starts_with(&format!("{}/", pattern.trim_end_matches('/')))
+            }
+        })
+    }
+}
+
+/// Import every `*.md` ADR from a directory, sorted by file name
+pub fn import_adr_directory(dir: &Path) -> Result<Vec<DecisionRecord>> {
+    let mut paths: Vec<_> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("md"))
+        .collect();
+    paths.sort();
+
+    let mut records = Vec::new();
+    for path in paths {
+        // Directory indexes and templates are not decisions
+        let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("").to_lowercase();
+        if file_name == "readme.md" || file_name.starts_with("template") {
+            continue;
+        }
+
+        let content = fs::read_to_string(&path)?;
+        let source = path.to_string_lossy().to_string();
+        let record = DecisionRecord::from_markdown(&content, Some(&source))
+            .map_err(|e| anyhow!("{}: {}", source, e))?;
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+fn number_from_file_name(path: &str) -> Option<u32> {
+    let file_name = Path::new(path).file_name()?.to_str()?;
+    let digits: String = file_name
+        .trim_start_matches(|c: char| !c.is_ascii_digit())
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().ok()
+}
+
+fn bullet_item(line: &str) -> Option<String> {
+    let item = line.trim().strip_prefix("- ").or_else(|| line.trim().strip_prefix("* "))?;
+    let item = item.replace('`', "");
+    let item = item.trim();
+    if item.is_empty() {
+        None
+    } else {
+        Some(item.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EntityType;
+
+    const ADR: &str = r#"# 3. Use SQLite for persistence
+
+## Status
+
+Accepted
+
+## Context
+
+Project memory must survive restarts and work offline.
+
+## Decision
+
+Store everything in a single SQLite database per project.
+
+## Consequences
+
+No server to run; concurrent writers must be serialised.
+
+## Governs
+
+- `MemoryStorage`
+- memory-engine/src/storage.rs
+- file:cli/src/**/*.rs
+"#;
+
+    #[test]
+    fn test_parse_markdown_adr() -> Result<()> {
+        let record = DecisionRecord::from_markdown(ADR, Some("docs/adr/0003-use-sqlite.md"))?;
+
+        assert_eq!(record.number, Some(3));
+        assert_eq!(record.title, "Use SQLite for persistence");
+        assert_eq!(record.status, DecisionStatus::Accepted);
+        assert!(record.context.starts_with("Project memory"));
+        assert!(record.decision.contains("single SQLite database"));
+        assert!(record.consequences.contains("serialised"));
+        assert_eq!(record.entities, vec!["MemoryStorage".to_string()]);
+        assert_eq!(record.files.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decision_governs_entities() -> Result<()> {
+        let record = DecisionRecord::from_markdown(ADR, None)?;
+
+        let by_name = CodeEntity::new("MemoryStorage".to_string(), EntityType::Class, "elsewhere.rs".to_string(), 1, 1, 0, 0);
+        let by_file = CodeEntity::new("save_memory".to_string(), EntityType::Function, "memory-engine/src/storage.rs".to_string(), 1, 1, 0, 0);
+        let by_glob = CodeEntity::new("main".to_string(), EntityType::Function, "cli/src/main.rs".to_string(), 1, 1, 0, 0);
+        let unrelated = CodeEntity::new("parse_file".to_string(), EntityType::Function, "memory-engine/src/parser.rs".to_string(), 1, 1, 0, 0);
+
+        assert!(record.governs(&by_name));
+        assert!(record.governs(&by_file));
+        assert!(record.governs(&by_glob));
+        assert!(!record.governs(&unrelated));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_status_parsing() {
+        assert_eq!(DecisionStatus::from_str("Superseded by ADR-7"), Some(DecisionStatus::Superseded));
+        assert_eq!(DecisionStatus::from_str("**Accepted**"), Some(DecisionStatus::Accepted));
+        assert_eq!(DecisionStatus::from_str(""), None);
+        assert!(!DecisionStatus::Rejected.is_in_force());
+    }
+}
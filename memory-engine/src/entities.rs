@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use crate::{Centrality, EntityMetrics, Signature};
 
 /// Types of code entities we track
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -32,6 +33,7 @@ impl EntityType {
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s {
             "function" => Some(EntityType::Function),
@@ -53,6 +55,10 @@ impl EntityType {
 pub struct CodeEntity {
     pub id: String,
     pub name: String,
+    /// `name` prefixed with the module, package or file and enclosing scopes,
+    /// e.g. `crate::storage::MemoryStorage::new` or `src/utils.ts#formatDate`
+    #[serde(default)]
+    pub qualified_name: String,
     pub entity_type: EntityType,
     pub file_path: String,
     pub line_start: u32,
@@ -60,6 +66,15 @@ pub struct CodeEntity {
     pub column_start: u32,
     pub column_end: u32,
     pub metadata: HashMap<String, String>,
+    /// Parameters, return type and fields as declared, when the parser knows them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<Signature>,
+    /// Size and complexity, for functions and types
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<EntityMetrics>,
+    /// PageRank, in-degree and betweenness in the dependency graph, after analysis
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub centrality: Option<Centrality>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -77,6 +92,7 @@ impl CodeEntity {
         let now = Utc::now();
         Self {
             id: Uuid::new_v4().to_string(),
+            qualified_name: name.clone(),
             name,
             entity_type,
             file_path,
@@ -85,6 +101,9 @@ impl CodeEntity {
             column_start,
             column_end,
             metadata: HashMap::new(),
+            signature: None,
+            metrics: None,
+            centrality: None,
             created_at: now,
             updated_at: now,
         }
@@ -103,9 +122,15 @@ impl CodeEntity {
         self.updated_at = Utc::now();
     }
 
-    pub fn get_signature(&self) -> String {
+    /// `file:type:name:line`, identifying where the entity is declared
+    pub fn location_key(&self) -> String {
         format!("{}:{}:{}:{}", self.file_path, self.entity_type.as_str(), self.name, self.line_start)
     }
+
+    #[deprecated(note = "this is a location key, use `location_key`; the declaration is in `signature`")]
+    pub fn get_signature(&self) -> String {
+        self.location_key()
+    }
 }
 
 #[cfg(test)]
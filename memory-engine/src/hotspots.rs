@@ -0,0 +1,128 @@
+use crate::{Centrality, CodeEntity};
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::process::Command;
+
+/// How often a file changed in the git history
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct FileChurn {
+    pub commits: u32,
+    /// Lines added plus lines deleted
+    pub lines_changed: u32,
+}
+
+/// An entity that is both central and in a frequently changed file
+#[derive(Debug, Clone, Serialize)]
+pub struct Hotspot {
+    #[serde(flatten)]
+    pub entity: CodeEntity,
+    pub churn: FileChurn,
+    /// Mean of PageRank, in-degree and betweenness, each relative to the project maximum
+    pub centrality_score: f64,
+    /// Centrality times relative churn, from 0 to 1
+    pub score: f64,
+}
+
+/// Commits and changed lines per file under `project_root`, with paths relative to it.
+/// `since` takes anything `git log --since` accepts, such as `6 months ago`.
+pub fn git_churn(project_root: &Path, since: Option<&str>) -> Result<HashMap<String, FileChurn>> {
+    let mut command = Command::new("git");
+    command.arg("-C").arg(project_root).args(["log", "--no-merges", "--no-renames", "--numstat", "--relative", "--format=@%H"]);
+    if let Some(since) = since {
+        command.arg(format!("--since={}", since));
+    }
+    let output = command.output().map_err(|e| anyhow!("Could not run git: {}", e))?;
+    if !output.status.success() {
+        return Err(anyhow!("git log failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(parse_numstat(&String::from_utf8_lossy(&output.stdout)))
+}
+
+// `@<hash>` starts a commit, followed by `added<TAB>deleted<TAB>path` lines
+// (`-` for binary files)
+fn parse_numstat(log: &str) -> HashMap<String, FileChurn> {
+    let mut churn: HashMap<String, FileChurn> = HashMap::new();
+    let mut in_commit: HashSet<String> = HashSet::new();
+    for line in log.lines() {
+        if line.starts_with('@') {
+            in_commit.clear();
+            continue;
+        }
+        let mut fields = line.splitn(3, '\t');
+        let (Some(added), Some(deleted), Some(path)) = (fields.next(), fields.next(), fields.next()) else { continue };
+        let file = churn.entry(path.to_string()).or_default();
+        file.lines_changed += added.parse::<u32>().unwrap_or(0) + deleted.parse::<u32>().unwrap_or(0);
+        if in_commit.insert(path.to_string()) {
+            file.commits += 1;
+        }
+    }
+    churn
+}
+
+/// Rank entities by centrality times the churn of their file, highest first.
+/// Without churn, as outside a git repository, centrality alone decides.
+pub fn rank_hotspots(entities: Vec<CodeEntity>, churn: &HashMap<String, FileChurn>) -> Vec<Hotspot> {
+    let max = |value: fn(&Centrality) -> f64| {
+        entities.iter().filter_map(|e| e.centrality.as_ref()).map(value).fold(0.0, f64::max)
+    };
+    let max_pagerank = max(|c| c.pagerank);
+    let max_in_degree = max(|c| c.in_degree as f64);
+    let max_betweenness = max(|c| c.betweenness);
+    let max_commits = churn.values().map(|c| c.commits).max().unwrap_or(0);
+    let relative = |value: f64, max: f64| if max > 0.0 { value / max } else { 0.0 };
+
+    let mut hotspots: Vec<Hotspot> = entities
+        .into_iter()
+        .filter_map(|entity| {
+            let centrality = entity.centrality.as_ref()?;
+            let centrality_score = (relative(centrality.pagerank, max_pagerank)
+                + relative(centrality.in_degree as f64, max_in_degree)
+                + relative(centrality.betweenness, max_betweenness))
+                / 3.0;
+            let file_churn = churn.get(&entity.file_path).cloned().unwrap_or_default();
+            // Logarithmic, so a few very busy files do not flatten everything else
+            let churn_score = if max_commits == 0 {
+                1.0
+            } else {
+                (1.0 + file_churn.commits as f64).ln() / (1.0 + max_commits as f64).ln()
+            };
+            let score = centrality_score * churn_score;
+            (score > 0.0).then_some(Hotspot { entity, churn: file_churn, centrality_score, score })
+        })
+        .collect();
+    hotspots.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.entity.qualified_name.cmp(&b.entity.qualified_name)));
+    hotspots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EntityType;
+
+    #[test]
+    fn test_hotspots_combine_centrality_and_churn() {
+        let log = "@a1\n3\t1\tsrc/core.rs\n10\t0\tsrc/util.rs\n\n@b2\n2\t2\tsrc/core.rs\n-\t-\tassets/logo.png\n";
+        let churn = parse_numstat(log);
+        assert_eq!(churn["src/core.rs"], FileChurn { commits: 2, lines_changed: 8 });
+        assert_eq!(churn["src/util.rs"], FileChurn { commits: 1, lines_changed: 10 });
+        assert_eq!(churn["assets/logo.png"].commits, 1);
+
+        let entity = |name: &str, file: &str, pagerank: f64, in_degree: u32| {
+            let mut entity = CodeEntity::new(name.to_string(), EntityType::Function, file.to_string(), 1, 1, 0, 0);
+            entity.centrality = Some(Centrality { pagerank, in_degree, betweenness: 0.0 });
+            entity
+        };
+        let entities = vec![
+            entity("format", "src/util.rs", 0.4, 6),
+            entity("run", "src/core.rs", 0.3, 4),
+            entity("stable", "src/stable.rs", 0.5, 8),
+        ];
+
+        let ranked: Vec<String> = rank_hotspots(entities.clone(), &churn).iter().map(|h| h.entity.name.clone()).collect();
+        assert_eq!(ranked, vec!["run", "format"]);
+        let by_centrality: Vec<String> = rank_hotspots(entities, &HashMap::new()).iter().map(|h| h.entity.name.clone()).collect();
+        assert_eq!(by_centrality, vec!["stable", "format", "run"]);
+    }
+}
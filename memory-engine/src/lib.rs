@@ -1,15 +1,39 @@
 pub mod entities;
+pub mod signatures;
+pub mod metrics;
 pub mod relationships;
 pub mod memory;
 pub mod parser;
 pub mod storage;
 pub mod watcher;
+// Keygen payload types mirror the API whether or not every field is read
+#[allow(dead_code, clippy::single_match, clippy::collapsible_if)]
 pub mod licensing;
+pub mod sessions;
+pub mod decisions;
+pub mod config;
+pub mod project;
+pub mod unused;
+pub mod rules;
+pub mod centrality;
+pub mod hotspots;
+pub mod overview;
 
 pub use entities::*;
+pub use signatures::*;
+pub use metrics::*;
 pub use relationships::*;
 pub use memory::*;
 pub use parser::*;
 pub use storage::*;
 pub use watcher::*;
 pub use licensing::*;
+pub use sessions::*;
+pub use decisions::*;
+pub use config::*;
+pub use project::*;
+pub use unused::*;
+pub use rules::*;
+pub use centrality::*;
+pub use hotspots::*;
+pub use overview::*;
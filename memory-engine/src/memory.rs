@@ -1,5 +1,6 @@
-use crate::{CodeEntity, Relationship, RelationshipQuery};
-use std::collections::HashMap;
+use crate::{CodeEntity, EntityType, RelationType, Relationship, RelationshipQuery};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
 
 /// Core project memory that holds the knowledge graph
 #[derive(Debug, Clone)]
@@ -33,6 +34,167 @@ impl ProjectMemory {
         });
     }
 
+    /// Remove every entity found in a file, along with their relationships
+    pub fn remove_file(&mut self, file_path: &str) {
+        let removed: HashSet<String> = self.entities
+            .values()
+            .filter(|entity| entity.file_path == file_path)
+            .map(|entity| entity.id.clone())
+            .collect();
+
+        self.entities.retain(|id, _| !removed.contains(id));
+        self.relationships.retain_mut(|rel| {
+            // Edges from other files into this one are re-linked once it is parsed
+            // again; unresolving also turns `member_of` edges back to the member
+            if removed.contains(&rel.from_entity) || removed.contains(&rel.to_entity) {
+                rel.unresolve();
+            }
+            !removed.contains(&rel.from_entity) && !removed.contains(&rel.to_entity)
+        });
+        self.file_hashes.remove(file_path);
+    }
+
+    /// Link relationships created with `Relationship::to_symbol` to the entities
+    /// they name. A symbol declared in the same file wins; otherwise the name must
+    /// match exactly one candidate. Method calls on receivers of unknown type stay
+    /// unresolved. Returns the number of relationships resolved.
+    pub fn resolve_references(&mut self) -> usize {
+        let mut by_name: HashMap<&str, Vec<&CodeEntity>> = HashMap::new();
+        for entity in self.entities.values() {
+            if !matches!(entity.entity_type, EntityType::Import | EntityType::Export) {
+                by_name.entry(entity.name.as_str()).or_default().push(entity);
+            }
+        }
+
+        let mut resolutions = Vec::new();
+        for (index, rel) in self.relationships.iter().enumerate() {
+            if rel.is_resolved() {
+                continue;
+            }
+            // `x.len()` names a method of whatever `x` is; any `len` may be the wrong one
+            if rel.metadata.contains_key("unknown_receiver") {
+                continue;
+            }
+            let Some(symbol) = rel.target_name() else { continue };
+            let Some(from) = self.entities.get(&rel.from_entity) else { continue };
+
+            // Include paths are looked up by file name, other symbols by their last segment
+            let search_paths = rel.metadata.get("search_paths");
+            let base_name = match search_paths {
+                Some(_) => symbol.rsplit('/').next().unwrap_or(symbol),
+                None => symbol_base_name(symbol),
+            };
+            let candidates: Vec<&&CodeEntity> = by_name
+                .get(base_name)
+                .map(|entities| {
+                    entities
+                        .iter()
+                        .filter(|e| e.id != from.id && accepts_target(rel, e))
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let target = if let Some(search_paths) = search_paths {
+                // The first search directory holding the file wins, like a compiler's include path
+                search_paths.split(',').find_map(|dir| {
+                    let path = join_relative(dir, symbol);
+                    candidates.iter().find(|e| e.file_path == path).map(|e| e.id.clone())
+                })
+            } else {
+                let same_file: Vec<_> = candidates.iter().copied().filter(|e| e.file_path == from.file_path).collect();
+                // `Foo::bar` prefers the `bar` declared inside `Foo`
+                let in_qualifier: Vec<_> = symbol_qualifier(symbol)
+                    .map(|qualifier| {
+                        candidates
+                            .iter()
+                            .copied()
+                            .filter(|e| e.metadata.get("enclosing").map(|enclosing| enclosing == qualifier).unwrap_or(false))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                match (same_file.as_slice(), in_qualifier.as_slice(), candidates.as_slice()) {
+                    ([only], _, _) | ([], [only], _) | ([], [], [only]) => Some(only.id.clone()),
+                    _ => None,
+                }
+            };
+            let Some(target) = target else { continue };
+            resolutions.push((index, target));
+        }
+
+        let resolved = resolutions.len();
+        for (index, target) in resolutions {
+            let relationship = &mut self.relationships[index];
+            // A Swift class's first inherited type is a superclass or a protocol
+            if relationship.metadata.get("inherits").map(String::as_str) == Some("either") {
+                relationship.relationship_type = match self.entities.get(&target).map(|e| &e.entity_type) {
+                    Some(EntityType::Interface) => RelationType::Implements,
+                    _ => RelationType::Extends,
+                };
+            }
+            relationship.resolve_to(target);
+        }
+        resolved
+    }
+
+    /// Merge the parts of partial types (C# `partial class`) declared across
+    /// files into one logical type. The part declared first becomes the primary:
+    /// it receives copies of the other parts' member and base-type edges, and
+    /// every part records the files the type spans. Returns the number of types merged.
+    pub fn merge_partial_types(&mut self) -> usize {
+        // Derived edges are rebuilt from scratch so re-analyzed parts don't leave stale copies
+        self.relationships.retain(|rel| rel.metadata.get("via").map(String::as_str) != Some("partial"));
+        for entity in self.entities.values_mut() {
+            entity.metadata.remove("merged_into");
+            entity.metadata.remove("partial_files");
+        }
+
+        let mut parts: HashMap<String, Vec<&CodeEntity>> = HashMap::new();
+        for entity in self.entities.values() {
+            if let Some(key) = crate::parser::partial_type_key(entity) {
+                parts.entry(format!("{}:{}", entity.entity_type.as_str(), key)).or_default().push(entity);
+            }
+        }
+
+        let mut merges = Vec::new();
+        for mut group in parts.into_values().filter(|group| group.len() > 1) {
+            group.sort_by(|a, b| (&a.file_path, a.line_start).cmp(&(&b.file_path, b.line_start)));
+            let mut files: Vec<&str> = group.iter().map(|e| e.file_path.as_str()).collect();
+            files.sort();
+            files.dedup();
+            let ids: Vec<String> = group.iter().map(|e| e.id.clone()).collect();
+            merges.push((ids, files.join(",")));
+        }
+
+        for (ids, files) in &merges {
+            let primary = &ids[0];
+            let derived: Vec<Relationship> = self
+                .relationships
+                .iter()
+                .filter(|rel| ids[1..].contains(&rel.from_entity))
+                .filter(|rel| matches!(rel.relationship_type, RelationType::Contains | RelationType::Extends | RelationType::Implements))
+                .map(|rel| {
+                    let mut copy = Relationship::new(primary.clone(), rel.to_entity.clone(), rel.relationship_type.clone());
+                    copy.metadata = rel.metadata.clone();
+                    copy.with_metadata("via".to_string(), "partial".to_string())
+                })
+                .collect();
+            for relationship in derived {
+                self.add_relationship(relationship);
+            }
+
+            for (position, id) in ids.iter().enumerate() {
+                if let Some(entity) = self.entities.get_mut(id) {
+                    entity.metadata.insert("partial_files".to_string(), files.clone());
+                    if position > 0 {
+                        entity.metadata.insert("merged_into".to_string(), primary.clone());
+                    }
+                }
+            }
+        }
+
+        merges.len()
+    }
+
     /// Add a relationship between entities
     pub fn add_relationship(&mut self, relationship: Relationship) {
         // Check if relationship already exists
@@ -127,8 +289,65 @@ impl ProjectMemory {
     }
 }
 
+// `com.example.Base`, `Base<T>` and `crate::Base` all name `Base`
+pub(crate) fn symbol_base_name(symbol: &str) -> &str {
+    let symbol = symbol.split('<').next().unwrap_or(symbol).trim();
+    symbol
+        .rsplit(SYMBOL_SEPARATORS)
+        .next()
+        .unwrap_or(symbol)
+}
+
+// `Foo::bar` -> `Foo`, `pkg.Foo.bar` -> `Foo`
+fn symbol_qualifier(symbol: &str) -> Option<&str> {
+    let symbol = symbol.split('<').next().unwrap_or(symbol).trim();
+    symbol
+        .rsplit(SYMBOL_SEPARATORS)
+        .filter(|segment| !segment.is_empty())
+        .nth(1)
+}
+
+const SYMBOL_SEPARATORS: [char; 4] = ['.', ':', '\\', '/'];
+
+// Join a root-relative directory and a relative path, folding `.` and `..`
+pub(crate) fn join_relative(dir: &str, path: &str) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in dir.split('/').chain(path.split('/')) {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+// Definitions link only to declarations (see `Relationship::to_symbol` users in
+// the C/C++ parser); inheritance only to type-like entities, mixins to modules and
+// traits; members to their owning types; calls to functions and to types constructed by name
+fn accepts_target(relationship: &Relationship, entity: &CodeEntity) -> bool {
+    match relationship.relationship_type {
+        RelationType::Calls => matches!(entity.entity_type, EntityType::Function | EntityType::Class | EntityType::Type),
+        // An extension adds to a type declared elsewhere; it is never the type itself
+        RelationType::Extends | RelationType::Implements => {
+            matches!(entity.entity_type, EntityType::Class | EntityType::Interface | EntityType::Type)
+                && entity.metadata.get("kind").map(String::as_str) != Some("extension")
+        }
+        RelationType::Uses if relationship.metadata.contains_key("mixin") => {
+            matches!(entity.entity_type, EntityType::Module | EntityType::Class | EntityType::Interface)
+        }
+        RelationType::Contains if relationship.metadata.contains_key("member_of") => {
+            matches!(entity.entity_type, EntityType::Class | EntityType::Interface | EntityType::Type)
+        }
+        RelationType::Defines => entity.metadata.get("declaration").map(|d| d == "true").unwrap_or(false),
+        _ => true,
+    }
+}
+
 /// Statistics about the project memory
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct MemoryStats {
     pub entity_count: usize,
     pub relationship_count: usize,
@@ -180,4 +399,42 @@ mod tests {
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].name, "test_function");
     }
+
+    #[test]
+    fn test_remove_file() {
+        let mut memory = ProjectMemory::new("/test".to_string());
+        let kept = CodeEntity::new("kept".to_string(), EntityType::Function, "a.js".to_string(), 1, 1, 0, 0);
+        let removed = CodeEntity::new("removed".to_string(), EntityType::Function, "b.js".to_string(), 1, 1, 0, 0);
+        memory.add_relationship(Relationship::new(kept.id.clone(), removed.id.clone(), crate::RelationType::Calls));
+        memory.add_entity(kept);
+        memory.add_entity(removed);
+        memory.update_file_hash("b.js".to_string(), "hash".to_string());
+
+        memory.remove_file("b.js");
+        assert_eq!(memory.entities.len(), 1);
+        assert!(memory.relationships.is_empty());
+        assert!(memory.file_hashes.is_empty());
+    }
+
+    #[test]
+    fn test_resolve_references() {
+        let mut memory = ProjectMemory::new("/test".to_string());
+        let base = CodeEntity::new("Base".to_string(), EntityType::Class, "Base.java".to_string(), 1, 1, 0, 0);
+        let child = CodeEntity::new("Child".to_string(), EntityType::Class, "Child.java".to_string(), 1, 1, 0, 0);
+        let base_id = base.id.clone();
+        memory.add_relationship(Relationship::to_symbol(child.id.clone(), "com.example.Base", RelationType::Extends));
+        memory.add_relationship(Relationship::to_symbol(child.id.clone(), "Missing", RelationType::Implements));
+        memory.add_entity(base);
+        memory.add_entity(child);
+
+        assert_eq!(memory.resolve_references(), 1);
+        assert_eq!(memory.relationships[0].to_entity, base_id);
+        assert!(!memory.relationships[1].is_resolved());
+
+        // Re-analyzing the target file detaches the edge instead of dropping it
+        memory.remove_file("Base.java");
+        assert_eq!(memory.relationships.len(), 2);
+        assert_eq!(memory.relationships[0].to_entity, "com.example.Base");
+        assert!(!memory.relationships[0].is_resolved());
+    }
 }
@@ -0,0 +1,201 @@
+use crate::{CodeEntity, EntityType};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Size and complexity of a function or type, measured while parsing
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct EntityMetrics {
+    /// Lines holding code; blank and comment-only lines are not counted
+    pub lines_of_code: u32,
+    pub comment_lines: u32,
+    /// Comment lines per line of code or comment, from 0 to 1
+    pub comment_ratio: f64,
+    /// One plus the number of branches: conditions, loops, cases, catches and `&&`/`||`
+    pub cyclomatic_complexity: u32,
+    /// Deepest block inside the body; 0 for straight-line code
+    pub max_nesting: u32,
+    /// Declared parameters, not counting `self`/`this` receivers
+    pub param_count: u32,
+}
+
+impl EntityMetrics {
+    pub fn value(&self, metric: MetricKind) -> f64 {
+        match metric {
+            MetricKind::Complexity => self.cyclomatic_complexity as f64,
+            MetricKind::Loc => self.lines_of_code as f64,
+            MetricKind::Nesting => self.max_nesting as f64,
+            MetricKind::Params => self.param_count as f64,
+            MetricKind::Comments => self.comment_ratio,
+        }
+    }
+}
+
+/// A metric results can be sorted or filtered by
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MetricKind {
+    Complexity,
+    Loc,
+    Nesting,
+    Params,
+    Comments,
+}
+
+impl MetricKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MetricKind::Complexity => "complexity",
+            MetricKind::Loc => "loc",
+            MetricKind::Nesting => "nesting",
+            MetricKind::Params => "params",
+            MetricKind::Comments => "comments",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "complexity" => Some(MetricKind::Complexity),
+            "loc" => Some(MetricKind::Loc),
+            "nesting" => Some(MetricKind::Nesting),
+            "params" => Some(MetricKind::Params),
+            "comments" => Some(MetricKind::Comments),
+            _ => None,
+        }
+    }
+}
+
+/// What metrics are aggregated over
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MetricsGrouping {
+    File,
+    /// The directory holding the file
+    Module,
+}
+
+impl MetricsGrouping {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MetricsGrouping::File => "file",
+            MetricsGrouping::Module => "module",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "file" => Some(MetricsGrouping::File),
+            "module" => Some(MetricsGrouping::Module),
+            _ => None,
+        }
+    }
+
+    fn key(&self, file_path: &str) -> String {
+        match self {
+            MetricsGrouping::File => file_path.to_string(),
+            MetricsGrouping::Module => Path::new(file_path)
+                .parent()
+                .map(|dir| dir.to_string_lossy().to_string())
+                .filter(|dir| !dir.is_empty())
+                .unwrap_or_else(|| ".".to_string()),
+        }
+    }
+}
+
+/// Metrics of the functions in one file or module
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct MetricsSummary {
+    pub path: String,
+    pub functions: u32,
+    pub lines_of_code: u32,
+    pub comment_lines: u32,
+    pub comment_ratio: f64,
+    pub total_complexity: u32,
+    pub average_complexity: f64,
+    pub max_complexity: u32,
+    pub max_nesting: u32,
+    pub max_params: u32,
+}
+
+impl MetricsSummary {
+    pub fn value(&self, metric: MetricKind) -> f64 {
+        match metric {
+            MetricKind::Complexity => self.total_complexity as f64,
+            MetricKind::Loc => self.lines_of_code as f64,
+            MetricKind::Nesting => self.max_nesting as f64,
+            MetricKind::Params => self.max_params as f64,
+            MetricKind::Comments => self.comment_ratio,
+        }
+    }
+}
+
+/// Aggregate the metrics of measured functions per file or per module.
+/// Types are left out so methods are not counted twice.
+pub fn summarize_metrics(entities: &[CodeEntity], grouping: MetricsGrouping) -> Vec<MetricsSummary> {
+    let mut groups: BTreeMap<String, MetricsSummary> = BTreeMap::new();
+    for entity in entities.iter().filter(|e| e.entity_type == EntityType::Function) {
+        let Some(metrics) = &entity.metrics else { continue };
+        let key = grouping.key(&entity.file_path);
+        let summary = groups.entry(key.clone()).or_insert_with(|| MetricsSummary { path: key, ..Default::default() });
+        summary.functions += 1;
+        summary.lines_of_code += metrics.lines_of_code;
+        summary.comment_lines += metrics.comment_lines;
+        summary.total_complexity += metrics.cyclomatic_complexity;
+        summary.max_complexity = summary.max_complexity.max(metrics.cyclomatic_complexity);
+        summary.max_nesting = summary.max_nesting.max(metrics.max_nesting);
+        summary.max_params = summary.max_params.max(metrics.param_count);
+    }
+
+    groups
+        .into_values()
+        .map(|mut summary| {
+            summary.comment_ratio = ratio(summary.comment_lines, summary.lines_of_code + summary.comment_lines);
+            summary.average_complexity = round(summary.total_complexity as f64 / summary.functions as f64);
+            summary
+        })
+        .collect()
+}
+
+/// `part / whole` rounded to two decimals, 0 when there is nothing to divide
+pub(crate) fn ratio(part: u32, whole: u32) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        round(part as f64 / whole as f64)
+    }
+}
+
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summaries_per_file_and_module() {
+        let function = |file: &str, loc: u32, complexity: u32| {
+            let mut entity = CodeEntity::new("f".to_string(), EntityType::Function, file.to_string(), 1, loc, 0, 0);
+            entity.metrics = Some(EntityMetrics {
+                lines_of_code: loc,
+                comment_lines: 1,
+                cyclomatic_complexity: complexity,
+                ..Default::default()
+            });
+            entity
+        };
+        let mut class = CodeEntity::new("C".to_string(), EntityType::Class, "src/a.rs".to_string(), 1, 50, 0, 0);
+        class.metrics = Some(EntityMetrics { lines_of_code: 50, ..Default::default() });
+        let entities = vec![function("src/a.rs", 9, 4), function("src/a.rs", 3, 1), function("src/b.rs", 4, 2), function("main.rs", 1, 1), class];
+
+        let files = summarize_metrics(&entities, MetricsGrouping::File);
+        assert_eq!(files.iter().map(|s| s.path.as_str()).collect::<Vec<_>>(), vec!["main.rs", "src/a.rs", "src/b.rs"]);
+        assert_eq!((files[1].functions, files[1].lines_of_code, files[1].max_complexity), (2, 12, 4));
+        assert_eq!(files[1].average_complexity, 2.5);
+        assert_eq!(files[1].comment_ratio, 0.14);
+
+        let modules = summarize_metrics(&entities, MetricsGrouping::Module);
+        assert_eq!(modules.iter().map(|s| (s.path.as_str(), s.total_complexity)).collect::<Vec<_>>(), vec![(".", 1), ("src", 7)]);
+    }
+}
@@ -0,0 +1,449 @@
+use crate::rules::ImportResolver;
+use crate::unused::is_exported;
+use crate::{CodeEntity, EntityType, ProjectMemory, RelationType};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+const KEY_SYMBOLS: usize = 3;
+const ENTRY_POINTS: usize = 5;
+
+/// A group of files that import and call each other more than the rest of the project
+#[derive(Debug, Clone, Serialize)]
+pub struct Component {
+    pub name: String,
+    /// Directory most of the files live in; empty for the project root
+    pub directory: String,
+    pub files: Vec<String>,
+    /// The most central types, then functions, by PageRank
+    pub key_symbols: Vec<String>,
+    pub entry_points: Vec<ComponentEntryPoint>,
+    pub depends_on: Vec<ComponentDependency>,
+}
+
+/// A declaration other components use, or an exported one when none are used yet
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentEntryPoint {
+    pub qualified_name: String,
+    pub entity_type: EntityType,
+    pub file: String,
+    pub line: u32,
+    /// Imports, calls and references reaching it from other components
+    pub used_by: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentDependency {
+    pub component: String,
+    /// Imports, calls and references from one component to the other
+    pub edges: u32,
+}
+
+/// Components detected in the project and how they depend on each other
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchitectureOverview {
+    pub file_count: usize,
+    pub components: Vec<Component>,
+}
+
+/// Cluster files into components with Louvain community detection on the
+/// file graph weighted by imports, calls, references and inheritance
+pub fn build_overview(memory: &ProjectMemory) -> ArchitectureOverview {
+    let mut files: Vec<&str> = memory.entities.values().map(|e| e.file_path.as_str()).collect();
+    files.sort_unstable();
+    files.dedup();
+    let file_index: HashMap<&str, usize> = files.iter().enumerate().map(|(i, f)| (*f, i)).collect();
+
+    // Directed entity-to-entity edges that cross files, plus imports resolved to a file
+    let mut edges: Vec<(usize, usize, Option<&CodeEntity>)> = Vec::new();
+    for rel in &memory.relationships {
+        let depends = matches!(
+            rel.relationship_type,
+            RelationType::Calls | RelationType::Uses | RelationType::References | RelationType::Extends | RelationType::Implements | RelationType::Imports
+        );
+        if !depends || !rel.is_resolved() {
+            continue;
+        }
+        if let (Some(from), Some(to)) = (memory.entities.get(&rel.from_entity), memory.entities.get(&rel.to_entity)) {
+            if from.file_path != to.file_path && to.entity_type != EntityType::Import {
+                edges.push((file_index[from.file_path.as_str()], file_index[to.file_path.as_str()], Some(to)));
+            }
+        }
+    }
+    let imports = ImportResolver::new(memory);
+    for import in memory.entities.values().filter(|e| e.entity_type == EntityType::Import) {
+        let target = imports.target_file(import).and_then(|file| file_index.get(file.as_str()).copied());
+        if let Some(target) = target.filter(|&target| target != file_index[import.file_path.as_str()]) {
+            edges.push((file_index[import.file_path.as_str()], target, None));
+        }
+    }
+
+    let mut weights: HashMap<(usize, usize), f64> = HashMap::new();
+    for &(from, to, _) in &edges {
+        *weights.entry((from.min(to), from.max(to))).or_default() += 1.0;
+    }
+    let mut membership = louvain(files.len(), &weights);
+
+    // Files linked to nothing join the other files of their directory
+    let mut degree = vec![0usize; files.len()];
+    for &(a, b) in weights.keys() {
+        degree[a] += 1;
+        degree[b] += 1;
+    }
+    let mut by_directory: HashMap<&str, usize> = HashMap::new();
+    for (i, file) in files.iter().enumerate().filter(|(i, _)| degree[*i] > 0) {
+        by_directory.entry(parent_directory(file)).or_insert(membership[i]);
+    }
+    let mut next = membership.iter().max().map(|m| m + 1).unwrap_or(0);
+    for (i, file) in files.iter().enumerate().filter(|(i, _)| degree[*i] == 0) {
+        membership[i] = *by_directory.entry(parent_directory(file)).or_insert_with(|| {
+            next += 1;
+            next - 1
+        });
+    }
+
+    // Largest components first
+    let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for (i, &community) in membership.iter().enumerate() {
+        groups.entry(community).or_default().push(i);
+    }
+    let mut groups: Vec<Vec<usize>> = groups.into_values().collect();
+    groups.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| files[a[0]].cmp(files[b[0]])));
+    let mut component_of = vec![0usize; files.len()];
+    for (c, group) in groups.iter().enumerate() {
+        group.iter().for_each(|&i| component_of[i] = c);
+    }
+
+    let mut declarations: Vec<Vec<&CodeEntity>> = vec![Vec::new(); groups.len()];
+    for entity in memory.entities.values() {
+        let is_declaration = matches!(entity.entity_type, EntityType::Function | EntityType::Class | EntityType::Interface | EntityType::Type);
+        if is_declaration {
+            declarations[component_of[file_index[entity.file_path.as_str()]]].push(entity);
+        }
+    }
+    // Types describe a component better than functions, whose short names often
+    // collect calls meant for other methods
+    let pagerank = |entity: &CodeEntity| entity.centrality.as_ref().map(|c| c.pagerank).unwrap_or(0.0);
+    let is_function = |entity: &CodeEntity| entity.entity_type == EntityType::Function;
+    for entities in &mut declarations {
+        entities.sort_by(|a, b| {
+            is_function(a)
+                .cmp(&is_function(b))
+                .then_with(|| pagerank(b).total_cmp(&pagerank(a)))
+                .then_with(|| a.qualified_name.cmp(&b.qualified_name))
+        });
+    }
+
+    let mut used_by: HashMap<&str, u32> = HashMap::new();
+    let mut dependencies: Vec<BTreeMap<usize, u32>> = vec![BTreeMap::new(); groups.len()];
+    for &(from, to, target) in &edges {
+        let (from, to) = (component_of[from], component_of[to]);
+        if from != to {
+            *dependencies[from].entry(to).or_default() += 1;
+            if let Some(target) = target {
+                *used_by.entry(target.id.as_str()).or_default() += 1;
+            }
+        }
+    }
+
+    let names = component_names(&groups, &files, &declarations);
+    let components = groups
+        .iter()
+        .enumerate()
+        .map(|(c, group)| {
+            let mut entry_points: Vec<ComponentEntryPoint> = declarations[c]
+                .iter()
+                .filter_map(|e| used_by.get(e.id.as_str()).map(|&count| entry_point(e, count)))
+                .collect();
+            entry_points.sort_by(|a, b| b.used_by.cmp(&a.used_by).then_with(|| a.qualified_name.cmp(&b.qualified_name)));
+            if entry_points.is_empty() {
+                entry_points = declarations[c].iter().filter(|e| is_exported(e)).map(|e| entry_point(e, 0)).collect();
+            }
+            entry_points.truncate(ENTRY_POINTS);
+
+            let mut depends_on: Vec<ComponentDependency> = dependencies[c]
+                .iter()
+                .map(|(&other, &edges)| ComponentDependency { component: names[other].clone(), edges })
+                .collect();
+            depends_on.sort_by(|a, b| b.edges.cmp(&a.edges).then_with(|| a.component.cmp(&b.component)));
+
+            Component {
+                name: names[c].clone(),
+                directory: dominant_directory(group.iter().map(|&i| files[i])),
+                files: group.iter().map(|&i| files[i].to_string()).collect(),
+                key_symbols: declarations[c].iter().take(KEY_SYMBOLS).map(|e| e.name.clone()).collect(),
+                entry_points,
+                depends_on,
+            }
+        })
+        .collect();
+
+    ArchitectureOverview { file_count: files.len(), components }
+}
+
+impl ArchitectureOverview {
+    /// A Markdown document for onboarding: a summary table, a dependency
+    /// diagram and a section per component
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::new();
+        let _ = writeln!(md, "# Architecture overview\n");
+        let _ = writeln!(
+            md,
+            "{} components across {} files, clustered by how the files import and call each other.\n",
+            self.components.len(),
+            self.file_count
+        );
+
+        let _ = writeln!(md, "## Components\n");
+        let _ = writeln!(md, "| Component | Files | Key symbols | Depends on |");
+        let _ = writeln!(md, "|-----------|-------|-------------|------------|");
+        for component in &self.components {
+            let symbols: Vec<String> = component.key_symbols.iter().map(|s| format!("`{}`", s)).collect();
+            let depends: Vec<&str> = component.depends_on.iter().map(|d| d.component.as_str()).collect();
+            let _ = writeln!(
+                md,
+                "| {} | {} | {} | {} |",
+                component.name,
+                component.files.len(),
+                symbols.join(", "),
+                if depends.is_empty() { "—".to_string() } else { depends.join(", ") }
+            );
+        }
+
+        if self.components.iter().any(|c| !c.depends_on.is_empty()) {
+            let _ = writeln!(md, "\n## Dependencies\n");
+            let _ = writeln!(md, "```mermaid\ngraph LR");
+            let index: HashMap<&str, usize> = self.components.iter().enumerate().map(|(i, c)| (c.name.as_str(), i)).collect();
+            for (i, component) in self.components.iter().enumerate() {
+                let _ = writeln!(md, "    c{}[\"{}\"]", i, component.name.replace('"', "'"));
+            }
+            for (i, component) in self.components.iter().enumerate() {
+                for dependency in &component.depends_on {
+                    let _ = writeln!(md, "    c{} -->|{}| c{}", i, dependency.edges, index[dependency.component.as_str()]);
+                }
+            }
+            let _ = writeln!(md, "```");
+        }
+
+        for component in &self.components {
+            let _ = writeln!(md, "\n## {}\n", component.name);
+            let directory = if component.directory.is_empty() { "project root" } else { &component.directory };
+            let _ = writeln!(md, "Mostly in `{}`, {} files.", directory, component.files.len());
+            if !component.entry_points.is_empty() {
+                let _ = writeln!(md, "\n### Public entry points\n");
+                for entry in &component.entry_points {
+                    let used = if entry.used_by > 0 { format!(", {} uses from other components", entry.used_by) } else { String::new() };
+                    let _ = writeln!(md, "- `{}` ({}) in `{}:{}`{}", entry.qualified_name, entry.entity_type.as_str(), entry.file, entry.line, used);
+                }
+            }
+            if !component.depends_on.is_empty() {
+                let _ = writeln!(md, "\n### Depends on\n");
+                for dependency in &component.depends_on {
+                    let _ = writeln!(md, "- {} ({} edges)", dependency.component, dependency.edges);
+                }
+            }
+            let _ = writeln!(md, "\n### Files\n");
+            for file in &component.files {
+                let _ = writeln!(md, "- `{}`", file);
+            }
+        }
+        md
+    }
+}
+
+fn entry_point(entity: &CodeEntity, used_by: u32) -> ComponentEntryPoint {
+    ComponentEntryPoint {
+        qualified_name: entity.qualified_name.clone(),
+        entity_type: entity.entity_type.clone(),
+        file: entity.file_path.clone(),
+        line: entity.line_start,
+        used_by,
+    }
+}
+
+// Named after their dominant directory; components sharing one add their most central symbol
+fn component_names(groups: &[Vec<usize>], files: &[&str], declarations: &[Vec<&CodeEntity>]) -> Vec<String> {
+    let directories: Vec<String> = groups.iter().map(|group| dominant_directory(group.iter().map(|&i| files[i]))).collect();
+    let mut names: Vec<String> = Vec::new();
+    for (c, directory) in directories.iter().enumerate() {
+        let base = if directory.is_empty() { "(root)".to_string() } else { directory.clone() };
+        let shared = directories.iter().filter(|d| *d == directory).count() > 1;
+        let mut name = match declarations[c].first() {
+            Some(symbol) if shared => format!("{} ({})", base, symbol.name),
+            _ => base,
+        };
+        if names.contains(&name) {
+            name = format!("{} #{}", name, c + 1);
+        }
+        names.push(name);
+    }
+    names
+}
+
+// The directory holding the most files, preferring the deeper one on ties
+fn dominant_directory<'a>(files: impl Iterator<Item = &'a str>) -> String {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for file in files {
+        *counts.entry(parent_directory(file)).or_default() += 1;
+    }
+    counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(directory, _)| directory.to_string())
+        .unwrap_or_default()
+}
+
+fn parent_directory(file: &str) -> &str {
+    file.rsplit_once('/').map(|(directory, _)| directory).unwrap_or("")
+}
+
+/// Undirected weighted graph; self loops hold weight inside an aggregated node
+struct Graph {
+    neighbors: Vec<Vec<(usize, f64)>>,
+    self_loops: Vec<f64>,
+}
+
+impl Graph {
+    fn degree(&self, node: usize) -> f64 {
+        self.neighbors[node].iter().map(|(_, w)| w).sum::<f64>() + 2.0 * self.self_loops[node]
+    }
+}
+
+// Louvain: move nodes to the neighboring community with the best modularity
+// gain, then merge communities into nodes and repeat until nothing moves
+fn louvain(n: usize, weights: &HashMap<(usize, usize), f64>) -> Vec<usize> {
+    let mut graph = Graph { neighbors: vec![Vec::new(); n], self_loops: vec![0.0; n] };
+    let mut sorted: Vec<(&(usize, usize), &f64)> = weights.iter().collect();
+    sorted.sort_by_key(|(key, _)| **key);
+    for (&(a, b), &w) in sorted {
+        graph.neighbors[a].push((b, w));
+        graph.neighbors[b].push((a, w));
+    }
+
+    let mut membership: Vec<usize> = (0..n).collect();
+    loop {
+        let communities = local_moves(&graph);
+        // Renumber in order of first appearance
+        let mut renumbered: HashMap<usize, usize> = HashMap::new();
+        let communities: Vec<usize> = communities
+            .iter()
+            .map(|c| {
+                let next = renumbered.len();
+                *renumbered.entry(*c).or_insert(next)
+            })
+            .collect();
+        if renumbered.len() == graph.neighbors.len() {
+            return membership;
+        }
+        membership.iter_mut().for_each(|m| *m = communities[*m]);
+
+        let count = renumbered.len();
+        let mut merged: BTreeMap<(usize, usize), f64> = BTreeMap::new();
+        let mut self_loops = vec![0.0; count];
+        for (node, neighbors) in graph.neighbors.iter().enumerate() {
+            self_loops[communities[node]] += graph.self_loops[node];
+            for &(other, w) in neighbors.iter().filter(|(other, _)| node < *other) {
+                let (a, b) = (communities[node], communities[other]);
+                if a == b {
+                    self_loops[a] += w;
+                } else {
+                    *merged.entry((a.min(b), a.max(b))).or_default() += w;
+                }
+            }
+        }
+        graph = Graph { neighbors: vec![Vec::new(); count], self_loops };
+        for ((a, b), w) in merged {
+            graph.neighbors[a].push((b, w));
+            graph.neighbors[b].push((a, w));
+        }
+    }
+}
+
+fn local_moves(graph: &Graph) -> Vec<usize> {
+    let n = graph.neighbors.len();
+    let degrees: Vec<f64> = (0..n).map(|node| graph.degree(node)).collect();
+    let total: f64 = degrees.iter().sum();
+    let mut community: Vec<usize> = (0..n).collect();
+    if total == 0.0 {
+        return community;
+    }
+    let mut community_degree = degrees.clone();
+
+    let mut moved = true;
+    let mut passes = 0;
+    while moved && passes < 100 {
+        moved = false;
+        passes += 1;
+        for node in 0..n {
+            let current = community[node];
+            community_degree[current] -= degrees[node];
+            let mut links: BTreeMap<usize, f64> = BTreeMap::from([(current, 0.0)]);
+            for &(other, w) in &graph.neighbors[node] {
+                *links.entry(community[other]).or_default() += w;
+            }
+            let gain = |c: usize, link: f64| link - community_degree[c] * degrees[node] / total;
+            let mut best = (current, gain(current, links[&current]));
+            for (&c, &link) in &links {
+                let g = gain(c, link);
+                if g > best.1 + 1e-12 {
+                    best = (c, g);
+                }
+            }
+            community[node] = best.0;
+            community_degree[best.0] += degrees[node];
+            moved |= best.0 != current;
+        }
+    }
+    community
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CodeParser;
+
+    #[test]
+    fn test_overview_clusters_files_into_components() -> anyhow::Result<()> {
+        let parser = CodeParser::new()?;
+        let mut memory = ProjectMemory::new("/test".to_string());
+        let files = [
+            ("src/api/routes.rs", "use crate::api::handlers::list_orders;\n\nfn routes() {\n    list_orders();\n    show_order();\n}\n"),
+            ("src/api/handlers.rs", "pub fn list_orders() {\n    render();\n    fetch_orders();\n}\n\npub fn show_order() {\n    render();\n}\n"),
+            ("src/api/render.rs", "pub fn render() {\n    escape();\n}\n\nfn escape() {}\n"),
+            ("src/db/pool.rs", "pub struct Pool;\n\npub fn connect() -> Pool {\n    Pool\n}\n"),
+            ("src/db/orders.rs", "use crate::db::pool::connect;\n\npub fn fetch_orders() {\n    connect();\n    run_query();\n}\n\nfn run_query() {\n    connect();\n}\n"),
+            ("src/db/schema.rs", "pub fn migrate() {\n    connect();\n    fetch_orders();\n}\n"),
+            ("tools/gen.py", "def main():\n    pass\n"),
+        ];
+        for (path, source) in files {
+            let (entities, relationships) = parser.parse_source(path, source)?;
+            entities.into_iter().for_each(|e| memory.add_entity(e));
+            relationships.into_iter().for_each(|r| memory.add_relationship(r));
+        }
+        memory.resolve_references();
+        crate::record_centrality(&mut memory);
+
+        let overview = build_overview(&memory);
+        let summary: Vec<(String, Vec<String>)> = overview.components.iter().map(|c| (c.name.clone(), c.files.clone())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("src/api".to_string(), vec!["src/api/handlers.rs".to_string(), "src/api/render.rs".to_string(), "src/api/routes.rs".to_string()]),
+                ("src/db".to_string(), vec!["src/db/orders.rs".to_string(), "src/db/pool.rs".to_string(), "src/db/schema.rs".to_string()]),
+                ("tools".to_string(), vec!["tools/gen.py".to_string()]),
+            ]
+        );
+        let api = &overview.components[0];
+        assert_eq!(api.depends_on.len(), 1);
+        assert_eq!(api.depends_on[0].component, "src/db");
+        let db = &overview.components[1];
+        assert_eq!(db.entry_points[0].qualified_name, "crate::db::orders::fetch_orders");
+
+        let markdown = overview.to_markdown();
+        assert!(markdown.starts_with("# Architecture overview\n"));
+        assert!(markdown.contains("| src/api | 3 |"));
+        assert!(markdown.contains("## src/db\n"));
+        assert!(markdown.contains("- `crate::db::orders::fetch_orders` (function) in `src/db/orders.rs:3`, 1 uses from other components"));
+        Ok(())
+    }
+}
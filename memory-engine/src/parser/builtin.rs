@@ -0,0 +1,110 @@
+// The languages shipped with the crate, registered through the same
+// `LanguageParser` trait that library users implement for their own.
+
+use super::cpp::CppParser;
+use super::csharp::CSharpParser;
+use super::go::GoParser;
+use super::java::JavaParser;
+use super::javascript::JavaScriptParser;
+use super::kotlin::KotlinParser;
+use super::php::PhpParser;
+use super::python::PythonParser;
+use super::ruby::RubyParser;
+use super::rust::RustParser;
+use super::swift::SwiftParser;
+use super::LanguageParser;
+use crate::{CodeEntity, Relationship};
+use anyhow::Result;
+use std::sync::Arc;
+
+macro_rules! builtin_parser {
+    ($parser:ty, $language:literal, [$($extension:literal),*], |$content:ident| $detect:expr) => {
+        impl LanguageParser for $parser {
+            fn language(&self) -> &str {
+                $language
+            }
+
+            fn extensions(&self) -> Vec<String> {
+                vec![$($extension.to_string()),*]
+            }
+
+            fn detect(&self, $content: &str) -> bool {
+                $detect
+            }
+
+            fn parse(&self, content: &str, file_path: &str) -> Result<(Vec<CodeEntity>, Vec<Relationship>)> {
+                <$parser>::parse(self, content, file_path)
+            }
+        }
+    };
+}
+
+builtin_parser!(JavaScriptParser, "javascript", ["js", "jsx"], |content| has_shebang(content, "node"));
+builtin_parser!(PythonParser, "python", ["py"], |content| has_shebang(content, "python"));
+builtin_parser!(RustParser, "rust", ["rs"], |_content| false);
+builtin_parser!(GoParser, "go", ["go"], |_content| false);
+builtin_parser!(JavaParser, "java", ["java"], |_content| false);
+builtin_parser!(KotlinParser, "kotlin", ["kt", "kts"], |_content| false);
+builtin_parser!(CppParser, "cpp", ["cc", "cpp", "cxx", "hh", "hpp", "hxx"], |_content| false);
+builtin_parser!(CSharpParser, "csharp", ["cs"], |_content| false);
+builtin_parser!(RubyParser, "ruby", ["rb", "rake"], |content| has_shebang(content, "ruby"));
+builtin_parser!(PhpParser, "php", ["php"], |content| content.trim_start().starts_with("<?php"));
+builtin_parser!(SwiftParser, "swift", ["swift"], |_content| false);
+
+/// One built-in parser registered again under a related language's name
+struct Dialect {
+    language: &'static str,
+    extensions: &'static [&'static str],
+    parser: Arc<dyn LanguageParser>,
+}
+
+impl LanguageParser for Dialect {
+    fn language(&self) -> &str {
+        self.language
+    }
+
+    fn extensions(&self) -> Vec<String> {
+        self.extensions.iter().map(|extension| extension.to_string()).collect()
+    }
+
+    fn parse(&self, content: &str, file_path: &str) -> Result<(Vec<CodeEntity>, Vec<Relationship>)> {
+        self.parser.parse(content, file_path)
+    }
+}
+
+// `#!/usr/bin/env python3` names its interpreter on the first line
+fn has_shebang(content: &str, interpreter: &str) -> bool {
+    content.lines().next().map(|line| line.starts_with("#!") && line.contains(interpreter)).unwrap_or(false)
+}
+
+/// Every built-in parser, in `SUPPORTED_LANGUAGES` order
+pub(crate) fn builtin_parsers(include_dirs: &[String]) -> Result<Vec<Arc<dyn LanguageParser>>> {
+    let javascript: Arc<dyn LanguageParser> = Arc::new(JavaScriptParser::new()?);
+    let mut cpp = CppParser::new()?;
+    cpp.set_include_dirs(include_dirs.to_vec());
+    let cpp: Arc<dyn LanguageParser> = Arc::new(cpp);
+
+    Ok(vec![
+        javascript.clone(),
+        Arc::new(Dialect {
+            language: "typescript",
+            extensions: &["ts", "tsx", "mts", "cts"],
+            parser: javascript,
+        }),
+        Arc::new(PythonParser::new()?),
+        Arc::new(RustParser::new()?),
+        Arc::new(GoParser::new()?),
+        Arc::new(JavaParser::new()?),
+        Arc::new(KotlinParser::new()?),
+        Arc::new(Dialect {
+            language: "c",
+            extensions: &["c", "h"],
+            parser: cpp.clone(),
+        }),
+        cpp,
+        Arc::new(CSharpParser::new()?),
+        Arc::new(RubyParser::new()?),
+        Arc::new(PhpParser::new()?),
+        Arc::new(SwiftParser::new()?),
+    ])
+}
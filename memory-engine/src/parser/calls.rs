@@ -0,0 +1,304 @@
+// Call sites and references to types and constants inside function and type
+// bodies. They are recorded as symbol links that
+// `ProjectMemory::resolve_references` later ties to entities in any file.
+// Method calls resolve only through `self`/`this`, a type name or an imported
+// module; `x.len()` on a value of unknown type stays a bare name.
+// Names are read from the code with comments and strings removed. Code outside
+// any declaration, such as a script's `main()` or a Rust macro invocation,
+// has no entity to link from; the entities it names are marked `file_level_use`.
+
+use super::measure::code_lines;
+use crate::{CodeEntity, EntityType, RelationType, Relationship};
+use std::collections::HashSet;
+
+/// Words followed by `(` that are syntax rather than calls
+const NOT_CALLS: &[&str] = &[
+    "if", "elif", "for", "foreach", "while", "switch", "match", "return", "catch", "sizeof", "typeof", "alignof",
+    "decltype", "function", "fn", "def", "func", "fun", "new", "await", "yield", "assert", "and", "or", "not", "in",
+    "is", "when", "unless", "until", "super", "this", "self", "using", "lock", "defer", "go", "with", "except",
+    "pub", "crate", "async", "where", "impl", "extern", "unsafe", "dyn", "mut", "move", "const", "static", "let",
+    "var", "val", "else", "loop", "throw", "throws", "delete", "void", "case", "lambda", "noexcept", "operator",
+    "template", "public", "private", "protected", "internal", "override", "virtual", "inline", "constexpr",
+];
+
+/// Capitalized names too common to be worth linking
+const NOT_REFERENCES: &[&str] = &["Self", "None", "Some", "Ok", "Err", "True", "False", "String", "Vec", "Option", "Result"];
+
+/// Symbol links from each function or type to the names it calls or mentions
+pub(crate) fn record_calls(language: &str, entities: &mut [CodeEntity], content: &str) -> Vec<Relationship> {
+    let scopes: Vec<&CodeEntity> = entities
+        .iter()
+        .filter(|e| matches!(e.entity_type, EntityType::Function | EntityType::Class | EntityType::Interface | EntityType::Type))
+        .collect();
+    if scopes.is_empty() {
+        return Vec::new();
+    }
+    // A function's own name on its declaration line is not a call
+    let declared: HashSet<(u32, &str)> = scopes.iter().map(|e| (e.line_start, e.name.as_str())).collect();
+    // Functions of this file may also be passed around by name, as in `.map(parse)`
+    let local_functions: HashSet<&str> =
+        scopes.iter().filter(|e| e.entity_type == EntityType::Function).map(|e| e.name.as_str()).collect();
+    let file_names: HashSet<&str> = entities
+        .iter()
+        .filter(|e| !matches!(e.entity_type, EntityType::Import | EntityType::Export | EntityType::Module))
+        .map(|e| e.name.as_str())
+        .collect();
+    // Imported modules and aliases, as in `fmt.Println` or `log.Info`
+    let imported: HashSet<&str> = entities
+        .iter()
+        .filter(|e| e.entity_type == EntityType::Import)
+        .filter_map(|e| e.metadata.get("alias").map(String::as_str).or_else(|| e.name.rsplit(['.', ':', '/', '\\']).next()))
+        .collect();
+    let mut file_level: HashSet<String> = HashSet::new();
+
+    let mut seen: HashSet<(&str, String, bool)> = HashSet::new();
+    let mut relationships = Vec::new();
+    for (line_index, code) in code_lines(language, content).iter().enumerate() {
+        let line_num = line_index as u32 + 1;
+        // The innermost declaration spanning the line
+        let Some(scope) = scopes
+            .iter()
+            .filter(|e| e.line_start <= line_num && line_num <= e.line_end.max(e.line_start))
+            .min_by_key(|e| e.line_end.max(e.line_start) - e.line_start)
+        else {
+            for (path, _, _) in symbol_paths(code) {
+                let last = path.rsplit(['.', ':']).next().unwrap_or(&path);
+                if file_names.contains(last) && !declared.contains(&(line_num, last)) {
+                    file_level.insert(last.to_string());
+                }
+            }
+            continue;
+        };
+
+        for (path, is_call, after_member) in symbol_paths(code) {
+            let segments: Vec<&str> = path.split(['.', ':']).filter(|s| !s.is_empty()).collect();
+            let Some(&last) = segments.last() else { continue };
+
+            let mut links = Vec::new();
+            let mut unknown_receiver = false;
+            if is_call {
+                let keyword_or_recursive =
+                    segments.len() == 1 && !after_member && (NOT_CALLS.contains(&last) || last == scope.name);
+                if !keyword_or_recursive && !declared.contains(&(line_num, last)) {
+                    // `.mode(` after a call, or `text.is_empty(` on a local value
+                    unknown_receiver = after_member
+                        || path
+                            .rsplit_once('.')
+                            .filter(|(_, method)| !method.contains(':'))
+                            .is_some_and(|(receiver, _)| !is_known_receiver(receiver, &imported));
+                    links.push((path.clone(), true));
+                }
+            } else if is_mention(last, scope) {
+                // `Shape::Circle` names the `Circle` declared in `Shape`
+                links.push((path.clone(), false));
+            } else if segments.len() == 1 && local_functions.contains(last) && last != scope.name && !declared.contains(&(line_num, last)) {
+                links.push((path.clone(), false));
+            }
+            // Qualifiers like `Config::load` mention a type too
+            for segment in &segments[..segments.len() - 1] {
+                if is_mention(segment, scope) {
+                    links.push((segment.to_string(), false));
+                }
+            }
+
+            for (symbol, is_call) in links {
+                if seen.insert((scope.id.as_str(), symbol.clone(), is_call)) {
+                    let relationship_type = if is_call { RelationType::Calls } else { RelationType::References };
+                    let mut relationship = Relationship::to_symbol(scope.id.clone(), &symbol, relationship_type)
+                        .with_metadata("line".to_string(), line_num.to_string());
+                    if is_call && unknown_receiver {
+                        relationship = relationship.with_metadata("unknown_receiver".to_string(), "true".to_string());
+                    }
+                    relationships.push(relationship);
+                }
+            }
+        }
+    }
+    for entity in entities.iter_mut().filter(|e| file_level.contains(&e.name)) {
+        entity.metadata.insert("file_level_use".to_string(), "true".to_string());
+    }
+    relationships
+}
+
+// A capitalized name, such as a type or constant, other than the enclosing declaration
+fn is_mention(name: &str, scope: &CodeEntity) -> bool {
+    name.starts_with(|c: char| c.is_uppercase()) && !NOT_REFERENCES.contains(&name) && name != scope.name
+}
+
+// `self.save(`, `Config.load(` and `os.path.join(` name where the method lives
+fn is_known_receiver(receiver: &str, imported: &HashSet<&str>) -> bool {
+    matches!(receiver, "self" | "this" | "Self" | "super")
+        || imported.contains(receiver)
+        || receiver.rsplit(['.', ':']).next().is_some_and(|name| name.starts_with(|c: char| c.is_uppercase()))
+}
+
+// Dotted or `::` paths in a line of code, whether each is called, and whether
+// it follows a member access on an expression, as `.mode` in `f(x).mode()`
+fn symbol_paths(code: &str) -> Vec<(String, bool, bool)> {
+    let chars: Vec<char> = code.chars().collect();
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut paths = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        // PHP variables are not symbols, except `$this`
+        let is_this = chars[i..].starts_with(&['t', 'h', 'i', 's']) && !chars.get(i + 4).is_some_and(|c| is_word(*c));
+        if !is_word(chars[i]) || (i > 0 && (is_word(chars[i - 1]) || (chars[i - 1] == '$' && !is_this))) {
+            i += 1;
+            continue;
+        }
+        // `..` is a range or struct update, not a member access
+        let after_member = (i >= 1 && chars[i - 1] == '.' && !(i >= 2 && chars[i - 2] == '.'))
+            || (i >= 2 && chars[i - 2..i] == ['-', '>']);
+        // `$this->load(` and `obj?.run(` continue a path like `.`
+        let mut path = String::new();
+        let mut end = i;
+        loop {
+            let start = end;
+            while end < chars.len() && is_word(chars[end]) {
+                end += 1;
+            }
+            path.extend(&chars[start..end]);
+            let separator = [&['.'][..], &['?', '.'], &[':', ':'], &['-', '>']]
+                .into_iter()
+                .find(|sep| chars[end..].starts_with(sep) && chars.get(end + sep.len()).is_some_and(|c| is_word(*c)));
+            match separator {
+                Some(sep) => {
+                    path.push_str(if sep == [':', ':'] { "::" } else { "." });
+                    end += sep.len();
+                }
+                None => break,
+            }
+        }
+
+        if !path.starts_with(|c: char| c.is_ascii_digit()) {
+            let next = chars[end..].iter().find(|c| !c.is_whitespace());
+            // Rust macro invocations: `name!(...)`, `name![...]`, `name! { ... }`
+            let is_macro = chars.get(end) == Some(&'!')
+                && chars[end + 1..].iter().find(|c| !c.is_whitespace()).is_some_and(|c| matches!(c, '(' | '[' | '{'));
+            paths.push((path, next == Some(&'(') || is_macro, after_member));
+        }
+        i = end;
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CodeParser, ProjectMemory, RelationType};
+
+    #[test]
+    fn test_calls_and_references_from_bodies() -> anyhow::Result<()> {
+        let parser = CodeParser::new()?;
+        let source = r#"const MAX_RETRIES: u32 = 3;
+
+fn load(path: &str) -> Config {
+    // save() in a comment is not a call
+    let text = read_file(path, "parse(x)");
+    if text.is_empty() { return load(path); }
+    Config::parse(&text).with_retries(MAX_RETRIES).mode(Mode::Strict)
+}
+
+fn load_all(paths: &[&str]) -> Vec<Config> {
+    paths.iter().map(load).collect()
+}
+
+register!(load_all);
+"#;
+        let (entities, relationships) = parser.parse_source("src/config.rs", source)?;
+        let load = entities.iter().find(|e| e.name == "load").unwrap();
+        let mut links: Vec<(&str, &str)> = relationships
+            .iter()
+            .filter(|r| r.from_entity == load.id && matches!(r.relationship_type, RelationType::Calls | RelationType::References))
+            .map(|r| (r.relationship_type.as_str(), r.target_name().unwrap()))
+            .collect();
+        links.sort();
+        assert_eq!(
+            links,
+            vec![
+                ("calls", "Config::parse"),
+                ("calls", "mode"),
+                ("calls", "read_file"),
+                ("calls", "text.is_empty"),
+                ("calls", "with_retries"),
+                ("references", "Config"),
+                ("references", "MAX_RETRIES"),
+                ("references", "Mode"),
+                ("references", "Mode::Strict"),
+            ]
+        );
+
+        // Methods of values whose type is unknown are never tied to a declaration
+        let mut unknown_receivers: Vec<&str> = relationships
+            .iter()
+            .filter(|r| r.from_entity == load.id && r.metadata.contains_key("unknown_receiver"))
+            .map(|r| r.target_name().unwrap())
+            .collect();
+        unknown_receivers.sort();
+        assert_eq!(unknown_receivers, vec!["mode", "text.is_empty", "with_retries"]);
+
+        let load_all = entities.iter().find(|e| e.name == "load_all").unwrap();
+        assert!(relationships
+            .iter()
+            .any(|r| r.from_entity == load_all.id && r.relationship_type == RelationType::References && r.target_name() == Some("load")));
+        assert_eq!(load_all.metadata.get("file_level_use").map(String::as_str), Some("true"));
+
+        // Keywords followed by `(` are not calls
+        let keyword_calls = |path: &str, source: &str| -> Vec<String> {
+            let (_, relationships) = parser.parse_source(path, source).unwrap();
+            relationships
+                .iter()
+                .filter(|r| r.relationship_type == RelationType::Calls)
+                .map(|r| r.target_name().unwrap().to_string())
+                .collect()
+        };
+        let rust = "pub(crate) fn go_now(x: u8) -> u8 {\n    unsafe { extern_call(x) }\n}\n";
+        assert_eq!(keyword_calls("src/now.rs", rust), vec!["extern_call"]);
+        let javascript = "const f = async (d) => {\n  return save(d);\n};\n";
+        assert_eq!(keyword_calls("f.js", javascript), vec!["save"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_method_calls_on_unknown_receivers_stay_unresolved() -> anyhow::Result<()> {
+        let rules = "pub struct RuleSet;\n\nimpl RuleSet {\n    pub fn len(&self) -> usize {\n        0\n    }\n}\n";
+        let session = r#"pub struct Session;
+
+impl Session {
+    pub fn size(&self, items: &[u8]) -> usize {
+        items.len() + self.total()
+    }
+
+    fn total(&self) -> usize {
+        RuleSet::len(&RuleSet)
+    }
+}
+"#;
+        let parser = CodeParser::new()?;
+        let mut memory = ProjectMemory::new("/project".to_string());
+        for (path, content) in [("src/rules.rs", rules), ("src/session.rs", session)] {
+            let (entities, relationships) = parser.parse_source(path, content)?;
+            entities.into_iter().for_each(|e| memory.add_entity(e));
+            relationships.into_iter().for_each(|r| memory.add_relationship(r));
+        }
+        memory.resolve_references();
+        let find = |name: &str| memory.entities.values().find(|e| e.name == name).unwrap();
+        let calls_from = |name: &str| -> Vec<(&str, bool)> {
+            let mut calls: Vec<(&str, bool)> = memory
+                .relationships
+                .iter()
+                .filter(|r| r.from_entity == find(name).id && r.relationship_type == RelationType::Calls)
+                .map(|r| (r.target_name().unwrap(), r.is_resolved()))
+                .collect();
+            calls.sort();
+            calls
+        };
+        // `items.len()` does not link to `RuleSet::len`; `self.total()` and `RuleSet::len` do
+        assert_eq!(calls_from("size"), vec![("items.len", false), ("self.total", true)]);
+        assert_eq!(calls_from("total"), vec![("RuleSet::len", true)]);
+        assert!(memory
+            .relationships
+            .iter()
+            .any(|r| r.from_entity == find("total").id && r.to_entity == find("len").id));
+        Ok(())
+    }
+}
@@ -0,0 +1,750 @@
+use super::parsed::ParsedFile;
+use super::signature::{record_signatures, SignatureStyle};
+use super::scope::{ScopeKind, ScopeTracker};
+use super::{line_entity, split_balanced, split_top_level, strip_generics};
+use crate::{CodeEntity, EntityType, RelationType, Relationship};
+use anyhow::Result;
+use regex::Regex;
+
+const HEADER_EXTENSIONS: &[&str] = &["h", "hh", "hpp", "hxx"];
+
+// Words that look like a function name before `(` but never declare one
+const NOT_FUNCTION_NAMES: &[&str] = &[
+    "if", "for", "while", "switch", "return", "sizeof", "catch", "decltype", "static_assert",
+    "alignof", "alignas", "defined", "__attribute__", "__declspec", "noexcept", "throw",
+];
+const NOT_DECLARATION_PREFIXES: &[&str] = &["return", "else", "new", "delete", "throw", "case", "goto", "typedef", "using"];
+const NOT_VARIABLE_TYPES: &[&str] = &["class", "struct", "union", "enum", "friend", "using", "return", "typedef", "namespace", "template", "public", "private", "protected"];
+
+/// Parser for C and C++ sources and headers
+pub(crate) struct CppParser {
+    include_dirs: Vec<String>,
+    include_regex: Regex,
+    ifndef_regex: Regex,
+    define_regex: Regex,
+    namespace_regex: Regex,
+    extern_block_regex: Regex,
+    type_regex: Regex,
+    enum_regex: Regex,
+    typedef_close_regex: Regex,
+    using_regex: Regex,
+    function_regex: Regex,
+    variable_regex: Regex,
+    return_type_line_regex: Regex,
+}
+
+/// A function signature that has not yet reached its body or `;`
+struct OpenSignature {
+    entity: usize,
+    text: String,
+    is_member: bool,
+    symbol: String,
+    lines_waited: usize,
+}
+
+// Trailing attributes or an initializer list may separate a signature from its body
+const MAX_SIGNATURE_LINES: usize = 3;
+
+impl OpenSignature {
+    /// What follows the parameter list, once it tells a declaration from a definition
+    fn rest(&self) -> Option<&str> {
+        let (_, rest) = split_balanced(&self.text, '(', ')')?;
+        let is_decided = rest.contains('{') || rest.contains(';') || self.lines_waited >= MAX_SIGNATURE_LINES;
+        Some(rest).filter(|_| is_decided)
+    }
+}
+
+impl CppParser {
+    pub(crate) fn new() -> Result<Self> {
+        Ok(Self {
+            include_dirs: Vec::new(),
+            include_regex: Regex::new(r#"^\s*#\s*include\s*([<"])([^>"]+)[>"]"#)?,
+            ifndef_regex: Regex::new(r"^\s*#\s*ifndef\s+(\w+)")?,
+            define_regex: Regex::new(r"^\s*#\s*define\s+(\w+)(\()?(.*)")?,
+            namespace_regex: Regex::new(r"^\s*(?:inline\s+)?namespace(?:\s+([\w:]+))?\s*(\{.*)?$")?,
+            extern_block_regex: Regex::new(r#"^\s*extern\s+"C(?:\+\+)?"\s*(\{.*)?$"#)?,
+            type_regex: Regex::new(
+                r"^\s*(typedef\s+)?(?:template\s*<.*>\s*)?(class|struct|union)\s+((?:[A-Z_][A-Z0-9_]*\s+)*?)(\w+)?\s*(?:final\s*)?(:[^{;]*)?(\{.*)?$",
+            )?,
+            enum_regex: Regex::new(r"^\s*(typedef\s+)?enum\s+(?:class\s+|struct\s+)?(\w+)?\s*(?::\s*[\w:\s]+)?(\{.*)?$")?,
+            typedef_close_regex: Regex::new(r"\}\s*(\w+)\s*[;,\[]")?,
+            using_regex: Regex::new(r"^\s*(?:template\s*<.*>\s*)?using\s+(\w+)\s*=")?,
+            function_regex: Regex::new(r"^\s*(.*?)((?:\w+(?:<[^<>()]*>)?::)*(?:~?\w+|operator\s*(?:\(\)|[^\s(\w]+)))\s*\(")?,
+            variable_regex: Regex::new(
+                r"^\s*((?:(?:static|extern|const|constexpr|inline|mutable|volatile|thread_local|unsigned|signed|struct|enum)\s+)*)([\w:]+)(?:<[^;{}()]*>)?[\s*&]+(?:const\s+)?(\w+)\s*(?:\[[^\]]*\])*\s*(?:=[^;]*|\{[^}]*\})?\s*[;,]",
+            )?,
+            return_type_line_regex: Regex::new(r"^\s*[\w:<>,\s\*&]+$")?,
+        })
+    }
+
+    /// Directories, relative to the project root, searched for `#include` targets
+    pub(crate) fn set_include_dirs(&mut self, include_dirs: Vec<String>) {
+        self.include_dirs = include_dirs
+            .into_iter()
+            .map(|dir| dir.trim_end_matches('/').to_string())
+            .collect();
+    }
+
+    pub(crate) fn parse(&self, content: &str, file_path: &str) -> Result<(Vec<CodeEntity>, Vec<Relationship>)> {
+        let mut parsed = ParsedFile::default();
+        let mut tracker = ScopeTracker::new();
+
+        let file_name = file_path.rsplit('/').next().unwrap_or(file_path);
+        let extension = file_name.rsplit('.').next().unwrap_or("").to_lowercase();
+        let is_header = HEADER_EXTENSIONS.contains(&extension.as_str());
+        let file_dir = file_path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+
+        // The file itself is the unit that includes and is included
+        let line_count = content.lines().count().max(1);
+        let mut file_entity = line_entity(file_name, EntityType::Module, file_path, 0, "");
+        file_entity.line_end = line_count as u32;
+        let file_entity = file_entity.with_metadata("kind".to_string(), if is_header { "header" } else { "source" }.to_string());
+        let file_index = parsed.push(file_entity);
+
+        let mut in_macro = false;
+        let mut last_ifndef: Option<String> = None;
+        let mut return_type_line: Option<String> = None;
+        let mut open_signature: Option<OpenSignature> = None;
+
+        for (line_num, line) in content.lines().enumerate() {
+            // Macro bodies continued with a backslash are not code
+            if in_macro {
+                in_macro = line.trim_end().ends_with('\\');
+                continue;
+            }
+            let trimmed = line.trim_start();
+
+            if trimmed.starts_with('#') {
+                in_macro = line.trim_end().ends_with('\\');
+                self.parse_directive(&mut parsed, file_index, &mut last_ifndef, file_dir, file_path, line_num, line);
+                continue;
+            }
+
+            if let Some(mut signature) = open_signature.take() {
+                signature.text.push(' ');
+                signature.text.push_str(trimmed);
+                signature.lines_waited += 1;
+                match signature.rest() {
+                    Some(rest) => self.finish_signature(&mut parsed, &mut tracker, signature.entity, rest, signature.is_member, &signature.symbol, is_header),
+                    None => open_signature = Some(signature),
+                }
+                for closed in tracker.process_line(line) {
+                    parsed.set_line_end(closed, line_num);
+                }
+                continue;
+            }
+
+            let enclosing = tracker
+                .current_body()
+                .filter(|scope| scope.kind != ScopeKind::Function)
+                .and_then(|scope| Some((scope.entity?, scope.name.clone(), scope.kind)));
+            let is_comment = tracker.in_block_comment() || trimmed.starts_with("//") || trimmed.starts_with("/*") || trimmed.starts_with('*');
+            let mut next_return_type_line = None;
+
+            if !is_comment && !trimmed.is_empty() && (tracker.at_top_level() || enclosing.is_some()) {
+                let context = Context {
+                    parent: enclosing.as_ref().map(|(parent, _, _)| *parent),
+                    parent_name: enclosing.as_ref().map(|(_, name, _)| name.as_str()),
+                    in_type: enclosing.as_ref().map(|(_, _, kind)| *kind == ScopeKind::Type).unwrap_or(false),
+                    file_path,
+                    line_num,
+                    line,
+                };
+
+                if let Some(captures) = self.namespace_regex.captures(trimmed).filter(|_| !trimmed.contains('=')) {
+                    match captures.get(1) {
+                        Some(name) => {
+                            let index = parsed.push(
+                                line_entity(name.as_str(), EntityType::Module, file_path, line_num, line)
+                                    .with_metadata("kind".to_string(), "namespace".to_string()),
+                            );
+                            context.attach(&mut parsed, index);
+                            tracker.expect_body(name.as_str(), index, ScopeKind::Namespace);
+                        }
+                        None => tracker.expect_anonymous_body(ScopeKind::Namespace),
+                    }
+                } else if self.extern_block_regex.is_match(trimmed) {
+                    tracker.expect_anonymous_body(ScopeKind::Namespace);
+                } else if let Some(captures) = self.type_regex.captures(trimmed) {
+                    self.parse_type(&mut parsed, &mut tracker, &context, &captures);
+                } else if let Some(captures) = self.enum_regex.captures(trimmed) {
+                    let is_typedef = captures.get(1).is_some();
+                    match captures.get(2) {
+                        Some(name) => {
+                            let index = parsed.push(
+                                line_entity(name.as_str(), EntityType::Class, file_path, line_num, line)
+                                    .with_metadata("kind".to_string(), "enum".to_string()),
+                            );
+                            context.attach(&mut parsed, index);
+                            if is_typedef {
+                                parsed.set_metadata(index, "typedef", String::new());
+                            }
+                            tracker.expect_body(name.as_str(), index, ScopeKind::Type);
+                        }
+                        None if is_typedef => {
+                            let index = parsed.push(
+                                line_entity("(anonymous)", EntityType::Class, file_path, line_num, line)
+                                    .with_metadata("kind".to_string(), "enum".to_string())
+                                    .with_metadata("typedef".to_string(), String::new()),
+                            );
+                            context.attach(&mut parsed, index);
+                            tracker.expect_body("(anonymous)", index, ScopeKind::Type);
+                        }
+                        None => {}
+                    }
+                } else if let Some(captures) = self.using_regex.captures(trimmed) {
+                    let index = parsed.push(
+                        line_entity(&captures[1], EntityType::Type, file_path, line_num, line)
+                            .with_metadata("kind".to_string(), "alias".to_string()),
+                    );
+                    context.attach(&mut parsed, index);
+                } else if trimmed.starts_with("typedef ") {
+                    if let Some(name) = typedef_name(trimmed) {
+                        let index = parsed.push(
+                            line_entity(name, EntityType::Type, file_path, line_num, line)
+                                .with_metadata("kind".to_string(), "typedef".to_string()),
+                        );
+                        context.attach(&mut parsed, index);
+                    }
+                } else if let Some(signature) = self.parse_function(&mut parsed, &mut tracker, &context, trimmed, return_type_line.as_deref(), is_header) {
+                    open_signature = Some(signature);
+                } else if !self.parse_variable(&mut parsed, &context, trimmed) {
+                    // `static int` alone on a line: the function name follows on the next one
+                    if tracker.at_top_level() && self.return_type_line_regex.is_match(trimmed) && !trimmed.ends_with(':') {
+                        next_return_type_line = Some(trimmed.trim_end().to_string());
+                    }
+                }
+            }
+            return_type_line = next_return_type_line;
+
+            for closed in tracker.process_line(line) {
+                parsed.set_line_end(closed, line_num);
+                self.close_typedef(&mut parsed, closed, line, file_path, line_num);
+            }
+        }
+
+        record_signatures(&mut parsed.entities, content, SignatureStyle::CFamily);
+        Ok(parsed.finish())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn parse_directive(
+        &self,
+        parsed: &mut ParsedFile,
+        file_index: usize,
+        last_ifndef: &mut Option<String>,
+        file_dir: &str,
+        file_path: &str,
+        line_num: usize,
+        line: &str,
+    ) {
+        if let Some(captures) = self.include_regex.captures(line) {
+            let is_system = &captures[1] == "<";
+            let include = &captures[2];
+            let mut entity = line_entity(include, EntityType::Import, file_path, line_num, line);
+            if is_system {
+                entity = entity.with_metadata("system".to_string(), "true".to_string());
+            }
+            parsed.push(entity);
+
+            // Quoted includes look next to the including file first
+            let mut search_paths: Vec<&str> = Vec::new();
+            if !is_system {
+                search_paths.push(file_dir);
+            }
+            search_paths.extend(self.include_dirs.iter().map(|dir| dir.as_str()));
+            if !search_paths.is_empty() {
+                let relationship = parsed.link_symbol(file_index, include, RelationType::Imports);
+                relationship.metadata.insert("search_paths".to_string(), search_paths.join(","));
+            }
+        } else if let Some(captures) = self.ifndef_regex.captures(line) {
+            *last_ifndef = Some(captures[1].to_string());
+        } else if let Some(captures) = self.define_regex.captures(line) {
+            let name = &captures[1];
+            let is_function_like = captures.get(2).is_some();
+            let value = captures[3].trim();
+            // Skip include guards: `#ifndef FOO_H` followed by a bare `#define FOO_H`
+            if !is_function_like && value.is_empty() && last_ifndef.as_deref() == Some(name) {
+                return;
+            }
+
+            let entity_type = if is_function_like { EntityType::Function } else { EntityType::Constant };
+            parsed.push(
+                line_entity(name, entity_type, file_path, line_num, line)
+                    .with_metadata("kind".to_string(), "macro".to_string()),
+            );
+        }
+    }
+
+    fn parse_type(&self, parsed: &mut ParsedFile, tracker: &mut ScopeTracker, context: &Context, captures: &regex::Captures) {
+        let is_typedef = captures.get(1).is_some();
+        let keyword = &captures[2];
+        let has_body = captures.get(6).is_some();
+        // Forward declarations and `struct foo *ptr` style uses are not definitions
+        if captures.get(6).is_none() && context.line.trim_end().ends_with(';') {
+            return;
+        }
+
+        let name = match captures.get(4) {
+            Some(name) => name.as_str(),
+            None if is_typedef || has_body => "(anonymous)",
+            None => return,
+        };
+        let index = parsed.push(
+            line_entity(name, EntityType::Class, context.file_path, context.line_num, context.line)
+                .with_metadata("kind".to_string(), keyword.to_string()),
+        );
+        if is_typedef {
+            parsed.set_metadata(index, "typedef", String::new());
+        }
+        context.attach(parsed, index);
+        tracker.expect_body(name, index, ScopeKind::Type);
+
+        if let Some(bases) = captures.get(5) {
+            for base in split_top_level(bases.as_str().trim_start_matches(':')) {
+                let base = base
+                    .split_whitespace()
+                    .filter(|word| !matches!(*word, "public" | "protected" | "private" | "virtual"))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                if !base.is_empty() {
+                    parsed.link_symbol(index, strip_generics(&base), RelationType::Extends);
+                }
+            }
+        }
+    }
+
+    /// `typedef struct { ... } name;` names the struct after its body closes
+    fn close_typedef(&self, parsed: &mut ParsedFile, index: usize, line: &str, file_path: &str, line_num: usize) {
+        if !parsed.entities[index].metadata.contains_key("typedef") {
+            return;
+        }
+        let Some(captures) = self.typedef_close_regex.captures(line) else { return };
+        let alias = captures[1].to_string();
+        parsed.set_metadata(index, "typedef", alias.clone());
+
+        if parsed.entities[index].name == "(anonymous)" {
+            // Members recorded while the struct had no name yet
+            let id = parsed.entities[index].id.clone();
+            let members: Vec<String> = parsed
+                .relationships
+                .iter()
+                .filter(|r| r.from_entity == id && r.relationship_type == RelationType::Contains)
+                .map(|r| r.to_entity.clone())
+                .collect();
+            for member in parsed.entities.iter_mut().filter(|e| members.contains(&e.id)) {
+                member.metadata.insert("enclosing".to_string(), alias.clone());
+            }
+            parsed.entities[index].name = alias;
+        } else {
+            let typedef = parsed.push(
+                line_entity(&alias, EntityType::Type, file_path, line_num, line)
+                    .with_metadata("kind".to_string(), "typedef".to_string()),
+            );
+            if let Some(parent) = parsed.entities[index].metadata.get("enclosing").cloned() {
+                parsed.set_metadata(typedef, "enclosing", parent);
+            }
+        }
+    }
+
+    /// Function declarations and definitions. Returns the signature when its
+    /// parameter list continues on the next line.
+    fn parse_function(
+        &self,
+        parsed: &mut ParsedFile,
+        tracker: &mut ScopeTracker,
+        context: &Context,
+        declaration: &str,
+        return_type_line: Option<&str>,
+        is_header: bool,
+    ) -> Option<OpenSignature> {
+        let captures = self.function_regex.captures(declaration)?;
+        let prefix = match captures[1].trim() {
+            "" => return_type_line.unwrap_or(""),
+            prefix => prefix,
+        };
+        // `Box<T>::get` is looked up as `Box::get`
+        let full_name = match captures[2].rsplit_once("::") {
+            Some((qualifier, name)) => format!("{}::{}", without_template_args(qualifier), name),
+            None => captures[2].to_string(),
+        };
+        let full_name = full_name.as_str();
+        let (qualifier, name) = match full_name.rsplit_once("::") {
+            Some((qualifier, name)) => (Some(qualifier), name),
+            None => (None, full_name),
+        };
+
+        if NOT_FUNCTION_NAMES.contains(&name)
+            || prefix.contains('=')
+            || prefix.ends_with('.')
+            || prefix.ends_with("->")
+            || prefix.split_whitespace().any(|word| NOT_DECLARATION_PREFIXES.contains(&word))
+        {
+            return None;
+        }
+        // Without a return type only constructors, destructors and qualified definitions declare anything
+        let is_constructor = context.in_type && (Some(name) == context.parent_name || name.starts_with('~'));
+        if prefix.is_empty() && !(is_constructor || qualifier.is_some() || name.starts_with('~')) {
+            return None;
+        }
+
+        let kind = if is_constructor || qualifier.map(|q| q.rsplit("::").next() == Some(name.trim_start_matches('~'))).unwrap_or(false) {
+            if name.starts_with('~') { "destructor" } else { "constructor" }
+        } else if context.in_type || qualifier.is_some() {
+            "method"
+        } else {
+            "function"
+        };
+
+        let mut entity = line_entity(name, EntityType::Function, context.file_path, context.line_num, context.line)
+            .with_metadata("kind".to_string(), kind.to_string());
+        if prefix.split_whitespace().any(|word| word == "static") {
+            entity = entity.with_metadata("static".to_string(), "true".to_string());
+        }
+        if prefix.split_whitespace().any(|word| word == "virtual") {
+            entity = entity.with_metadata("virtual".to_string(), "true".to_string());
+        }
+        let index = parsed.push(entity);
+        context.attach(parsed, index);
+        if let Some(qualifier) = qualifier {
+            // `void Foo::bar()` belongs to `Foo`, declared elsewhere
+            let owner = qualifier.rsplit("::").next().unwrap_or(qualifier);
+            parsed.set_metadata(index, "enclosing", owner.to_string());
+        }
+
+        // The name a declaration elsewhere would be found under
+        let symbol = match (qualifier, context.parent_name) {
+            (Some(_), _) => full_name.to_string(),
+            (None, Some(parent)) => format!("{}::{}", parent, name),
+            (None, None) => name.to_string(),
+        };
+
+        let params_start = captures.get(0)?.end() - 1;
+        let signature = OpenSignature {
+            entity: index,
+            text: declaration[params_start..].to_string(),
+            is_member: context.in_type,
+            symbol,
+            lines_waited: 0,
+        };
+        match signature.rest() {
+            Some(rest) => {
+                self.finish_signature(parsed, tracker, index, rest, signature.is_member, &signature.symbol, is_header);
+                None
+            }
+            None => Some(signature),
+        }
+    }
+
+    /// Decide between declaration and definition from what follows the parameters
+    #[allow(clippy::too_many_arguments)]
+    fn finish_signature(
+        &self,
+        parsed: &mut ParsedFile,
+        tracker: &mut ScopeTracker,
+        index: usize,
+        rest: &str,
+        is_member: bool,
+        symbol: &str,
+        is_header: bool,
+    ) {
+        let body_start = rest.find('{');
+        let statement_end = rest.find(';');
+        let is_declaration = match (body_start, statement_end) {
+            (Some(body), Some(end)) => end < body,
+            (None, Some(_)) => true,
+            // The body (or a constructor's initializer list) starts on a later line
+            (_, None) => false,
+        };
+
+        if is_declaration {
+            parsed.set_metadata(index, "declaration", "true".to_string());
+            if rest.contains("= 0") {
+                parsed.set_metadata(index, "abstract", "true".to_string());
+            }
+            return;
+        }
+
+        let name = parsed.entities[index].name.clone();
+        tracker.expect_body(&name, index, ScopeKind::Function);
+
+        // Out-of-line definitions in source files implement a declaration from a header
+        let is_static = parsed.entities[index].metadata.contains_key("static");
+        if !is_header && !is_member && !is_static {
+            parsed.link_symbol(index, symbol, RelationType::Defines);
+        }
+    }
+
+    /// Global variables, namespace-level constants and struct/class fields
+    fn parse_variable(&self, parsed: &mut ParsedFile, context: &Context, declaration: &str) -> bool {
+        let Some(captures) = self.variable_regex.captures(declaration) else { return false };
+        // `bool operator==(...)` reads like `bool operator = ...`
+        if NOT_VARIABLE_TYPES.contains(&&captures[2]) || &captures[3] == "operator" {
+            return false;
+        }
+
+        let modifiers = &captures[1];
+        let is_const = modifiers.split_whitespace().any(|m| m == "const" || m == "constexpr");
+        let entity_type = if is_const { EntityType::Constant } else { EntityType::Variable };
+        let kind = if context.in_type { "field" } else { "variable" };
+        let mut entity = line_entity(&captures[3], entity_type, context.file_path, context.line_num, context.line)
+            .with_metadata("kind".to_string(), kind.to_string());
+        if modifiers.split_whitespace().any(|m| m == "extern") {
+            entity = entity.with_metadata("declaration".to_string(), "true".to_string());
+        }
+        let index = parsed.push(entity);
+        context.attach(parsed, index);
+        true
+    }
+}
+
+/// Where a declaration line sits
+struct Context<'a> {
+    parent: Option<usize>,
+    parent_name: Option<&'a str>,
+    in_type: bool,
+    file_path: &'a str,
+    line_num: usize,
+    line: &'a str,
+}
+
+impl Context<'_> {
+    fn attach(&self, parsed: &mut ParsedFile, index: usize) {
+        if let Some(parent) = self.parent {
+            parsed.contain(parent, index);
+        }
+    }
+}
+
+/// `ns::Box<T, N>` -> `ns::Box`
+fn without_template_args(qualifier: &str) -> String {
+    let mut depth = 0;
+    qualifier
+        .chars()
+        .filter(|&c| {
+            match c {
+                '<' => depth += 1,
+                '>' => depth -= 1,
+                _ => return depth == 0,
+            }
+            false
+        })
+        .collect()
+}
+
+/// Name introduced by a one-line `typedef`, including function pointer typedefs
+fn typedef_name(declaration: &str) -> Option<&str> {
+    let declaration = declaration.trim_end().strip_suffix(';')?;
+    if let Some(start) = declaration.find("(*") {
+        let name = declaration[start + 2..].split(')').next()?.trim();
+        return Some(name).filter(|name| !name.is_empty());
+    }
+
+    let declaration = declaration.split('[').next()?;
+    declaration
+        .rsplit(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .find(|word| !word.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_support::{resolve_files, ParsedSource};
+
+    const HEADER: &str = r#"#ifndef SHAPES_H
+#define SHAPES_H
+
+#include <vector>
+#include "util/math.h"
+
+#define MAX_SHAPES 64
+#define SQUARE(x) ((x) * (x))
+
+namespace geo {
+
+typedef struct {
+    double x;
+    double y;
+} point_t;
+
+typedef void (*visit_fn)(int);
+using Shapes = std::vector<Shape*>;
+
+enum class Color { Red, Green };
+
+class Shape {
+public:
+    virtual ~Shape();
+    virtual double area() const = 0;
+};
+
+class Circle : public Shape, private Named {
+public:
+    Circle(double r);
+    double area() const override;
+    double radius() const { return r_; }
+private:
+    double r_;
+};
+
+double distance(point_t a, point_t b);
+void *copy(void *dest, const void *src)
+    __attribute__((nonnull(1, 2)));
+
+}  // namespace geo
+
+#endif
+"#;
+
+    const SOURCE: &str = r#"#include "shapes.h"
+
+namespace {
+int counter = 0;
+}
+
+static int
+helper(int value)
+{
+    return value * 2;
+}
+
+geo::Circle::Circle(double r) : r_(r) {
+    counter++;
+}
+
+double geo::Circle::area() const {
+    return 3.14 * r_ * r_;
+}
+
+namespace geo {
+double distance(point_t a,
+                point_t b) {
+    if (a.x > b.x) {
+        return a.x - b.x;
+    }
+    return b.x - a.x;
+}
+}
+"#;
+
+    fn parser() -> Result<CppParser> {
+        let mut parser = CppParser::new()?;
+        parser.set_include_dirs(vec!["include".to_string()]);
+        Ok(parser)
+    }
+
+    #[test]
+    fn test_cpp_header_entities() -> Result<()> {
+        let file = ParsedSource::new(&parser()?, "include/shapes.h", HEADER)?;
+
+        assert_eq!(file.meta("shapes.h", "kind"), Some("header"));
+        assert!(!file.has("SHAPES_H"), "include guards are skipped");
+        assert_eq!(file.find("MAX_SHAPES").entity_type, EntityType::Constant);
+        assert_eq!(file.find("SQUARE").entity_type, EntityType::Function);
+        assert_eq!(file.meta("vector", "system"), Some("true"));
+
+        assert_eq!(file.meta("geo", "kind"), Some("namespace"));
+        assert_eq!(file.find("point_t").entity_type, EntityType::Class);
+        assert_eq!(file.meta("x", "enclosing"), Some("point_t"));
+        assert_eq!(file.find("visit_fn").entity_type, EntityType::Type);
+        assert_eq!(file.meta("Shapes", "kind"), Some("alias"));
+        assert_eq!(file.meta("Color", "kind"), Some("enum"));
+
+        assert_eq!(file.meta("Circle", "enclosing"), Some("geo"));
+        assert_eq!(file.meta("area", "abstract"), Some("true"));
+        assert_eq!(file.meta("~Shape", "kind"), Some("destructor"));
+        assert_eq!(file.meta("radius", "declaration"), None);
+        assert_eq!(file.meta("r_", "kind"), Some("field"));
+        assert_eq!(file.meta("distance", "declaration"), Some("true"));
+        assert_eq!(file.meta("distance", "enclosing"), Some("geo"));
+        assert_eq!(file.meta("copy", "declaration"), Some("true"));
+        let circle_ctor = file.find_typed("Circle", EntityType::Function);
+        assert_eq!(circle_ctor.metadata.get("declaration").map(String::as_str), Some("true"));
+
+        let extends: Vec<_> = file.relationships.iter().filter(|r| r.relationship_type == RelationType::Extends).map(|r| r.to_entity.as_str()).collect();
+        assert_eq!(extends, vec!["Shape", "Named"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cpp_definitions_link_to_header() -> Result<()> {
+        let memory = resolve_files(
+            &parser()?,
+            &[("include/shapes.h", HEADER), ("src/shapes.cpp", SOURCE), ("include/util/math.h", "int add(int a, int b);\n")],
+        )?;
+
+        let by_id = |id: &str| memory.entities.get(id);
+        let edges: Vec<(String, &str, String)> = memory
+            .relationships
+            .iter()
+            .filter(|r| matches!(r.relationship_type, RelationType::Defines | RelationType::Imports) && r.is_resolved())
+            .filter_map(|r| {
+                let from = by_id(&r.from_entity)?;
+                let to = by_id(&r.to_entity)?;
+                Some((format!("{}:{}", from.file_path, from.name), r.relationship_type.as_str(), format!("{}:{}", to.file_path, to.name)))
+            })
+            .collect();
+
+        let expect = |from: &str, kind: &str, to: &str| {
+            assert!(
+                edges.iter().any(|(f, k, t)| f == from && *k == kind && t == to),
+                "missing {} -{}-> {} in {:?}",
+                from, kind, to, edges
+            );
+        };
+        expect("src/shapes.cpp:shapes.cpp", "imports", "include/shapes.h:shapes.h");
+        expect("include/shapes.h:shapes.h", "imports", "include/util/math.h:math.h");
+        expect("src/shapes.cpp:Circle", "defines", "include/shapes.h:Circle");
+        expect("src/shapes.cpp:area", "defines", "include/shapes.h:area");
+        expect("src/shapes.cpp:distance", "defines", "include/shapes.h:distance");
+
+        let source = |name: &str| memory.entities.values().find(|e| e.file_path == "src/shapes.cpp" && e.name == name).unwrap();
+        assert_eq!(source("counter").entity_type, EntityType::Variable);
+        assert_eq!(source("helper").metadata.get("static").map(String::as_str), Some("true"));
+        assert_eq!((source("distance").line_start, source("distance").line_end), (22, 28));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cpp_templates_and_operators() -> Result<()> {
+        let source = r#"namespace app::core {
+
+template <typename T>
+class Box : public Base<T> {
+public:
+    bool operator==(const Box& other) const;
+    T get() const { return value_; }
+private:
+    T value_;
+};
+
+}
+
+const char* open_brace() {
+    return "{";
+}
+
+template <typename T>
+bool app::core::Box<T>::operator==(const Box& other) const {
+    return value_ == other.value_;
+}
+"#;
+        let file = ParsedSource::new(&parser()?, "src/box.cpp", source)?;
+        assert_eq!(file.meta("Box", "enclosing"), Some("app::core"));
+        assert_eq!(file.edges("Box"), vec![("extends", "Base")]);
+        // An operator declaration is not a field named `operator`
+        assert_eq!(file.members("Box"), vec!["get", "operator==", "value_"]);
+        assert_eq!(file.find("open_brace").line_end, 16);
+
+        // Template arguments in the qualifier do not hide the owning class
+        let definition = file.entities.iter().find(|e| e.name == "operator==" && e.line_start == 19).unwrap();
+        assert_eq!(definition.metadata.get("enclosing").map(String::as_str), Some("Box"));
+        assert_eq!(definition.metadata.get("kind").map(String::as_str), Some("method"));
+        let defines = file.relationships.iter().find(|r| r.from_entity == definition.id).unwrap();
+        assert_eq!(defines.target_name(), Some("app::core::Box::operator=="));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,515 @@
+use super::parsed::ParsedFile;
+use super::signature::{record_signatures, SignatureStyle};
+use super::scope::{ScopeKind, ScopeTracker};
+use super::{line_entity, split_balanced, split_top_level, strip_generics};
+use crate::{CodeEntity, EntityType, RelationType, Relationship};
+use anyhow::Result;
+use regex::Regex;
+
+pub(crate) struct CSharpParser {
+    using_regex: Regex,
+    namespace_regex: Regex,
+    type_regex: Regex,
+    method_regex: Regex,
+    constructor_regex: Regex,
+    property_regex: Regex,
+    event_regex: Regex,
+    field_regex: Regex,
+}
+
+impl CSharpParser {
+    pub(crate) fn new() -> Result<Self> {
+        Ok(Self {
+            using_regex: Regex::new(r"^\s*(?:global\s+)?using\s+(static\s+)?(?:(\w+)\s*=\s*)?([\w.]+(?:<[^;]*>)?)\s*;")?,
+            namespace_regex: Regex::new(r"^\s*namespace\s+([\w.]+)\s*(;)?")?,
+            type_regex: Regex::new(
+                r"^\s*((?:(?:public|private|protected|internal|static|abstract|sealed|partial|readonly|ref|unsafe|new|file)\s+)*)(class|interface|struct|enum|record(?:\s+struct|\s+class)?)\s+(\w+)",
+            )?,
+            method_regex: Regex::new(
+                r"^\s*((?:(?:public|private|protected|internal|static|virtual|override|abstract|sealed|async|extern|unsafe|new|partial|readonly)\s+)*)([\w.]+(?:<[^()]*?>)?(?:\[\])*\??)\s+(?:[\w.]+\.)?(\w+)\s*(?:<[^()]*>)?\s*\(",
+            )?,
+            constructor_regex: Regex::new(r"^\s*((?:(?:public|private|protected|internal|static)\s+)*)(~?\w+)\s*\(")?,
+            property_regex: Regex::new(
+                r"^\s*((?:(?:public|private|protected|internal|static|virtual|override|abstract|sealed|required|readonly|new)\s+)*)([\w.]+(?:<[^()]*?>)?(?:\[\])*\??)\s+(?:[\w.]+\.)?(\w+)\s*(\{|=>|$)",
+            )?,
+            event_regex: Regex::new(
+                r"^\s*((?:(?:public|private|protected|internal|static|virtual|override|abstract|sealed|new)\s+)*)event\s+[\w.]+(?:<[^()]*?>)?\??\s+(\w+)\s*(\{|=|;|$)",
+            )?,
+            field_regex: Regex::new(
+                r"^\s*((?:(?:public|private|protected|internal|static|readonly|const|volatile|new)\s+)*)([\w.]+(?:<[^()]*?>)?(?:\[\])*\??)\s+(\w+)\s*(?:=|;|,)",
+            )?,
+        })
+    }
+
+    pub(crate) fn parse(&self, content: &str, file_path: &str) -> Result<(Vec<CodeEntity>, Vec<Relationship>)> {
+        let mut parsed = ParsedFile::default();
+        let mut tracker = ScopeTracker::new().with_verbatim_strings();
+        let mut imports = Vec::new();
+        let mut first_namespace = None;
+        // `namespace Acme.Orders;` applies to the rest of the file
+        let mut file_namespace: Option<(usize, String)> = None;
+        let mut attributes: Vec<String> = Vec::new();
+
+        for (line_num, line) in content.lines().enumerate() {
+            let trimmed = line.trim_start();
+            let enclosing = tracker
+                .current_body()
+                .filter(|scope| scope.kind != ScopeKind::Function)
+                .and_then(|scope| Some((scope.entity?, scope.name.clone(), scope.kind)));
+            let is_comment = tracker.in_block_comment() || tracker.in_string() || trimmed.starts_with("//") || trimmed.starts_with("/*") || trimmed.starts_with('*');
+
+            if !is_comment && !trimmed.starts_with('#') && (tracker.depth() == 0 || enclosing.is_some()) {
+                let (found, declaration) = split_attributes(line);
+                attributes.extend(found);
+
+                if !declaration.trim().is_empty() {
+                    let parent = enclosing
+                        .as_ref()
+                        .map(|(parent, _, _)| *parent)
+                        .or(file_namespace.as_ref().map(|(index, _)| *index));
+                    let mut container: Vec<&str> = file_namespace.iter().map(|(_, name)| name.as_str()).collect();
+                    container.extend(tracker.path());
+                    let container = container.join(".");
+
+                    let entity = if let Some(captures) = self.using_regex.captures(declaration).filter(|_| tracker.depth() == 0 || enclosing.as_ref().map(|e| e.2 == ScopeKind::Namespace).unwrap_or(false)) {
+                        let mut entity = line_entity(&captures[3], EntityType::Import, file_path, line_num, line);
+                        if let Some(alias) = captures.get(2) {
+                            entity = entity.with_metadata("alias".to_string(), alias.as_str().to_string());
+                        }
+                        if captures.get(1).is_some() {
+                            entity = entity.with_metadata("static".to_string(), "true".to_string());
+                        }
+                        imports.push(parsed.push(entity));
+                        None
+                    } else if let Some(captures) = self.namespace_regex.captures(declaration) {
+                        let name = &captures[1];
+                        let index = parsed.push(
+                            line_entity(name, EntityType::Module, file_path, line_num, line)
+                                .with_metadata("kind".to_string(), "namespace".to_string()),
+                        );
+                        if let Some(parent) = parent {
+                            parsed.contain(parent, index);
+                        }
+                        first_namespace.get_or_insert(index);
+                        if captures.get(2).is_some() {
+                            file_namespace = Some((index, name.to_string()));
+                        } else {
+                            tracker.expect_body(name, index, ScopeKind::Namespace);
+                        }
+                        None
+                    } else if let Some(captures) = self.type_regex.captures(declaration) {
+                        Some(self.parse_type(&mut parsed, &mut tracker, &captures, declaration, parent, &container, file_path, line_num, line))
+                    } else if let Some((parent, parent_name, ScopeKind::Type)) = &enclosing {
+                        self.parse_member(&mut parsed, &mut tracker, declaration, *parent, parent_name, file_path, line_num, line)
+                    } else {
+                        None
+                    };
+
+                    if let Some(index) = entity {
+                        if !attributes.is_empty() {
+                            parsed.set_metadata(index, "attributes", attributes.join(","));
+                        }
+                    }
+                    attributes.clear();
+                }
+            }
+
+            for closed in tracker.process_line(line) {
+                parsed.set_line_end(closed, line_num);
+            }
+        }
+
+        if let Some((index, _)) = file_namespace {
+            parsed.set_line_end(index, content.lines().count().saturating_sub(1));
+        }
+        if let Some(namespace) = first_namespace {
+            for import in imports {
+                parsed.link(namespace, import, RelationType::Imports);
+            }
+        }
+
+        record_signatures(&mut parsed.entities, content, SignatureStyle::CFamily);
+        Ok(parsed.finish())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn parse_type(
+        &self,
+        parsed: &mut ParsedFile,
+        tracker: &mut ScopeTracker,
+        captures: &regex::Captures,
+        declaration: &str,
+        parent: Option<usize>,
+        container: &str,
+        file_path: &str,
+        line_num: usize,
+        line: &str,
+    ) -> usize {
+        let modifiers = &captures[1];
+        let keyword = captures[2].split_whitespace().collect::<Vec<_>>().join(" ");
+        let name = &captures[3];
+        let entity_type = if keyword == "interface" { EntityType::Interface } else { EntityType::Class };
+
+        let mut entity = line_entity(name, entity_type.clone(), file_path, line_num, line)
+            .with_metadata("kind".to_string(), keyword.clone());
+        if !container.is_empty() {
+            entity = entity.with_metadata("namespace".to_string(), container.to_string());
+        }
+        for modifier in ["partial", "abstract", "static", "sealed"] {
+            if modifiers.split_whitespace().any(|m| m == modifier) {
+                entity = entity.with_metadata(modifier.to_string(), "true".to_string());
+            }
+        }
+        let index = parsed.push(entity);
+        if let Some(parent) = parent {
+            parsed.contain(parent, index);
+        }
+        tracker.expect_body(name, index, ScopeKind::Type);
+
+        let mut rest = &declaration[captures.get(0).map(|m| m.end()).unwrap_or(0)..];
+        if let Some((_, after_generics)) = split_balanced(rest, '<', '>') {
+            rest = after_generics;
+        }
+        // Positional records declare their properties in the header
+        if keyword.starts_with("record") {
+            if let Some((parameters, after_parameters)) = split_balanced(rest, '(', ')') {
+                for parameter in split_top_level(parameters) {
+                    let (_, parameter) = split_attributes(parameter);
+                    let parameter = parameter.split('=').next().unwrap_or(parameter);
+                    if let Some(property) = parameter.split_whitespace().last() {
+                        let property = parsed.push(
+                            line_entity(property, EntityType::Variable, file_path, line_num, line)
+                                .with_metadata("kind".to_string(), "property".to_string()),
+                        );
+                        parsed.contain(index, property);
+                    }
+                }
+                rest = after_parameters;
+            }
+        }
+
+        // Enums list an underlying type, not base types
+        if keyword == "enum" {
+            return index;
+        }
+        if let Some(bases) = rest.trim_start().strip_prefix(':') {
+            let bases = bases.split(['{', ';']).next().unwrap_or(bases);
+            let bases = bases.split(" where ").next().unwrap_or(bases);
+            for (position, base) in split_top_level(bases).into_iter().enumerate() {
+                let base = strip_generics(base.split('(').next().unwrap_or(base));
+                // Only a class or record's first base can be a class; `IName` is the interface convention
+                let relationship_type = if entity_type == EntityType::Interface
+                    || (position == 0 && !keyword.contains("struct") && !looks_like_interface(base))
+                {
+                    RelationType::Extends
+                } else {
+                    RelationType::Implements
+                };
+                parsed.link_symbol(index, base, relationship_type);
+            }
+        }
+
+        index
+    }
+
+    /// Methods, constructors, properties and fields declared in a type body
+    #[allow(clippy::too_many_arguments)]
+    fn parse_member(
+        &self,
+        parsed: &mut ParsedFile,
+        tracker: &mut ScopeTracker,
+        declaration: &str,
+        parent: usize,
+        parent_name: &str,
+        file_path: &str,
+        line_num: usize,
+        line: &str,
+    ) -> Option<usize> {
+        let (name, kind, entity_type, modifiers, expects_body) = if let Some(captures) = self.constructor_regex.captures(declaration)
+            .filter(|c| c[2].trim_start_matches('~') == parent_name)
+        {
+            let kind = if captures[2].starts_with('~') { "finalizer" } else { "constructor" };
+            let rest = &declaration[captures.get(0)?.end() - 1..];
+            (captures[2].to_string(), kind, EntityType::Function, captures[1].to_string(), has_block_body(rest))
+        } else if let Some(captures) = self.method_regex.captures(declaration).filter(|c| !is_keyword(&c[2])) {
+            let rest = &declaration[captures.get(0)?.end() - 1..];
+            (captures[3].to_string(), "method", EntityType::Function, captures[1].to_string(), has_block_body(rest))
+        } else if let Some(captures) = self.event_regex.captures(declaration) {
+            // `{ add; remove; }` accessors are skipped like a property's
+            let expects_body = !matches!(&captures[3], "=" | ";");
+            (captures[2].to_string(), "event", EntityType::Variable, captures[1].to_string(), expects_body)
+        } else if let Some(captures) = self.property_regex.captures(declaration).filter(|c| !is_keyword(&c[2])) {
+            // `{ get; set; }` and `=> value` bodies are skipped like method bodies
+            let expects_body = &captures[4] != "=>";
+            (captures[3].to_string(), "property", EntityType::Variable, captures[1].to_string(), expects_body)
+        } else if let Some(captures) = self.field_regex.captures(declaration).filter(|c| !is_keyword(&c[2])) {
+            let modifiers = captures[1].to_string();
+            let entity_type = if modifiers.split_whitespace().any(|m| m == "const") { EntityType::Constant } else { EntityType::Variable };
+            (captures[3].to_string(), "field", entity_type, modifiers, false)
+        } else {
+            return None;
+        };
+
+        let mut entity = line_entity(&name, entity_type, file_path, line_num, line)
+            .with_metadata("kind".to_string(), kind.to_string());
+        for modifier in ["static", "async", "abstract", "override", "virtual"] {
+            if modifiers.split_whitespace().any(|m| m == modifier) {
+                entity = entity.with_metadata(modifier.to_string(), "true".to_string());
+            }
+        }
+        let index = parsed.push(entity);
+        parsed.contain(parent, index);
+        if expects_body {
+            tracker.expect_body(&name, index, ScopeKind::Function);
+        }
+        Some(index)
+    }
+}
+
+/// Merge key for the parts of a `partial` type spread over several files
+pub(crate) fn partial_type_key(entity: &CodeEntity) -> Option<String> {
+    if entity.metadata.get("partial").map(String::as_str) != Some("true") {
+        return None;
+    }
+    Some(match entity.metadata.get("namespace") {
+        Some(namespace) => format!("{}.{}", namespace, entity.name),
+        None => entity.name.clone(),
+    })
+}
+
+/// Strip leading `[Attribute(...)]` groups, returning the attribute names and the rest
+fn split_attributes(line: &str) -> (Vec<String>, &str) {
+    let mut names = Vec::new();
+    let mut rest = line.trim_start();
+
+    while rest.starts_with('[') {
+        let Some((group, after)) = split_balanced(rest, '[', ']') else {
+            return (names, "");
+        };
+        for attribute in split_top_level(group) {
+            let attribute = attribute.split('(').next().unwrap_or(attribute).trim();
+            // `[return: NotNull]` targets keep just the attribute name
+            let attribute = attribute.rsplit(':').next().unwrap_or(attribute).trim();
+            if !attribute.is_empty() {
+                names.push(attribute.to_string());
+            }
+        }
+        rest = after.trim_start();
+    }
+
+    (names, rest)
+}
+
+fn looks_like_interface(name: &str) -> bool {
+    let name = name.rsplit('.').next().unwrap_or(name);
+    let mut chars = name.chars();
+    chars.next() == Some('I') && chars.next().map(|c| c.is_ascii_uppercase()).unwrap_or(false)
+}
+
+// Statements that the member patterns would otherwise read as `type name`
+fn is_keyword(word: &str) -> bool {
+    matches!(word, "return" | "using" | "namespace" | "new" | "throw" | "await" | "yield" | "else" | "case" | "event" | "delegate" | "operator")
+}
+
+// `Foo() => expr;` and `Foo();` have no braced body
+fn has_block_body(rest: &str) -> bool {
+    match split_balanced(rest, '(', ')') {
+        Some((_, after)) => !after.contains("=>") && !after.trim_end().ends_with(';'),
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_support::{resolve_files, ParsedSource};
+
+    const ORDER: &str = r#"using System;
+using System.Collections.Generic;
+using Json = System.Text.Json.JsonSerializer;
+
+namespace Acme.Orders
+{
+    [Serializable]
+    public partial class Order : EntityBase, IComparable<Order>, IDisposable
+    {
+        public const int MaxLines = 50;
+        private readonly List<Line> _lines = new();
+
+        public Order(Guid id)
+        {
+            Id = id;
+        }
+
+        public Guid Id { get; }
+        public decimal Total => _lines.Sum(l => l.Price);
+
+        [HttpGet("total")]
+        public async Task<decimal> ComputeAsync(int retries)
+        {
+            if (retries > 0) { return 0; }
+            return Total;
+        }
+
+        public int CompareTo(Order other) => Total.CompareTo(other.Total);
+
+        public class Line
+        {
+            public decimal Price { get; set; }
+        }
+    }
+
+    public interface IOrderRepository : IRepository<Order>
+    {
+        Order Find(Guid id);
+    }
+
+    public record OrderPlaced(Guid OrderId, DateTime At) : DomainEvent;
+
+    public enum Status : byte { Open, Closed }
+}
+"#;
+
+    const ORDER_PARTIAL: &str = r#"namespace Acme.Orders;
+
+public partial class Order : IValidatable
+{
+    public bool Validate() { return true; }
+}
+
+public readonly record struct Money(decimal Amount, string Currency);
+"#;
+
+    #[test]
+    fn test_csharp_entities() -> Result<()> {
+        let file = ParsedSource::new(&CSharpParser::new()?, "src/Order.cs", ORDER)?;
+
+        assert_eq!(file.meta("System.Text.Json.JsonSerializer", "alias"), Some("Json"));
+        assert_eq!(file.meta("Acme.Orders", "kind"), Some("namespace"));
+        assert_eq!(file.meta("Order", "partial"), Some("true"));
+        assert_eq!(file.meta("Order", "namespace"), Some("Acme.Orders"));
+        assert_eq!(file.meta("Order", "attributes"), Some("Serializable"));
+        assert_eq!((file.find("Order").line_start, file.find("Order").line_end), (8, 34));
+
+        assert_eq!(file.find("MaxLines").entity_type, EntityType::Constant);
+        assert_eq!(file.meta("_lines", "kind"), Some("field"));
+        let constructor = file.find_typed("Order", EntityType::Function);
+        assert_eq!(constructor.metadata.get("kind").map(String::as_str), Some("constructor"));
+        assert_eq!(file.meta("Id", "kind"), Some("property"));
+        assert_eq!(file.meta("Total", "kind"), Some("property"));
+        assert_eq!(file.meta("ComputeAsync", "async"), Some("true"));
+        assert_eq!(file.meta("ComputeAsync", "attributes"), Some("HttpGet"));
+        assert_eq!(file.meta("CompareTo", "kind"), Some("method"));
+        assert_eq!(file.meta("Line", "namespace"), Some("Acme.Orders.Order"));
+        assert_eq!(file.meta("Price", "enclosing"), Some("Line"));
+        assert_eq!(file.meta("Find", "enclosing"), Some("IOrderRepository"));
+        assert_eq!(file.meta("OrderId", "enclosing"), Some("OrderPlaced"));
+        assert_eq!(file.meta("Status", "kind"), Some("enum"));
+
+        assert_eq!(file.edges("Order"), vec![("extends", "EntityBase"), ("implements", "IComparable"), ("implements", "IDisposable")]);
+        assert_eq!(file.edges("IOrderRepository"), vec![("extends", "IRepository")]);
+        assert_eq!(file.edges("OrderPlaced"), vec![("extends", "DomainEvent")]);
+        assert!(file.edges("Status").is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_csharp_constraints_events_and_strings() -> Result<()> {
+        let source = r#"namespace Acme.Data
+{
+    public class Repo<T> : IRepo<T>, IDisposable where T : class, new()
+    {
+        private readonly string _open = "{";
+        private readonly string _template = $"{Name} }}";
+
+        public event EventHandler Changed;
+        public event EventHandler<int> Moved
+        {
+            add { }
+            remove { }
+        }
+
+        public T Get(int id) { return default; }
+    }
+
+    internal static class Extensions
+    {
+        public static string Slug(this string value) => value;
+    }
+}
+"#;
+        let file = ParsedSource::new(&CSharpParser::new()?, "src/Repo.cs", source)?;
+        // `where` constraints are not base types
+        assert_eq!(file.edges("Repo"), vec![("implements", "IDisposable"), ("implements", "IRepo")]);
+        assert_eq!(file.find("Repo").line_end, 16);
+        assert_eq!(file.members("Repo"), vec!["Changed", "Get", "Moved", "_open", "_template"]);
+        assert_eq!(file.meta("Changed", "kind"), Some("event"));
+        // Event accessors are skipped like a property's
+        assert_eq!(file.find("Moved").line_end, 13);
+        assert_eq!(file.meta("Extensions", "static"), Some("true"));
+        assert_eq!(file.meta("Slug", "static"), Some("true"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_csharp_verbatim_strings() -> Result<()> {
+        let source = r#"namespace App
+{
+    public static class Paths
+    {
+        public static bool IsRoot(string path) { return path == @"C:\"; }
+        public const string Quoted = @"say ""{"" twice";
+        public static string Home(string user) => $@"C:\Users\{user}\";
+        public const string Banner = @"
+} banner
+";
+
+        public class Other { }
+    }
+
+    public class Next { }
+}
+"#;
+        let file = ParsedSource::new(&CSharpParser::new()?, "src/Paths.cs", source)?;
+        assert_eq!(file.members("Paths"), vec!["Banner", "Home", "IsRoot", "Other", "Quoted"]);
+        assert_eq!(file.find("IsRoot").line_end, 5);
+        assert_eq!(file.find("Paths").line_end, 13);
+        assert_eq!(file.meta("Other", "namespace"), Some("App.Paths"));
+        assert_eq!(file.meta("Next", "namespace"), Some("App"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_partial_classes_merge_across_files() -> Result<()> {
+        let mut memory = resolve_files(&CSharpParser::new()?, &[("src/Order.cs", ORDER), ("src/OrderValidation.cs", ORDER_PARTIAL)])?;
+
+        let partial = memory.find_entities_in_file("src/OrderValidation.cs").into_iter().find(|e| e.name == "Order").unwrap();
+        assert_eq!(partial.metadata.get("namespace").map(String::as_str), Some("Acme.Orders"));
+        let money = memory.find_entities_in_file("src/OrderValidation.cs").into_iter().find(|e| e.name == "Money").unwrap();
+        assert_eq!(money.metadata.get("kind").map(String::as_str), Some("record struct"));
+
+        memory.merge_partial_types();
+        let order = memory
+            .entities
+            .values()
+            .find(|e| e.name == "Order" && e.entity_type == EntityType::Class && !e.metadata.contains_key("merged_into"))
+            .unwrap();
+        assert_eq!(order.file_path, "src/Order.cs");
+        assert_eq!(order.metadata.get("partial_files").map(String::as_str), Some("src/Order.cs,src/OrderValidation.cs"));
+
+        // Members and base types of every part hang off the primary part
+        let members: Vec<_> = memory.get_dependencies(&order.id).iter().map(|e| e.name.clone()).collect();
+        assert!(members.contains(&"Validate".to_string()));
+        assert!(members.contains(&"ComputeAsync".to_string()));
+        assert!(memory.relationships.iter().any(|r| r.from_entity == order.id && r.to_entity == "IValidatable"));
+
+        // Merging again replaces the derived edges instead of duplicating them
+        let count = memory.relationships.len();
+        memory.merge_partial_types();
+        assert_eq!(memory.relationships.len(), count);
+
+        Ok(())
+    }
+}
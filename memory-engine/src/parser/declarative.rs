@@ -0,0 +1,316 @@
+// Languages defined in `.aimemoryengine/languages/*.toml` (or `.yaml`) rather
+// than in Rust: each pattern is a regex whose named captures become entities
+// or relationships.
+
+use super::parsed::ParsedFile;
+use super::{line_entity, LanguageParser};
+use crate::config::build_globset;
+use crate::{CodeEntity, EntityType, RelationType, Relationship};
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+/// Directory under the memory dir holding language definitions
+pub const LANGUAGES_DIR: &str = "languages";
+
+/// A language definition file as written by the user
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LanguageDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    /// Globs matched against project-relative paths, e.g. `**/BUILD`
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// Regex matched against the content of files with an unknown extension
+    #[serde(default)]
+    pub detect: Option<String>,
+    #[serde(default)]
+    pub patterns: Vec<PatternDefinition>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PatternDefinition {
+    /// Label used in error messages; defaults to the pattern's position
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub regex: Option<String>,
+    #[serde(default)]
+    pub query: Option<String>,
+    /// Entity type created from the `name` capture
+    #[serde(default)]
+    pub entity: Option<String>,
+    /// Stored as the entity's `kind` metadata
+    #[serde(default)]
+    pub kind: Option<String>,
+    /// Relationship created to the symbol in the `target` capture
+    #[serde(default)]
+    pub relationship: Option<String>,
+}
+
+#[derive(Debug)]
+struct Pattern {
+    regex: Regex,
+    entity: Option<EntityType>,
+    kind: Option<String>,
+    relationship: Option<RelationType>,
+}
+
+/// A parser built from a [`LanguageDefinition`]
+#[derive(Debug)]
+pub struct DeclarativeParser {
+    language: String,
+    extensions: Vec<String>,
+    files: Vec<String>,
+    detect: Option<Regex>,
+    patterns: Vec<Pattern>,
+}
+
+impl DeclarativeParser {
+    /// Validate a definition and compile its patterns
+    pub fn new(definition: LanguageDefinition) -> Result<Self> {
+        let language = definition.name.trim().to_lowercase();
+        if language.is_empty() || language.contains(char::is_whitespace) {
+            return Err(anyhow!("`name` must be a single non-empty word, got '{}'", definition.name));
+        }
+        if definition.extensions.is_empty() && definition.files.is_empty() && definition.detect.is_none() {
+            return Err(anyhow!("language '{}' needs at least one of `extensions`, `files` or `detect`", language));
+        }
+        if definition.patterns.is_empty() {
+            return Err(anyhow!("language '{}' defines no `patterns`", language));
+        }
+        build_globset(&definition.files)?;
+
+        let detect = definition
+            .detect
+            .as_deref()
+            .map(|detect| Regex::new(detect).map_err(|e| anyhow!("invalid `detect` regex: {}", e)))
+            .transpose()?;
+
+        let patterns = definition
+            .patterns
+            .iter()
+            .enumerate()
+            .map(|(index, pattern)| {
+                let label = pattern.name.clone().unwrap_or_else(|| format!("#{}", index + 1));
+                compile_pattern(pattern).map_err(|e| anyhow!("pattern '{}': {}", label, e))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            language,
+            extensions: definition.extensions,
+            files: definition.files,
+            detect,
+            patterns,
+        })
+    }
+
+    /// Parse a definition from TOML or YAML, chosen by the file's extension
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let definition: LanguageDefinition = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => toml::from_str(&content).map_err(|e| anyhow!("{}", e))?,
+            Some("yaml") | Some("yml") => serde_yaml::from_str(&content).map_err(|e| anyhow!("{}", e))?,
+            _ => return Err(anyhow!("expected a .toml, .yaml or .yml file")),
+        };
+        Self::new(definition)
+    }
+
+    /// Load every definition in a `languages` directory, sorted by file name.
+    /// A missing directory defines no languages.
+    pub fn load_dir(dir: &Path) -> Result<Vec<Self>> {
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut paths: Vec<_> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| matches!(path.extension().and_then(|ext| ext.to_str()), Some("toml" | "yaml" | "yml")))
+            .collect();
+        paths.sort();
+
+        paths
+            .iter()
+            .map(|path| Self::from_file(path).map_err(|e| anyhow!("Invalid language definition {}: {}", path.display(), e)))
+            .collect()
+    }
+}
+
+fn compile_pattern(pattern: &PatternDefinition) -> Result<Pattern> {
+    if pattern.query.is_some() {
+        return Err(anyhow!("tree-sitter `query` patterns are not supported; use `regex`"));
+    }
+    let source = pattern.regex.as_deref().ok_or_else(|| anyhow!("missing `regex`"))?;
+    let regex = Regex::new(source).map_err(|e| anyhow!("invalid regex: {}", e))?;
+    let has_group = |group: &str| regex.capture_names().any(|name| name == Some(group));
+
+    let entity = pattern
+        .entity
+        .as_deref()
+        .map(|name| {
+            EntityType::from_str(&name.to_lowercase()).ok_or_else(|| {
+                anyhow!(
+                    "unknown entity type '{}' (expected one of: function, class, module, variable, import, export, interface, type, constant)",
+                    name
+                )
+            })
+        })
+        .transpose()?;
+    let relationship = pattern
+        .relationship
+        .as_deref()
+        .map(|name| {
+            RelationType::from_str(&name.to_lowercase()).ok_or_else(|| {
+                anyhow!(
+                    "unknown relationship type '{}' (expected one of: calls, imports, extends, implements, uses, defines, references, contains)",
+                    name
+                )
+            })
+        })
+        .transpose()?;
+
+    match (&entity, &relationship) {
+        (None, None) => return Err(anyhow!("needs an `entity` or a `relationship`")),
+        (Some(_), _) if !has_group("name") => return Err(anyhow!("regex needs a `(?P<name>...)` group for `entity`")),
+        (_, Some(_)) if !has_group("target") => return Err(anyhow!("regex needs a `(?P<target>...)` group for `relationship`")),
+        _ => {}
+    }
+
+    Ok(Pattern {
+        regex,
+        entity,
+        kind: pattern.kind.clone(),
+        relationship,
+    })
+}
+
+impl LanguageParser for DeclarativeParser {
+    fn language(&self) -> &str {
+        &self.language
+    }
+
+    fn extensions(&self) -> Vec<String> {
+        self.extensions.clone()
+    }
+
+    fn file_globs(&self) -> Vec<String> {
+        self.files.clone()
+    }
+
+    fn detect(&self, content: &str) -> bool {
+        self.detect.as_ref().map(|detect| detect.is_match(content)).unwrap_or(false)
+    }
+
+    fn parse(&self, content: &str, file_path: &str) -> Result<(Vec<CodeEntity>, Vec<Relationship>)> {
+        let mut parsed = ParsedFile::default();
+        // Relationship-only patterns link from the most recent entity
+        let mut current: Option<usize> = None;
+
+        for (line_num, line) in content.lines().enumerate() {
+            for pattern in &self.patterns {
+                for captures in pattern.regex.captures_iter(line) {
+                    let mut from = current;
+                    if let (Some(entity_type), Some(name)) = (&pattern.entity, captures.name("name")) {
+                        let mut entity = line_entity(name.as_str(), entity_type.clone(), file_path, line_num, line);
+                        if let Some(kind) = &pattern.kind {
+                            entity = entity.with_metadata("kind".to_string(), kind.clone());
+                        }
+                        let index = parsed.push(entity);
+                        current = Some(index);
+                        from = Some(index);
+                    }
+                    if let (Some(relationship), Some(target), Some(from)) = (&pattern.relationship, captures.name("target"), from) {
+                        parsed.link_symbol(from, target.as_str(), relationship.clone());
+                    }
+                }
+            }
+        }
+
+        Ok(parsed.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CodeParser;
+    use tempfile::TempDir;
+
+    const PROTO: &str = r#"
+name = "proto"
+extensions = ["proto"]
+files = ["**/protos/*.schema"]
+
+[[patterns]]
+name = "message"
+regex = '^\s*message\s+(?P<name>\w+)'
+entity = "class"
+kind = "message"
+
+[[patterns]]
+regex = '^\s*import\s+"(?P<name>[^"]+)"'
+entity = "import"
+
+[[patterns]]
+name = "field type"
+regex = '^\s+(?:repeated\s+)?(?P<target>[A-Z]\w*)\s+\w+\s*='
+relationship = "uses"
+"#;
+
+    #[test]
+    fn test_load_and_parse_definition() -> Result<()> {
+        let dir = TempDir::new()?;
+        fs::write(dir.path().join("proto.toml"), PROTO)?;
+        fs::write(dir.path().join("notes.md"), "ignored")?;
+        fs::write(
+            dir.path().join("build.yaml"),
+            "name: build\nfiles: ['**/BUILD']\npatterns:\n  - regex: '^(?P<name>\\w+)\\('\n    entity: function\n",
+        )?;
+
+        let mut parser = CodeParser::new()?;
+        for language in DeclarativeParser::load_dir(dir.path())? {
+            parser.register(language)?;
+        }
+        assert_eq!(parser.language_for_path("api/user.proto"), Some("proto"));
+        assert_eq!(parser.language_for_path("api/protos/user.schema"), Some("proto"));
+        assert_eq!(parser.language_for_path("services/api/BUILD"), Some("build"));
+
+        let source = "import \"common.proto\";\nmessage User {\n  Address address = 1;\n  repeated Tag tags = 2;\n  string name = 3;\n}\n";
+        let (entities, relationships) = parser.parse_source("api/user.proto", source)?;
+        let names: Vec<_> = entities.iter().map(|e| (e.entity_type.as_str(), e.name.as_str())).collect();
+        assert_eq!(names, vec![("import", "common.proto"), ("class", "User")]);
+        assert_eq!(entities[1].metadata.get("kind").map(String::as_str), Some("message"));
+        let targets: Vec<_> = relationships.iter().map(|r| (r.from_entity.as_str(), r.to_entity.as_str())).collect();
+        assert_eq!(targets, vec![(entities[1].id.as_str(), "Address"), (entities[1].id.as_str(), "Tag")]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_definitions_explain_the_problem() {
+        let error = |toml: &str| {
+            let definition: LanguageDefinition = toml::from_str(toml).unwrap();
+            DeclarativeParser::new(definition).unwrap_err().to_string()
+        };
+
+        let bad_regex = "name = 'x'\nextensions = ['x']\n[[patterns]]\nname = 'broken'\nregex = '(?P<name>\\w+'\nentity = 'class'\n";
+        assert!(error(bad_regex).starts_with("pattern 'broken': invalid regex"));
+
+        let bad_type = "name = 'x'\nextensions = ['x']\n[[patterns]]\nregex = '(?P<name>\\w+)'\nentity = 'struct'\n";
+        assert!(error(bad_type).starts_with("pattern '#1': unknown entity type 'struct'"));
+
+        let no_group = "name = 'x'\nextensions = ['x']\n[[patterns]]\nregex = '\\w+'\nrelationship = 'calls'\n";
+        assert!(error(no_group).contains("(?P<target>...)"));
+
+        let query = "name = 'x'\nextensions = ['x']\n[[patterns]]\nquery = '(function_item)'\nentity = 'function'\n";
+        assert!(error(query).contains("not supported"));
+
+        assert!(error("name = 'x'\n[[patterns]]\nregex = '(?P<name>x)'\nentity = 'class'\n").contains("`extensions`, `files` or `detect`"));
+    }
+}
@@ -0,0 +1,384 @@
+// Documentation comments: JSDoc/TSDoc blocks, rustdoc `///`/`//!` lines and
+// Python docstrings, split into a description and the tagged sections an
+// assistant cares about (parameters, return value, examples).
+
+use super::parsed::ParsedFile;
+
+/// A parsed documentation comment
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct DocComment {
+    pub description: String,
+    pub params: Vec<(String, String)>,
+    pub returns: Option<String>,
+    pub examples: Vec<String>,
+}
+
+impl DocComment {
+    pub fn is_empty(&self) -> bool {
+        self.description.is_empty() && self.params.is_empty() && self.returns.is_none() && self.examples.is_empty()
+    }
+
+    /// Store as `doc`, `doc_params` (`name: text` per line), `doc_returns` and `doc_examples`
+    pub fn apply(&self, parsed: &mut ParsedFile, index: usize) {
+        if self.is_empty() {
+            return;
+        }
+        if !self.description.is_empty() {
+            parsed.set_metadata(index, "doc", self.description.clone());
+        }
+        if !self.params.is_empty() {
+            let params: Vec<String> = self.params.iter().map(|(name, text)| format!("{}: {}", name, text)).collect();
+            parsed.set_metadata(index, "doc_params", params.join("\n"));
+        }
+        if let Some(returns) = &self.returns {
+            parsed.set_metadata(index, "doc_returns", returns.clone());
+        }
+        if !self.examples.is_empty() {
+            parsed.set_metadata(index, "doc_examples", self.examples.join("\n\n"));
+        }
+    }
+}
+
+/// Collects the doc comment lines seen since the last declaration
+#[derive(Debug, Default)]
+pub(crate) struct PendingDoc {
+    lines: Vec<String>,
+    in_block: bool,
+}
+
+impl PendingDoc {
+    /// Feed one line of a C-family file. Returns true when the line is part of
+    /// a doc comment (`/** */` or, with `slashes`, `///`) and holds no code.
+    pub fn feed(&mut self, line: &str, slashes: bool) -> bool {
+        let trimmed = line.trim();
+        if self.in_block {
+            let (text, closed) = match trimmed.find("*/") {
+                Some(end) => (&trimmed[..end], true),
+                None => (trimmed, false),
+            };
+            self.lines.push(strip_star(text).to_string());
+            self.in_block = !closed;
+            return true;
+        }
+
+        if let Some(rest) = trimmed.strip_prefix("/**").filter(|rest| !rest.starts_with('*') && !rest.starts_with('/')) {
+            self.lines.clear();
+            match rest.find("*/") {
+                Some(end) => {
+                    self.lines.push(strip_star(&rest[..end]).to_string());
+                    // `/** doc */ code` on one line still documents what follows
+                    return rest[end + 2..].trim().is_empty();
+                }
+                None => {
+                    self.lines.push(strip_star(rest).to_string());
+                    self.in_block = true;
+                }
+            }
+            return true;
+        }
+
+        if slashes {
+            if let Some(rest) = trimmed.strip_prefix("///").filter(|rest| !rest.starts_with('/')) {
+                self.lines.push(rest.strip_prefix(' ').unwrap_or(rest).to_string());
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Add one already-unwrapped doc line, e.g. from a `//!` comment
+    pub fn push(&mut self, line: &str) {
+        self.lines.push(line.to_string());
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.in_block = false;
+    }
+
+    /// The collected text, leaving the collector empty
+    pub fn take(&mut self) -> String {
+        let text = self.lines.join("\n");
+        self.clear();
+        text
+    }
+}
+
+fn strip_star(line: &str) -> &str {
+    let line = line.trim();
+    let line = line.strip_prefix('*').unwrap_or(line);
+    line.strip_prefix(' ').unwrap_or(line).trim_end()
+}
+
+/// JSDoc/TSDoc: `@param {type} name - text`, `@returns text`, `@example` blocks
+pub(crate) fn parse_jsdoc(text: &str) -> DocComment {
+    let mut doc = DocComment::default();
+    let mut description = Vec::new();
+    // The tag whose text continues on following lines
+    let mut section: Option<(String, Vec<String>)> = None;
+
+    let finish = |doc: &mut DocComment, section: Option<(String, Vec<String>)>| {
+        let Some((tag, lines)) = section else { return };
+        match tag.as_str() {
+            "example" => doc.examples.push(dedent(&lines).trim_matches('\n').to_string()),
+            "returns" | "return" => doc.returns = Some(join_words(&lines)),
+            tag if tag.starts_with("param ") => {
+                doc.params.push((tag["param ".len()..].to_string(), join_words(&lines)));
+            }
+            _ => {}
+        }
+    };
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if let Some(tag_line) = trimmed.strip_prefix('@') {
+            finish(&mut doc, section.take());
+            let (tag, rest) = tag_line.split_once(char::is_whitespace).unwrap_or((tag_line, ""));
+            let rest = skip_type(rest.trim());
+            section = match tag {
+                "param" | "arg" | "argument" => {
+                    let (name, text) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                    let name = name.trim_matches(|c| c == '[' || c == ']').split('=').next().unwrap_or(name);
+                    let text = text.trim().trim_start_matches('-').trim();
+                    Some((format!("param {}", name), vec![text.to_string()]))
+                }
+                "returns" | "return" => Some(("returns".to_string(), vec![rest.trim_start_matches('-').trim().to_string()])),
+                "example" => Some(("example".to_string(), if rest.is_empty() { Vec::new() } else { vec![rest.to_string()] })),
+                _ => Some((tag.to_string(), Vec::new())),
+            };
+        } else if let Some((_, lines)) = &mut section {
+            lines.push(line.to_string());
+        } else {
+            description.push(trimmed.to_string());
+        }
+    }
+    finish(&mut doc, section);
+    doc.description = join_paragraphs(&description);
+    doc
+}
+
+/// Rustdoc markdown: `# Arguments` bullets, `# Returns` and `# Examples` sections
+pub(crate) fn parse_rustdoc(text: &str) -> DocComment {
+    let mut doc = DocComment::default();
+    let mut description = Vec::new();
+    let mut section = String::new();
+    let mut section_lines: Vec<String> = Vec::new();
+
+    let finish = |doc: &mut DocComment, section: &str, lines: &mut Vec<String>| {
+        match section {
+            "arguments" | "parameters" | "params" => {
+                for line in lines.iter() {
+                    let item = line.trim().trim_start_matches(['*', '-']).trim();
+                    if let Some((name, text)) = item.split_once(" - ").or_else(|| item.split_once(": ")) {
+                        doc.params.push((name.trim().trim_matches('`').to_string(), text.trim().to_string()));
+                    }
+                }
+            }
+            "returns" | "return" => doc.returns = Some(join_words(lines)),
+            "examples" | "example" => doc.examples.push(lines.join("\n").trim_matches('\n').to_string()),
+            _ => {}
+        }
+        lines.clear();
+    };
+
+    for line in text.lines() {
+        if let Some(heading) = line.trim().strip_prefix("# ") {
+            finish(&mut doc, &section, &mut section_lines);
+            section = heading.trim().to_lowercase();
+        } else if section.is_empty() {
+            description.push(line.trim().to_string());
+        } else {
+            section_lines.push(line.to_string());
+        }
+    }
+    finish(&mut doc, &section, &mut section_lines);
+    doc.description = join_paragraphs(&description);
+    doc
+}
+
+/// Python docstrings in Google (`Args:`), reST (`:param x:`) and doctest styles
+pub(crate) fn parse_docstring(text: &str) -> DocComment {
+    let mut doc = DocComment::default();
+    let mut description = Vec::new();
+    let lines: Vec<&str> = text.lines().collect();
+    let body: Vec<String> = dedent(&lines.iter().skip(1).map(|l| l.to_string()).collect::<Vec<_>>()).lines().map(str::to_string).collect();
+    let all: Vec<String> = lines.first().map(|first| first.trim().to_string()).into_iter().chain(body).collect();
+
+    let mut section = String::new();
+    let mut example: Vec<String> = Vec::new();
+    for line in &all {
+        let trimmed = line.trim();
+        let heading = trimmed.strip_suffix(':').map(|h| h.to_lowercase());
+        match heading.as_deref() {
+            Some("args" | "arguments" | "parameters" | "returns" | "return" | "yields" | "examples" | "example" | "raises")
+                if !line.starts_with(char::is_whitespace) =>
+            {
+                if !example.is_empty() {
+                    doc.examples.push(dedent(&example).trim_matches('\n').to_string());
+                    example.clear();
+                }
+                section = heading.unwrap_or_default();
+                continue;
+            }
+            _ => {}
+        }
+        if section == "examples" || section == "example" {
+            example.push(line.to_string());
+            continue;
+        }
+
+        if let Some(rest) = trimmed.strip_prefix(":param ") {
+            if let Some((name, text)) = rest.split_once(':') {
+                let name = name.split_whitespace().last().unwrap_or(name);
+                doc.params.push((name.to_string(), text.trim().to_string()));
+            }
+            continue;
+        }
+        if let Some(rest) = trimmed.strip_prefix(":returns:").or_else(|| trimmed.strip_prefix(":return:")) {
+            doc.returns = Some(rest.trim().to_string());
+            continue;
+        }
+        // A doctest outside an Examples section runs until the next blank line
+        if trimmed.starts_with(">>>") || (!example.is_empty() && !trimmed.is_empty()) {
+            example.push(trimmed.to_string());
+            continue;
+        }
+        if !example.is_empty() {
+            doc.examples.push(example.join("\n"));
+            example.clear();
+        }
+
+        match section.as_str() {
+            "args" | "arguments" | "parameters" => {
+                // `name (type): text`, with continuation lines indented further
+                if let Some((name, text)) = trimmed.split_once(':').filter(|_| !line.starts_with("        ")) {
+                    let name = name.split_whitespace().next().unwrap_or(name).trim_start_matches('*');
+                    doc.params.push((name.to_string(), text.trim().to_string()));
+                } else if let Some((_, text)) = doc.params.last_mut() {
+                    if !trimmed.is_empty() {
+                        text.push(' ');
+                        text.push_str(trimmed);
+                    }
+                }
+            }
+            "returns" | "return" | "yields" if !trimmed.is_empty() => {
+                let text = doc.returns.get_or_insert_with(String::new);
+                if !text.is_empty() {
+                    text.push(' ');
+                }
+                text.push_str(trimmed);
+            }
+            "" => description.push(trimmed.to_string()),
+            _ => {}
+        }
+    }
+    if !example.is_empty() {
+        doc.examples.push(dedent(&example).trim_matches('\n').to_string());
+    }
+    doc.description = join_paragraphs(&description);
+    doc
+}
+
+// `{string} name` -> `name`
+fn skip_type(text: &str) -> &str {
+    if !text.starts_with('{') {
+        return text;
+    }
+    let mut depth = 0;
+    for (index, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return text[index + 1..].trim_start();
+                }
+            }
+            _ => {}
+        }
+    }
+    text
+}
+
+fn join_words(lines: &[String]) -> String {
+    lines.iter().map(|line| line.trim()).filter(|line| !line.is_empty()).collect::<Vec<_>>().join(" ")
+}
+
+// Lines within a paragraph are joined; blank lines separate paragraphs
+fn join_paragraphs(lines: &[String]) -> String {
+    let mut paragraphs: Vec<String> = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    for line in lines {
+        if line.is_empty() {
+            if !current.is_empty() {
+                paragraphs.push(current.join(" "));
+                current.clear();
+            }
+        } else {
+            current.push(line);
+        }
+    }
+    if !current.is_empty() {
+        paragraphs.push(current.join(" "));
+    }
+    paragraphs.join("\n\n")
+}
+
+fn dedent<S: AsRef<str>>(lines: &[S]) -> String {
+    let indent = lines
+        .iter()
+        .map(AsRef::as_ref)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| line.as_ref().get(indent..).unwrap_or("").trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jsdoc_and_rustdoc_sections() {
+        let jsdoc = parse_jsdoc(
+            "Format a date for display.\nUses the user's locale.\n\n@param {Date} date - The date to format\n@param {string} [pattern=iso] Output pattern\n@returns {string} The formatted date\n@example\nformatDate(new Date())\n// => '2024-01-01'",
+        );
+        assert_eq!(jsdoc.description, "Format a date for display. Uses the user's locale.");
+        assert_eq!(
+            jsdoc.params,
+            vec![("date".to_string(), "The date to format".to_string()), ("pattern".to_string(), "Output pattern".to_string())]
+        );
+        assert_eq!(jsdoc.returns.as_deref(), Some("The formatted date"));
+        assert_eq!(jsdoc.examples, vec!["formatDate(new Date())\n// => '2024-01-01'".to_string()]);
+
+        let rustdoc = parse_rustdoc(
+            "Opens the memory database.\n\n# Arguments\n\n* `db_path` - Path of the SQLite file\n\n# Examples\n\n```\nlet storage = MemoryStorage::new(\"memory.db\")?;\n```",
+        );
+        assert_eq!(rustdoc.description, "Opens the memory database.");
+        assert_eq!(rustdoc.params, vec![("db_path".to_string(), "Path of the SQLite file".to_string())]);
+        assert_eq!(rustdoc.examples, vec!["```\nlet storage = MemoryStorage::new(\"memory.db\")?;\n```".to_string()]);
+    }
+
+    #[test]
+    fn test_python_docstring_styles() {
+        let google = parse_docstring(
+            "Load a user.\n\n    Args:\n        user_id (int): Id of the user\n            to load.\n        cache: Whether to cache.\n\n    Returns:\n        The user, or None.\n\n    Examples:\n        >>> load(1)\n        User(1)\n    ",
+        );
+        assert_eq!(google.description, "Load a user.");
+        assert_eq!(
+            google.params,
+            vec![("user_id".to_string(), "Id of the user to load.".to_string()), ("cache".to_string(), "Whether to cache.".to_string())]
+        );
+        assert_eq!(google.returns.as_deref(), Some("The user, or None."));
+        assert_eq!(google.examples, vec![">>> load(1)\nUser(1)".to_string()]);
+
+        let rest = parse_docstring("Save it.\n\n:param str path: Where to save\n:returns: Bytes written\n");
+        assert_eq!(rest.params, vec![("path".to_string(), "Where to save".to_string())]);
+        assert_eq!(rest.returns.as_deref(), Some("Bytes written"));
+    }
+}
@@ -0,0 +1,345 @@
+use super::line_entity;
+use super::signature::{record_signatures, SignatureStyle};
+use super::scope::{ScopeKind, ScopeTracker};
+use crate::{CodeEntity, EntityType, RelationType, Relationship};
+use anyhow::Result;
+use regex::Regex;
+use std::collections::HashMap;
+
+/// Grouped declaration blocks: `import (`, `const (`, `var (`, `type (`
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GoBlock {
+    Import,
+    Const,
+    Var,
+    Type,
+}
+
+pub(crate) struct GoParser {
+    package_regex: Regex,
+    import_regex: Regex,
+    import_spec_regex: Regex,
+    block_start_regex: Regex,
+    function_regex: Regex,
+    method_regex: Regex,
+    type_regex: Regex,
+    type_spec_regex: Regex,
+    value_regex: Regex,
+    value_spec_regex: Regex,
+    interface_method_regex: Regex,
+}
+
+impl GoParser {
+    pub(crate) fn new() -> Result<Self> {
+        Ok(Self {
+            package_regex: Regex::new(r"^package\s+(\w+)")?,
+            import_regex: Regex::new(r#"^import\s+(?:([\w.]+)\s+)?"([^"]+)""#)?,
+            import_spec_regex: Regex::new(r#"^\s*(?:([\w.]+)\s+)?"([^"]+)""#)?,
+            block_start_regex: Regex::new(r"^(import|const|var|type)\s*\(")?,
+            function_regex: Regex::new(r"^func\s+(\w+)\s*[\[(]")?,
+            method_regex: Regex::new(r"^func\s*\(\s*(?:\w+\s+)?\*?\s*(\w+)(?:\[[^\]]*\])?\s*\)\s*(\w+)\s*[\[(]")?,
+            type_regex: Regex::new(r"^type\s+(\w+)(?:\[[^\]]*\])?\s+(=\s*)?(struct|interface)?")?,
+            type_spec_regex: Regex::new(r"^\s+(\w+)(?:\[[^\]]*\])?\s+(=\s*)?(struct|interface)?")?,
+            value_regex: Regex::new(r"^(const|var)\s+(\w+)")?,
+            value_spec_regex: Regex::new(r"^\s+(\w+)")?,
+            interface_method_regex: Regex::new(r"^\s+(\w+)\s*\(")?,
+        })
+    }
+
+    pub(crate) fn parse(&self, content: &str, file_path: &str) -> Result<(Vec<CodeEntity>, Vec<Relationship>)> {
+        let mut entities: Vec<CodeEntity> = Vec::new();
+        let mut relationships = Vec::new();
+
+        let mut tracker = ScopeTracker::new();
+        let mut block: Option<GoBlock> = None;
+        let mut package_index = None;
+        let mut import_indexes = Vec::new();
+        let mut type_indexes: HashMap<String, usize> = HashMap::new();
+        let mut methods: Vec<(usize, String)> = Vec::new(); // (method index, receiver type)
+
+        for (line_num, line) in content.lines().enumerate() {
+            let trimmed = line.trim();
+
+            if let Some(current_block) = block {
+                if trimmed.starts_with(')') {
+                    block = None;
+                } else if tracker.depth() > 0 {
+                    self.push_interface_method(&mut entities, &mut methods, &tracker, file_path, line_num, line);
+                } else if !trimmed.is_empty() && !trimmed.starts_with("//") {
+                    match current_block {
+                        GoBlock::Import => {
+                            if let Some(captures) = self.import_spec_regex.captures(line) {
+                                import_indexes.push(entities.len());
+                                entities.push(import_entity(&captures, file_path, line_num, line));
+                            }
+                        }
+                        GoBlock::Type => {
+                            if let Some(captures) = self.type_spec_regex.captures(line) {
+                                let index = self.push_type(&mut entities, &mut tracker, &captures, file_path, line_num, line);
+                                type_indexes.insert(entities[index].name.clone(), index);
+                            }
+                        }
+                        GoBlock::Const | GoBlock::Var => {
+                            if let Some(captures) = self.value_spec_regex.captures(line) {
+                                let entity_type = if current_block == GoBlock::Const { EntityType::Constant } else { EntityType::Variable };
+                                entities.push(line_entity(&captures[1], entity_type, file_path, line_num, line));
+                            }
+                        }
+                    }
+                }
+            } else if let Some(captures) = self.package_regex.captures(line) {
+                package_index = Some(entities.len());
+                entities.push(line_entity(&captures[1], EntityType::Module, file_path, line_num, line));
+            } else if let Some(captures) = self.block_start_regex.captures(line) {
+                block = match &captures[1] {
+                    "import" => Some(GoBlock::Import),
+                    "const" => Some(GoBlock::Const),
+                    "var" => Some(GoBlock::Var),
+                    _ => Some(GoBlock::Type),
+                };
+            } else if let Some(captures) = self.import_regex.captures(line) {
+                import_indexes.push(entities.len());
+                entities.push(import_entity(&captures, file_path, line_num, line));
+            } else if let Some(captures) = self.method_regex.captures(line) {
+                let receiver = captures[1].to_string();
+                let entity = line_entity(&captures[2], EntityType::Function, file_path, line_num, line)
+                    .with_metadata("receiver".to_string(), receiver.clone());
+                tracker.expect_body(&captures[2], entities.len(), ScopeKind::Function);
+                methods.push((entities.len(), receiver));
+                entities.push(entity);
+            } else if let Some(captures) = self.function_regex.captures(line) {
+                tracker.expect_body(&captures[1], entities.len(), ScopeKind::Function);
+                entities.push(line_entity(&captures[1], EntityType::Function, file_path, line_num, line));
+            } else if let Some(captures) = self.type_regex.captures(line) {
+                let index = self.push_type(&mut entities, &mut tracker, &captures, file_path, line_num, line);
+                type_indexes.insert(entities[index].name.clone(), index);
+            } else if let Some(captures) = self.value_regex.captures(line) {
+                let entity_type = if &captures[1] == "const" { EntityType::Constant } else { EntityType::Variable };
+                entities.push(line_entity(&captures[2], entity_type, file_path, line_num, line));
+            } else {
+                self.push_interface_method(&mut entities, &mut methods, &tracker, file_path, line_num, line);
+            }
+
+            for closed in tracker.process_line(line) {
+                entities[closed].line_end = line_num as u32 + 1;
+            }
+        }
+
+        // Methods belong to their receiver type, which may be declared in another
+        // file of the package
+        for (method_index, receiver) in methods {
+            match type_indexes.get(&receiver) {
+                Some(&type_index) => relationships.push(Relationship::new(
+                    entities[type_index].id.clone(),
+                    entities[method_index].id.clone(),
+                    RelationType::Contains,
+                )),
+                None => relationships.push(Relationship::member_of(entities[method_index].id.clone(), &receiver)),
+            }
+        }
+
+        if let Some(package_index) = package_index {
+            for import_index in import_indexes {
+                relationships.push(Relationship::new(
+                    entities[package_index].id.clone(),
+                    entities[import_index].id.clone(),
+                    RelationType::Imports,
+                ));
+            }
+        }
+
+        record_signatures(&mut entities, content, SignatureStyle::Go);
+        Ok((entities, relationships))
+    }
+
+    /// Method sets declared directly inside an interface body, also in a `type (` block
+    fn push_interface_method(
+        &self,
+        entities: &mut Vec<CodeEntity>,
+        methods: &mut Vec<(usize, String)>,
+        tracker: &ScopeTracker,
+        file_path: &str,
+        line_num: usize,
+        line: &str,
+    ) {
+        let Some(interface) = tracker.innermost().filter(|s| s.kind == ScopeKind::Type) else { return };
+        let is_interface = interface.entity.map(|index| entities[index].entity_type == EntityType::Interface).unwrap_or(false);
+        if !is_interface || tracker.depth() != 1 {
+            return;
+        }
+        if let Some(captures) = self.interface_method_regex.captures(line) {
+            let interface_name = interface.name.clone();
+            methods.push((entities.len(), interface_name.clone()));
+            entities.push(
+                line_entity(&captures[1], EntityType::Function, file_path, line_num, line)
+                    .with_metadata("receiver".to_string(), interface_name),
+            );
+        }
+    }
+
+    fn push_type(
+        &self,
+        entities: &mut Vec<CodeEntity>,
+        tracker: &mut ScopeTracker,
+        captures: &regex::Captures,
+        file_path: &str,
+        line_num: usize,
+        line: &str,
+    ) -> usize {
+        let name = &captures[1];
+        let is_alias = captures.get(2).is_some();
+        let (entity_type, kind) = match captures.get(3).map(|m| m.as_str()) {
+            Some("struct") if !is_alias => (EntityType::Class, "struct"),
+            Some("interface") if !is_alias => (EntityType::Interface, "interface"),
+            _ => (EntityType::Type, if is_alias { "alias" } else { "named" }),
+        };
+
+        let index = entities.len();
+        if entity_type != EntityType::Type {
+            tracker.expect_body(name, index, ScopeKind::Type);
+        }
+        entities.push(
+            line_entity(name, entity_type, file_path, line_num, line)
+                .with_metadata("kind".to_string(), kind.to_string()),
+        );
+        index
+    }
+}
+
+fn import_entity(captures: &regex::Captures, file_path: &str, line_num: usize, line: &str) -> CodeEntity {
+    let entity = line_entity(&captures[2], EntityType::Import, file_path, line_num, line);
+    match captures.get(1) {
+        Some(alias) => entity.with_metadata("alias".to_string(), alias.as_str().to_string()),
+        None => entity,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_support::{resolve_files, ParsedSource};
+
+    const SOURCE: &str = r#"package storage
+
+import "fmt"
+
+import (
+	"database/sql"
+	log "github.com/sirupsen/logrus"
+)
+
+const MaxEntities = 1000
+
+const (
+	ModeRead = iota
+	ModeWrite
+)
+
+type Store struct {
+	db *sql.DB
+}
+
+type Reader interface {
+	Read(id string) (string, error)
+}
+
+type ID = string
+
+func NewStore(db *sql.DB) *Store {
+	return &Store{db: db}
+}
+
+func (s *Store) Read(id string) (string, error) {
+	if id == "" {
+		return "", fmt.Errorf("empty id")
+	}
+	return id, nil
+}
+"#;
+
+    #[test]
+    fn test_go_entities() -> Result<()> {
+        let file = ParsedSource::new(&GoParser::new()?, "storage/store.go", SOURCE)?;
+
+        assert_eq!(file.find("storage").entity_type, EntityType::Module);
+        assert_eq!(file.find("fmt").entity_type, EntityType::Import);
+        assert_eq!(file.find("database/sql").entity_type, EntityType::Import);
+        assert_eq!(file.meta("github.com/sirupsen/logrus", "alias"), Some("log"));
+        assert_eq!(file.edges("storage").len(), 3, "one import edge per import");
+
+        for constant in ["MaxEntities", "ModeRead", "ModeWrite"] {
+            assert_eq!(file.find(constant).entity_type, EntityType::Constant);
+        }
+        let store = file.find_typed("Store", EntityType::Class);
+        assert_eq!((store.line_start, store.line_end), (17, 19));
+        assert_eq!(file.find("Reader").entity_type, EntityType::Interface);
+        assert_eq!(file.find("ID").entity_type, EntityType::Type);
+        assert_eq!(file.find("NewStore").entity_type, EntityType::Function);
+
+        // The interface method and the Store method share a name
+        let methods: Vec<_> = file.entities.iter().filter(|e| e.name == "Read").collect();
+        assert_eq!(methods.len(), 2);
+        let store_read = methods.iter().find(|e| e.metadata.get("receiver").map(String::as_str) == Some("Store")).unwrap();
+        assert_eq!(store_read.line_end, 36);
+        assert_eq!(file.members("Store"), vec!["Read"]);
+        assert_eq!(file.members("Reader"), vec!["Read"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_go_generics_groups_and_other_files() -> Result<()> {
+        let source = r#"package list
+
+type List[T any] struct {
+	items []T
+}
+
+func (l *List[T]) Push(v T) {
+	open := "{"
+	_ = `}
+}`
+	l.items = append(l.items, v)
+}
+
+func (List[T]) Len() int { return 0 }
+
+type (
+	Node struct {
+		next *Node
+	}
+	Visitor interface {
+		Visit(n *Node)
+	}
+)
+"#;
+        let file = ParsedSource::new(&GoParser::new()?, "list/list.go", source)?;
+        // Braces in strings, raw strings included, do not end the body
+        assert_eq!((file.find("Push").line_start, file.find("Push").line_end), (7, 12));
+        assert_eq!(file.members("List"), vec!["Len", "Push"]);
+        assert_eq!(file.find("Node").entity_type, EntityType::Class);
+        assert_eq!(file.members("Visitor"), vec!["Visit"]);
+
+        // A receiver declared in another file of the package resolves across files
+        let mut memory = resolve_files(
+            &GoParser::new()?,
+            &[("list/list.go", source), ("list/sort.go", "package list\n\nfunc (l *List[T]) Sort() {\n}\n")],
+        )?;
+        let list = memory.entities.values().find(|e| e.name == "List").unwrap();
+        let sort = memory.entities.values().find(|e| e.name == "Sort").unwrap();
+        // Resolved, the edge points from the receiver type to the method
+        let member_of = memory.relationships.iter().find(|r| r.metadata.contains_key("member_of")).unwrap();
+        assert_eq!(member_of.relationship_type, RelationType::Contains);
+        assert!(member_of.is_resolved());
+        assert_eq!((member_of.from_entity.as_str(), member_of.to_entity.as_str()), (list.id.as_str(), sort.id.as_str()));
+
+        // Removing the receiver's file turns the edge back into a symbol link from the method
+        let sort_id = sort.id.clone();
+        memory.remove_file("list/list.go");
+        let member_of = memory.relationships.iter().find(|r| r.metadata.contains_key("member_of")).unwrap();
+        assert!(!member_of.is_resolved());
+        assert_eq!((member_of.from_entity.as_str(), member_of.to_entity.as_str()), (sort_id.as_str(), "List"));
+
+        Ok(())
+    }
+}
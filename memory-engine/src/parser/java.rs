@@ -0,0 +1,374 @@
+use super::parsed::ParsedFile;
+use super::signature::{record_signatures, SignatureStyle};
+use super::scope::{ScopeKind, ScopeTracker};
+use super::{line_entity, split_balanced, split_top_level, strip_generics};
+use crate::{CodeEntity, EntityType, RelationType, Relationship};
+use anyhow::Result;
+use regex::Regex;
+
+pub(crate) struct JavaParser {
+    package_regex: Regex,
+    import_regex: Regex,
+    type_regex: Regex,
+    heritage_regex: Regex,
+    method_regex: Regex,
+    field_regex: Regex,
+}
+
+impl JavaParser {
+    pub(crate) fn new() -> Result<Self> {
+        Ok(Self {
+            package_regex: Regex::new(r"^\s*package\s+([\w.]+)\s*;")?,
+            import_regex: Regex::new(r"^\s*import\s+(static\s+)?([\w.]+(?:\.\*)?)\s*;")?,
+            type_regex: Regex::new(
+                r"^\s*((?:(?:public|protected|private|static|final|abstract|sealed|non-sealed|strictfp)\s+)*)(class|interface|enum|record|@interface)\s+(\w+)",
+            )?,
+            heritage_regex: Regex::new(r"\b(extends|implements|permits)\b")?,
+            method_regex: Regex::new(
+                r"^\s*(?:(?:public|protected|private|static|final|abstract|synchronized|native|default|strictfp)\s+)*(?:<[^()=;]*?>\s+)?(?:([\w.$]+(?:<[^()=;]*>)?(?:\[\])*)\s+)?(\w+)\s*\(",
+            )?,
+            field_regex: Regex::new(
+                r"^\s*((?:(?:public|protected|private|static|final|transient|volatile)\s+)*)[\w.$]+(?:<[^()=;]*>)?(?:\[\])*\s+(\w+)\s*(?:=|;|,)",
+            )?,
+        })
+    }
+
+    pub(crate) fn parse(&self, content: &str, file_path: &str) -> Result<(Vec<CodeEntity>, Vec<Relationship>)> {
+        let mut parsed = ParsedFile::default();
+        let mut tracker = ScopeTracker::new();
+        let mut package = None;
+        let mut imports = Vec::new();
+        let mut annotations: Vec<String> = Vec::new();
+
+        for (line_num, line) in content.lines().enumerate() {
+            let trimmed = line.trim_start();
+            let enclosing = tracker
+                .current_body()
+                .filter(|scope| scope.kind == ScopeKind::Type)
+                .and_then(|scope| Some((scope.entity?, scope.name.clone())));
+            let is_comment = tracker.in_block_comment() || trimmed.starts_with("//") || trimmed.starts_with("/*") || trimmed.starts_with('*');
+
+            // Only type bodies and the top level hold declarations; method bodies are skipped
+            if !is_comment && (tracker.depth() == 0 || enclosing.is_some()) {
+                let (found, declaration) = split_annotations(line);
+                annotations.extend(found);
+
+                if !declaration.trim().is_empty() {
+                    let entity = if tracker.depth() == 0 {
+                        if let Some(captures) = self.package_regex.captures(declaration) {
+                            package = Some(parsed.push(line_entity(&captures[1], EntityType::Module, file_path, line_num, line)));
+                            None
+                        } else if let Some(captures) = self.import_regex.captures(declaration) {
+                            let mut entity = line_entity(&captures[2], EntityType::Import, file_path, line_num, line);
+                            if captures.get(1).is_some() {
+                                entity = entity.with_metadata("static".to_string(), "true".to_string());
+                            }
+                            imports.push(parsed.push(entity));
+                            None
+                        } else {
+                            self.parse_type(&mut parsed, &mut tracker, declaration, None, package, file_path, line_num, line)
+                        }
+                    } else {
+                        self.parse_type(&mut parsed, &mut tracker, declaration, enclosing.as_ref(), package, file_path, line_num, line)
+                            .or_else(|| {
+                                let (parent, parent_name) = enclosing.as_ref()?;
+                                self.parse_member(&mut parsed, &mut tracker, declaration, *parent, parent_name, file_path, line_num, line)
+                            })
+                    };
+
+                    if let Some(index) = entity {
+                        if !annotations.is_empty() {
+                            parsed.set_metadata(index, "annotations", annotations.join(","));
+                        }
+                    }
+                    annotations.clear();
+                }
+            }
+
+            for closed in tracker.process_line(line) {
+                parsed.set_line_end(closed, line_num);
+            }
+        }
+
+        if let Some(package) = package {
+            for import in imports {
+                parsed.link(package, import, RelationType::Imports);
+            }
+        }
+
+        record_signatures(&mut parsed.entities, content, SignatureStyle::CFamily);
+        Ok(parsed.finish())
+    }
+
+    /// Classes, interfaces, enums, records and annotation types
+    #[allow(clippy::too_many_arguments)]
+    fn parse_type(
+        &self,
+        parsed: &mut ParsedFile,
+        tracker: &mut ScopeTracker,
+        declaration: &str,
+        enclosing: Option<&(usize, String)>,
+        package: Option<usize>,
+        file_path: &str,
+        line_num: usize,
+        line: &str,
+    ) -> Option<usize> {
+        let captures = self.type_regex.captures(declaration)?;
+        let keyword = captures.get(2)?.as_str();
+        let name = &captures[3];
+        let (entity_type, kind) = match keyword {
+            "interface" => (EntityType::Interface, "interface"),
+            "@interface" => (EntityType::Interface, "annotation"),
+            "enum" => (EntityType::Class, "enum"),
+            "record" => (EntityType::Class, "record"),
+            _ => (EntityType::Class, "class"),
+        };
+
+        let index = parsed.push(
+            line_entity(name, entity_type, file_path, line_num, line)
+                .with_metadata("kind".to_string(), kind.to_string()),
+        );
+        if captures[1].contains("abstract") {
+            parsed.set_metadata(index, "abstract", "true".to_string());
+        }
+        match (enclosing, package) {
+            (Some((parent, _)), _) => parsed.contain(*parent, index),
+            (None, Some(package)) => parsed.link(package, index, RelationType::Contains),
+            (None, None) => {}
+        }
+        tracker.expect_body(name, index, ScopeKind::Type);
+
+        let mut rest = &declaration[captures.get(0)?.end()..];
+        if let Some((_, after_generics)) = split_balanced(rest, '<', '>') {
+            rest = after_generics;
+        }
+        if kind == "record" {
+            if let Some((components, after_components)) = split_balanced(rest, '(', ')') {
+                for component in split_top_level(components) {
+                    let (_, component) = split_annotations(component);
+                    if let Some(field) = component.split_whitespace().last() {
+                        let field = parsed.push(
+                            line_entity(field, EntityType::Variable, file_path, line_num, line)
+                                .with_metadata("kind".to_string(), "field".to_string()),
+                        );
+                        parsed.contain(index, field);
+                    }
+                }
+                rest = after_components;
+            }
+        }
+
+        let heritage = rest.split('{').next().unwrap_or(rest);
+        let keywords: Vec<_> = self.heritage_regex.find_iter(heritage).collect();
+        for (position, keyword) in keywords.iter().enumerate() {
+            let end = keywords.get(position + 1).map(|next| next.start()).unwrap_or(heritage.len());
+            let relationship_type = match keyword.as_str() {
+                "extends" => RelationType::Extends,
+                "implements" => RelationType::Implements,
+                _ => continue,
+            };
+            for parent in split_top_level(&heritage[keyword.end()..end]) {
+                parsed.link_symbol(index, strip_generics(parent), relationship_type.clone());
+            }
+        }
+
+        Some(index)
+    }
+
+    /// Methods, constructors and fields declared directly in a type body
+    #[allow(clippy::too_many_arguments)]
+    fn parse_member(
+        &self,
+        parsed: &mut ParsedFile,
+        tracker: &mut ScopeTracker,
+        declaration: &str,
+        parent: usize,
+        parent_name: &str,
+        file_path: &str,
+        line_num: usize,
+        line: &str,
+    ) -> Option<usize> {
+        if let Some(captures) = self.method_regex.captures(declaration) {
+            let name = &captures[2];
+            // Without a return type only a constructor is a declaration
+            let kind = match captures.get(1) {
+                Some(_) => "method",
+                None if name == parent_name => "constructor",
+                None => return None,
+            };
+            let index = parsed.push(
+                line_entity(name, EntityType::Function, file_path, line_num, line)
+                    .with_metadata("kind".to_string(), kind.to_string()),
+            );
+            parsed.contain(parent, index);
+            tracker.expect_body(name, index, ScopeKind::Function);
+            return Some(index);
+        }
+
+        let captures = self.field_regex.captures(declaration)?;
+        let modifiers = &captures[1];
+        let entity_type = if modifiers.contains("static") && modifiers.contains("final") {
+            EntityType::Constant
+        } else {
+            EntityType::Variable
+        };
+        let index = parsed.push(
+            line_entity(&captures[2], entity_type, file_path, line_num, line)
+                .with_metadata("kind".to_string(), "field".to_string()),
+        );
+        parsed.contain(parent, index);
+        Some(index)
+    }
+}
+
+/// Strip leading `@Annotation(...)` markers from a declaration, returning their
+/// names and the rest of the line. Use-site targets like `@field:Json` keep `Json`.
+pub(super) fn split_annotations(line: &str) -> (Vec<String>, &str) {
+    let mut names = Vec::new();
+    let mut rest = line.trim_start();
+
+    while let Some(after_at) = rest.strip_prefix('@') {
+        if after_at.starts_with("interface") {
+            break;
+        }
+        let name_len = after_at
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.' || c == ':'))
+            .unwrap_or(after_at.len());
+        if name_len == 0 {
+            break;
+        }
+
+        let name = &after_at[..name_len];
+        names.push(name.rsplit(':').next().unwrap_or(name).to_string());
+        rest = after_at[name_len..].trim_start();
+        if rest.starts_with('(') {
+            // Arguments spilling onto the next lines are dropped with the rest of this one
+            rest = split_balanced(rest, '(', ')').map(|(_, after)| after.trim_start()).unwrap_or("");
+        }
+    }
+
+    (names, rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_support::ParsedSource;
+
+    const SOURCE: &str = r#"package com.example.orders;
+
+import java.util.List;
+import static java.util.Objects.requireNonNull;
+
+/**
+ * Order service.
+ */
+@Service
+@Transactional(readOnly = true)
+public class OrderService extends BaseService<Order> implements Closeable, Repository<Order, Long> {
+    private static final int MAX_ORDERS = 100;
+    private final List<Order> orders;
+
+    public OrderService(List<Order> orders) {
+        this.orders = requireNonNull(orders);
+    }
+
+    @Override
+    public List<Order> findAll() {
+        Runnable r = new Runnable() {
+            public void run() {}
+        };
+        return orders;
+    }
+
+    static class Cache {
+        void clear() {}
+    }
+
+    public enum Status implements Labeled { OPEN, CLOSED }
+}
+
+record Point(int x, @Positive int y) implements Comparable<Point> {}
+
+interface Labeled extends Named, Serializable {
+    String label();
+}
+"#;
+
+    #[test]
+    fn test_java_entities_and_edges() -> Result<()> {
+        let file = ParsedSource::new(&JavaParser::new()?, "src/OrderService.java", SOURCE)?;
+
+        assert_eq!(file.find("com.example.orders").entity_type, EntityType::Module);
+        assert_eq!(file.find("java.util.List").entity_type, EntityType::Import);
+        assert_eq!(file.meta("java.util.Objects.requireNonNull", "static"), Some("true"));
+        assert_eq!(file.edges("com.example.orders").len(), 2);
+
+        let service = file.find("OrderService");
+        assert_eq!(service.entity_type, EntityType::Class);
+        assert_eq!(file.meta("OrderService", "annotations"), Some("Service,Transactional"));
+        assert_eq!((service.line_start, service.line_end), (11, 32));
+        assert_eq!(
+            file.edges("OrderService"),
+            vec![("extends", "BaseService"), ("implements", "Closeable"), ("implements", "Repository")]
+        );
+
+        assert_eq!(file.find("MAX_ORDERS").entity_type, EntityType::Constant);
+        assert_eq!(file.find("orders").entity_type, EntityType::Variable);
+        let constructor = file.find_typed("OrderService", EntityType::Function);
+        assert_eq!(constructor.metadata.get("kind").map(String::as_str), Some("constructor"));
+        assert_eq!(file.meta("findAll", "annotations"), Some("Override"));
+        assert!(!file.has("run"), "anonymous class members are not type members");
+
+        assert_eq!(file.meta("Cache", "enclosing"), Some("OrderService"));
+        assert!(file.members("OrderService").contains(&"Cache"));
+        assert_eq!(file.meta("clear", "enclosing"), Some("Cache"));
+        assert_eq!(file.meta("Status", "kind"), Some("enum"));
+        assert_eq!(file.edges("Status"), vec![("implements", "Labeled")]);
+        assert_eq!(file.meta("Point", "kind"), Some("record"));
+        assert_eq!(file.meta("y", "enclosing"), Some("Point"));
+        assert_eq!(file.edges("Point"), vec![("implements", "Comparable")]);
+        assert_eq!(file.find("Labeled").entity_type, EntityType::Interface);
+        assert_eq!(file.meta("label", "enclosing"), Some("Labeled"));
+        assert_eq!(file.edges("Labeled"), vec![("extends", "Named"), ("extends", "Serializable")]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_java_generics_literals_and_default_methods() -> Result<()> {
+        let source = r#"package util;
+
+@SuppressWarnings({"unchecked", "rawtypes"})
+public final class Util<T extends Comparable<T>> extends Base<T> implements Api {
+    public static <R extends Comparable<R>> R max(List<R> xs) {
+        String open = "{";
+        char close = '}';
+        return xs.stream().reduce((a, b) -> { return a; }).get();
+    }
+
+    String sql() {
+        return """
+            }
+            """;
+    }
+
+    public interface Api {
+        default void ping() {}
+    }
+}
+"#;
+        let file = ParsedSource::new(&JavaParser::new()?, "src/Util.java", source)?;
+        // Bounds inside the type parameters are not supertypes
+        assert_eq!(file.edges("Util"), vec![("extends", "Base"), ("implements", "Api")]);
+        assert_eq!(file.meta("Util", "annotations"), Some("SuppressWarnings"));
+        assert_eq!((file.find("Util").line_start, file.find("Util").line_end), (4, 20));
+        // Strings, char literals, lambdas and text blocks keep their braces to themselves
+        assert_eq!((file.find("max").line_start, file.find("max").line_end), (5, 9));
+        assert_eq!(file.find("sql").line_end, 15);
+        assert_eq!(file.members("Util"), vec!["Api", "max", "sql"]);
+        assert_eq!(file.members("Api"), vec!["ping"]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,752 @@
+use super::docs::{parse_jsdoc, PendingDoc};
+use super::parsed::ParsedFile;
+use super::signature::{record_signatures, SignatureStyle};
+use super::scope::{ScopeKind, ScopeTracker};
+use super::{line_entity, split_balanced, split_top_level, strip_generics};
+use crate::{CodeEntity, EntityType, RelationType, Relationship};
+use anyhow::Result;
+use regex::Regex;
+
+/// Parser for JavaScript and TypeScript, including `.d.ts` declaration files
+pub(crate) struct JavaScriptParser {
+    function_regex: Regex,
+    class_regex: Regex,
+    import_regex: Regex,
+    import_start_regex: Regex,
+    from_regex: Regex,
+    require_regex: Regex,
+    variable_regex: Regex,
+    function_value_regex: Regex,
+    interface_regex: Regex,
+    type_alias_regex: Regex,
+    enum_regex: Regex,
+    namespace_regex: Regex,
+    global_regex: Regex,
+    heritage_regex: Regex,
+    signature_regex: Regex,
+    enum_member_regex: Regex,
+    class_member_regex: Regex,
+    object_method_regex: Regex,
+    object_property_regex: Regex,
+    export_list_regex: Regex,
+    export_star_regex: Regex,
+    export_default_regex: Regex,
+    commonjs_export_regex: Regex,
+}
+
+impl JavaScriptParser {
+    pub(crate) fn new() -> Result<Self> {
+        Ok(Self {
+            function_regex: Regex::new(r"^\s*((?:(?:export|default|declare|async)\s+)*)function\*?\s*(\w+)\s*[<(]")?,
+            class_regex: Regex::new(r"^\s*((?:(?:export|default|declare|abstract)\s+)*)class\s+(\w+)")?,
+            import_regex: Regex::new(r#"^\s*import\s+(?:[^'"]*?\s+from\s+)?['"]([^'"]+)['"]"#)?,
+            import_start_regex: Regex::new(r#"^\s*import\s+(?:type\s+)?[^'"]*\{[^}'"]*$"#)?,
+            from_regex: Regex::new(r#"\bfrom\s+['"]([^'"]+)['"]"#)?,
+            require_regex: Regex::new(r#"\brequire\s*\(\s*['"]([^'"]+)['"]\s*\)"#)?,
+            variable_regex: Regex::new(r"^\s*((?:(?:export|declare)\s+)*)(?:const|let|var)\s+(\w+)")?,
+            function_value_regex: Regex::new(r"^\s*(async\s+)?(?:(function)\b|(?:<[^>]*>\s*)?\([^)]*\)\s*(?::\s*[^=]+)?=>|\w+\s*=>)")?,
+            interface_regex: Regex::new(r"^\s*((?:(?:export|default|declare)\s+)*)interface\s+(\w+)")?,
+            type_alias_regex: Regex::new(r"^\s*((?:(?:export|declare)\s+)*)type\s+(\w+)\s*[<=]")?,
+            enum_regex: Regex::new(r"^\s*((?:(?:export|declare|const)\s+)*)enum\s+(\w+)")?,
+            namespace_regex: Regex::new(r#"^\s*((?:(?:export|declare)\s+)*)(namespace|module)\s+([\w.]+|'[^']+'|"[^"]+")"#)?,
+            global_regex: Regex::new(r"^\s*declare\s+global\b")?,
+            heritage_regex: Regex::new(r"\b(extends|implements)\b")?,
+            signature_regex: Regex::new(r"^\s*(?:readonly\s+)?(\w+)\??\s*([(<:])")?,
+            enum_member_regex: Regex::new(r#"^\s*(\w+|'[^']+'|"[^"]+")\s*(?:=|,|$)"#)?,
+            class_member_regex: Regex::new(
+                r"^\s*((?:(?:public|private|protected|static|readonly|abstract|override|async|get|set|declare|accessor)\s+)*)(\*\s*)?(#?\w+)\s*[?!]?\s*([(<=:;]|$)",
+            )?,
+            object_method_regex: Regex::new(r"^\s*(async\s+)?(?:\*\s*)?(?:(get|set)\s+)?(\w+)\s*\([^)]*\)?[^=]*\{")?,
+            object_property_regex: Regex::new(r#"^\s*(\w+|'[^']+'|"[^"]+")\s*:\s*(.*)"#)?,
+            export_list_regex: Regex::new(r#"^\s*export\s+(?:type\s+)?\{([^}]*)\}(?:\s*from\s+['"]([^'"]+)['"])?"#)?,
+            export_star_regex: Regex::new(r#"^\s*export\s+\*(?:\s+as\s+(\w+))?\s+from\s+['"]([^'"]+)['"]"#)?,
+            export_default_regex: Regex::new(r"^\s*export\s+(?:default|=)\s+(.*)")?,
+            commonjs_export_regex: Regex::new(r"^\s*(?:module\.exports|exports)(?:\.(\w+))?\s*=[^=]\s*(.*)")?,
+        })
+    }
+
+    pub(crate) fn parse(&self, content: &str, file_path: &str) -> Result<(Vec<CodeEntity>, Vec<Relationship>)> {
+        let mut parsed = ParsedFile::default();
+        let mut tracker = ScopeTracker::new().with_single_quoted_strings().with_regex_literals();
+        // `import {` whose `from '...'` comes on a later line
+        let mut open_import: Option<usize> = None;
+        let mut docs = PendingDoc::default();
+
+        for (line_num, line) in content.lines().enumerate() {
+            if let Some(start) = open_import {
+                if let Some(captures) = self.from_regex.captures(line) {
+                    let import = parsed.push(line_entity(&captures[1], EntityType::Import, file_path, start, line));
+                    parsed.set_line_end(import, line_num);
+                    open_import = None;
+                }
+                tracker.process_line(line);
+                continue;
+            }
+
+            let trimmed = line.trim_start();
+            let enclosing = tracker
+                .current_body()
+                .filter(|scope| scope.kind != ScopeKind::Function)
+                .and_then(|scope| Some((scope.entity?, scope.kind)));
+            let is_comment = tracker.in_block_comment() || trimmed.starts_with("//") || trimmed.starts_with("/*") || trimmed.starts_with('*');
+            let is_doc = docs.feed(line, false);
+
+            if !is_comment && !is_doc && !trimmed.is_empty() {
+                let index = match enclosing {
+                    None if tracker.at_top_level() => {
+                        if self.import_start_regex.is_match(line) {
+                            open_import = Some(line_num);
+                            None
+                        } else {
+                            self.parse_declaration(&mut parsed, &mut tracker, None, file_path, line_num, line)
+                        }
+                    }
+                    Some((parent, ScopeKind::Namespace)) => {
+                        self.parse_declaration(&mut parsed, &mut tracker, Some(parent), file_path, line_num, line)
+                    }
+                    Some((parent, ScopeKind::Type)) => self.parse_member(&mut parsed, &mut tracker, parent, file_path, line_num, line),
+                    _ => None,
+                };
+                match index {
+                    Some(index) => parse_jsdoc(&docs.take()).apply(&mut parsed, index),
+                    // Decorators sit between a doc comment and what it documents
+                    None if trimmed.starts_with('@') => {}
+                    None => docs.clear(),
+                }
+            }
+
+            for closed in tracker.process_line(line) {
+                parsed.set_line_end(closed, line_num);
+            }
+        }
+
+        // Everything in a declaration file describes code that lives elsewhere
+        if file_path.ends_with(".d.ts") || file_path.ends_with(".d.mts") || file_path.ends_with(".d.cts") {
+            for entity in &mut parsed.entities {
+                entity.metadata.insert("ambient".to_string(), "true".to_string());
+            }
+        }
+
+        record_signatures(&mut parsed.entities, content, SignatureStyle::JavaScript);
+        Ok(parsed.finish())
+    }
+
+    /// Declarations at file level or directly inside a namespace
+    fn parse_declaration(
+        &self,
+        parsed: &mut ParsedFile,
+        tracker: &mut ScopeTracker,
+        parent: Option<usize>,
+        file_path: &str,
+        line_num: usize,
+        line: &str,
+    ) -> Option<usize> {
+        let (index, modifiers, rest) = if let Some(captures) = self.import_regex.captures(line) {
+            return Some(parsed.push(line_entity(&captures[1], EntityType::Import, file_path, line_num, line)));
+        } else if self.require_regex.is_match(line) {
+            // `const x = require('y')` is an import, not a variable
+            let mut last = None;
+            for captures in self.require_regex.captures_iter(line) {
+                last = Some(parsed.push(
+                    line_entity(&captures[1], EntityType::Import, file_path, line_num, line)
+                        .with_metadata("kind".to_string(), "require".to_string()),
+                ));
+            }
+            return last;
+        } else if self.global_regex.is_match(line) {
+            let index = parsed.push(
+                line_entity("global", EntityType::Module, file_path, line_num, line)
+                    .with_metadata("kind".to_string(), "global".to_string()),
+            );
+            tracker.expect_body("global", index, ScopeKind::Namespace);
+            (index, "declare".to_string(), "")
+        } else if let Some(captures) = self.namespace_regex.captures(line) {
+            // `declare module "express"` augments a package; `namespace A.B` nests
+            let name = captures[3].trim_matches(|c| c == '"' || c == '\'');
+            let kind = if captures[3].starts_with(['"', '\'']) { "module" } else { "namespace" };
+            let index = parsed.push(
+                line_entity(name, EntityType::Module, file_path, line_num, line)
+                    .with_metadata("kind".to_string(), kind.to_string()),
+            );
+            tracker.expect_body(name, index, ScopeKind::Namespace);
+            (index, captures[1].to_string(), "")
+        } else if let Some(captures) = self.interface_regex.captures(line) {
+            let index = parsed.push(
+                line_entity(&captures[2], EntityType::Interface, file_path, line_num, line)
+                    .with_metadata("kind".to_string(), "interface".to_string()),
+            );
+            tracker.expect_body(&captures[2], index, ScopeKind::Type);
+            (index, captures[1].to_string(), &line[captures.get(0)?.end()..])
+        } else if let Some(captures) = self.type_alias_regex.captures(line) {
+            let index = parsed.push(
+                line_entity(&captures[2], EntityType::Type, file_path, line_num, line)
+                    .with_metadata("kind".to_string(), "alias".to_string()),
+            );
+            let rest = &line[captures.get(0)?.end() - 1..];
+            let definition = split_balanced(rest, '<', '>').map(|(_, after)| after).unwrap_or(rest);
+            // Object-literal types list their members like an interface
+            if definition.trim_start().trim_start_matches('=').trim_start().starts_with('{') {
+                tracker.expect_body(&captures[2], index, ScopeKind::Type);
+            }
+            (index, captures[1].to_string(), rest)
+        } else if let Some(captures) = self.enum_regex.captures(line) {
+            let index = parsed.push(
+                line_entity(&captures[2], EntityType::Class, file_path, line_num, line)
+                    .with_metadata("kind".to_string(), "enum".to_string()),
+            );
+            if captures[1].split_whitespace().any(|m| m == "const") {
+                parsed.set_metadata(index, "const", "true".to_string());
+            }
+            tracker.expect_body(&captures[2], index, ScopeKind::Type);
+            (index, captures[1].to_string(), "")
+        } else if let Some(captures) = self.class_regex.captures(line) {
+            let index = parsed.push(
+                line_entity(&captures[2], EntityType::Class, file_path, line_num, line)
+                    .with_metadata("kind".to_string(), "class".to_string()),
+            );
+            if captures[1].split_whitespace().any(|m| m == "abstract") {
+                parsed.set_metadata(index, "abstract", "true".to_string());
+            }
+            tracker.expect_body(&captures[2], index, ScopeKind::Type);
+            (index, captures[1].to_string(), &line[captures.get(0)?.end()..])
+        } else if let Some(captures) = self.function_regex.captures(line) {
+            let index = parsed.push(line_entity(&captures[2], EntityType::Function, file_path, line_num, line));
+            if captures[1].split_whitespace().any(|m| m == "async") {
+                parsed.set_metadata(index, "async", "true".to_string());
+            }
+            tracker.expect_body(&captures[2], index, ScopeKind::Function);
+            (index, captures[1].to_string(), &line[captures.get(0)?.end() - 1..])
+        } else if let Some(captures) = self.variable_regex.captures(line) {
+            let name = &captures[2];
+            let initializer = initializer(&line[captures.get(0)?.end()..]);
+            let index = if let Some(function) = initializer.and_then(|init| self.function_value_regex.captures(init)) {
+                // `const handler = async (req) => {}` declares a function
+                let kind = if function.get(2).is_some() { "function" } else { "arrow" };
+                let index = parsed.push(
+                    line_entity(name, EntityType::Function, file_path, line_num, line)
+                        .with_metadata("kind".to_string(), kind.to_string()),
+                );
+                if function.get(1).is_some() {
+                    parsed.set_metadata(index, "async", "true".to_string());
+                }
+                tracker.expect_body(name, index, ScopeKind::Function);
+                index
+            } else {
+                let index = parsed.push(line_entity(name, EntityType::Variable, file_path, line_num, line));
+                if initializer.map(|init| init.trim_start().starts_with('{')).unwrap_or(false) {
+                    parsed.set_metadata(index, "kind", "object".to_string());
+                    tracker.expect_body(name, index, ScopeKind::Type);
+                }
+                index
+            };
+            (index, captures[1].to_string(), "")
+        } else {
+            return self.parse_export_statement(parsed, tracker, parent, file_path, line_num, line);
+        };
+
+        if modifiers.split_whitespace().any(|m| m == "declare") {
+            parsed.set_metadata(index, "ambient", "true".to_string());
+        }
+        if let Some(parent) = parent {
+            parsed.contain(parent, index);
+        }
+        self.record_type_parameters(parsed, index, rest);
+
+        // `export default class Foo` exports `Foo` as `default`
+        let mut modifiers = modifiers.split_whitespace();
+        if modifiers.any(|m| m == "export") {
+            let local = parsed.entities[index].name.clone();
+            let (name, kind) = if modifiers.any(|m| m == "default") { ("default", "default") } else { (local.as_str(), "named") };
+            let export = push_export(parsed, name, kind, file_path, line_num, line);
+            parsed.set_metadata(export, "local", local.clone());
+            parsed.link(export, index, RelationType::References);
+        }
+        Some(index)
+    }
+
+    /// Export lists, re-exports, `export default <expr>` and CommonJS `module.exports`
+    fn parse_export_statement(
+        &self,
+        parsed: &mut ParsedFile,
+        tracker: &mut ScopeTracker,
+        parent: Option<usize>,
+        file_path: &str,
+        line_num: usize,
+        line: &str,
+    ) -> Option<usize> {
+        let mut exports = Vec::new();
+
+        if let Some(captures) = self.export_list_regex.captures(line) {
+            let source = captures.get(2).map(|m| m.as_str().to_string());
+            for specifier in captures[1].split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                let specifier = specifier.trim_start_matches("type ").trim();
+                let (local, exported) = specifier.split_once(" as ").unwrap_or((specifier, specifier));
+                let kind = if source.is_some() { "reexport" } else { "named" };
+                let export = push_export(parsed, exported.trim(), kind, file_path, line_num, line);
+                parsed.set_metadata(export, "local", local.trim().to_string());
+                if let Some(source) = &source {
+                    parsed.set_metadata(export, "source", source.clone());
+                }
+                exports.push(export);
+            }
+        } else if let Some(captures) = self.export_star_regex.captures(line) {
+            let name = captures.get(1).map(|m| m.as_str()).unwrap_or("*");
+            let export = push_export(parsed, name, "reexport", file_path, line_num, line);
+            parsed.set_metadata(export, "source", captures[2].to_string());
+            exports.push(export);
+        } else if let Some(captures) = self.export_default_regex.captures(line) {
+            let export = push_export(parsed, "default", "default", file_path, line_num, line);
+            self.record_exported_value(parsed, tracker, export, &captures[1]);
+            exports.push(export);
+        } else if let Some(captures) = self.commonjs_export_regex.captures(line) {
+            let (name, kind) = match captures.get(1) {
+                Some(name) => (name.as_str(), "named"),
+                None => ("default", "default"),
+            };
+            let export = push_export(parsed, name, kind, file_path, line_num, line);
+            parsed.set_metadata(export, "module_system", "commonjs".to_string());
+            self.record_exported_value(parsed, tracker, export, &captures[2]);
+            exports.push(export);
+        }
+
+        if let Some(parent) = parent {
+            for export in &exports {
+                parsed.contain(parent, *export);
+            }
+        }
+        exports.last().copied()
+    }
+
+    // `export default foo;` names what it exports; `= {` opens an object whose methods are recorded
+    fn record_exported_value(&self, parsed: &mut ParsedFile, tracker: &mut ScopeTracker, export: usize, value: &str) {
+        let value = value.trim().trim_end_matches(';').trim();
+        if value.starts_with('{') {
+            let name = parsed.entities[export].name.clone();
+            tracker.expect_body(&name, export, ScopeKind::Type);
+        } else if !value.is_empty() && value.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '$' || c == '.') {
+            parsed.set_metadata(export, "local", value.to_string());
+        }
+    }
+
+    /// `<T extends X = Y>` goes in metadata; `extends`/`implements` clauses become edges
+    fn record_type_parameters(&self, parsed: &mut ParsedFile, index: usize, rest: &str) {
+        let mut rest = rest;
+        if let Some((generics, after)) = split_balanced(rest, '<', '>') {
+            parsed.set_metadata(index, "generics", split_top_level(generics).join(", "));
+            rest = after;
+        }
+        let entity_type = parsed.entities[index].entity_type.clone();
+        if !matches!(entity_type, EntityType::Class | EntityType::Interface) {
+            return;
+        }
+
+        let heritage = rest.split('{').next().unwrap_or(rest);
+        let keywords: Vec<_> = self.heritage_regex.find_iter(heritage).collect();
+        for (position, keyword) in keywords.iter().enumerate() {
+            let end = keywords.get(position + 1).map(|next| next.start()).unwrap_or(heritage.len());
+            let relationship_type = match keyword.as_str() {
+                "extends" => RelationType::Extends,
+                _ => RelationType::Implements,
+            };
+            for parent in split_top_level(&heritage[keyword.end()..end]) {
+                // `extends Mixin(Base)` names the mixin factory
+                let parent = strip_generics(parent.split('(').next().unwrap_or(parent));
+                if !parent.is_empty() {
+                    parsed.link_symbol(index, parent, relationship_type.clone());
+                }
+            }
+        }
+    }
+
+    /// Members of classes, interfaces, object types, enums and object literals
+    fn parse_member(
+        &self,
+        parsed: &mut ParsedFile,
+        tracker: &mut ScopeTracker,
+        parent: usize,
+        file_path: &str,
+        line_num: usize,
+        line: &str,
+    ) -> Option<usize> {
+        let parent_entity = &parsed.entities[parent];
+        let parent_kind = parent_entity.metadata.get("kind").cloned().unwrap_or_default();
+
+        let index = match parent_entity.entity_type {
+            EntityType::Class if parent_kind == "enum" => {
+                let captures = self.enum_member_regex.captures(line)?;
+                let name = captures[1].trim_matches(|c| c == '"' || c == '\'');
+                parsed.push(
+                    line_entity(name, EntityType::Constant, file_path, line_num, line)
+                        .with_metadata("kind".to_string(), "member".to_string()),
+                )
+            }
+            EntityType::Class => self.parse_class_member(parsed, tracker, file_path, line_num, line)?,
+            EntityType::Interface | EntityType::Type => {
+                let captures = self.signature_regex.captures(line)?;
+                let (entity_type, kind) = match &captures[2] {
+                    ":" => (EntityType::Variable, "property"),
+                    _ => (EntityType::Function, "method"),
+                };
+                let index = parsed.push(
+                    line_entity(&captures[1], entity_type, file_path, line_num, line)
+                        .with_metadata("kind".to_string(), kind.to_string()),
+                );
+                // Nested object types and method bodies hold no members of this type
+                if line.trim_end().ends_with('{') {
+                    tracker.expect_body(&captures[1], index, ScopeKind::Function);
+                }
+                index
+            }
+            // Object literals assigned to a variable or exported
+            _ => self.parse_object_member(parsed, tracker, parent, file_path, line_num, line)?,
+        };
+
+        parsed.contain(parent, index);
+        Some(index)
+    }
+
+    /// Methods, accessors and fields, including `handler = () => {}` fields
+    fn parse_class_member(
+        &self,
+        parsed: &mut ParsedFile,
+        tracker: &mut ScopeTracker,
+        file_path: &str,
+        line_num: usize,
+        line: &str,
+    ) -> Option<usize> {
+        let captures = self.class_member_regex.captures(line)?;
+        let modifiers: Vec<&str> = captures[1].split_whitespace().collect();
+        let name = &captures[3];
+        if is_statement_keyword(name) {
+            return None;
+        }
+
+        let rest = &line[captures.get(4)?.start()..];
+        let is_function_field = initializer(rest).map(|init| self.function_value_regex.is_match(init)).unwrap_or(false);
+        let (entity_type, kind) = match &captures[4] {
+            "(" | "<" if name == "constructor" => (EntityType::Function, "constructor"),
+            "(" | "<" if modifiers.contains(&"get") => (EntityType::Function, "getter"),
+            "(" | "<" if modifiers.contains(&"set") => (EntityType::Function, "setter"),
+            "(" | "<" => (EntityType::Function, "method"),
+            _ if is_function_field => (EntityType::Function, "method"),
+            _ => (EntityType::Variable, "field"),
+        };
+
+        let mut entity = line_entity(name, entity_type.clone(), file_path, line_num, line)
+            .with_metadata("kind".to_string(), kind.to_string());
+        for modifier in ["static", "async", "abstract"] {
+            if modifiers.contains(&modifier) {
+                entity = entity.with_metadata(modifier.to_string(), "true".to_string());
+            }
+        }
+        if name.starts_with('#') || modifiers.contains(&"private") {
+            entity = entity.with_metadata("visibility".to_string(), "private".to_string());
+        } else if modifiers.contains(&"protected") {
+            entity = entity.with_metadata("visibility".to_string(), "protected".to_string());
+        }
+        let index = parsed.push(entity);
+
+        // Field initializers like `state = {` are skipped along with method bodies
+        if entity_type == EntityType::Function || line.trim_end().ends_with('{') {
+            tracker.expect_body(name, index, ScopeKind::Function);
+        }
+        Some(index)
+    }
+
+    /// Shorthand methods and function-valued properties; a `module.exports`
+    /// object also exports each of its keys
+    fn parse_object_member(
+        &self,
+        parsed: &mut ParsedFile,
+        tracker: &mut ScopeTracker,
+        parent: usize,
+        file_path: &str,
+        line_num: usize,
+        line: &str,
+    ) -> Option<usize> {
+        let exports_object = parsed.entities[parent].entity_type == EntityType::Export;
+
+        let function = if let Some(captures) = self.object_method_regex.captures(line).filter(|c| !is_statement_keyword(&c[3])) {
+            let kind = captures.get(2).map(|accessor| if accessor.as_str() == "get" { "getter" } else { "setter" }).unwrap_or("method");
+            Some((captures[3].to_string(), kind, captures.get(1).is_some()))
+        } else if let Some(captures) = self.object_property_regex.captures(line) {
+            self.function_value_regex.captures(&captures[2]).map(|function| {
+                (captures[1].trim_matches(|c| c == '"' || c == '\'').to_string(), "method", function.get(1).is_some())
+            })
+        } else {
+            None
+        };
+
+        if let Some((name, kind, is_async)) = function {
+            let index = parsed.push(
+                line_entity(&name, EntityType::Function, file_path, line_num, line)
+                    .with_metadata("kind".to_string(), kind.to_string()),
+            );
+            if is_async {
+                parsed.set_metadata(index, "async", "true".to_string());
+            }
+            tracker.expect_body(&name, index, ScopeKind::Function);
+            return Some(index);
+        }
+
+        // `module.exports = { parse, format: formatDate }`
+        if exports_object {
+            let key = line.trim().split([':', ',']).next()?.trim().trim_matches(|c| c == '"' || c == '\'');
+            if !key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '$') {
+                return Some(push_export(parsed, key, "named", file_path, line_num, line));
+            }
+        }
+        None
+    }
+}
+
+fn push_export(parsed: &mut ParsedFile, name: &str, kind: &str, file_path: &str, line_num: usize, line: &str) -> usize {
+    parsed.push(
+        line_entity(name, EntityType::Export, file_path, line_num, line)
+            .with_metadata("kind".to_string(), kind.to_string()),
+    )
+}
+
+/// Text after the `=` of a declaration, skipping a type annotation like `: (a: A) => B`
+fn initializer(rest: &str) -> Option<&str> {
+    let bytes = rest.as_bytes();
+    (0..bytes.len())
+        .find(|&i| bytes[i] == b'=' && bytes.get(i + 1) != Some(&b'>') && bytes.get(i + 1) != Some(&b'='))
+        .map(|i| &rest[i + 1..])
+}
+
+fn is_statement_keyword(word: &str) -> bool {
+    matches!(word, "if" | "for" | "while" | "switch" | "catch" | "return" | "function" | "static" | "else" | "do" | "try")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_support::ParsedSource;
+
+    #[test]
+    fn test_typescript_type_level_entities() -> Result<()> {
+        let source = r#"import { Request } from 'express';
+
+export interface Repository<T extends Entity, K = string> extends Readable, Disposable {
+    readonly name: string;
+    find(id: K): Promise<T | undefined>;
+    options?: {
+        retries: number;
+    };
+}
+
+export type Handler<T> = (req: Request) => Promise<T>;
+type Point = {
+    x: number;
+    y: number;
+};
+
+export const enum Direction {
+    Up = 1,
+    Down,
+}
+
+export abstract class BaseStore<T> extends EventEmitter implements Repository<T>, Iterable<T> {
+    protected items: T[] = [];
+}
+
+export namespace Validation {
+    export interface StringValidator {
+        isAcceptable(s: string): boolean;
+    }
+    /**
+     * Checks a value against the pattern.
+     * @param value - the text to check
+     * @returns whether it matched
+     */
+    export function validate(value: string): boolean {
+        const pattern = /^[a-z]+$/;
+        return pattern.test(value);
+    }
+}
+"#;
+        let (entities, relationships) = JavaScriptParser::new()?.parse(source, "src/store.ts")?;
+        let find = |name: &str| entities.iter().find(|e| e.name == name).unwrap_or_else(|| panic!("missing {}", name));
+        let meta = |name: &str, key: &str| find(name).metadata.get(key).cloned();
+
+        assert_eq!(find("express").entity_type, EntityType::Import);
+        assert_eq!(find("Repository").entity_type, EntityType::Interface);
+        assert_eq!(meta("Repository", "generics").as_deref(), Some("T extends Entity, K = string"));
+        assert_eq!((find("Repository").line_start, find("Repository").line_end), (3, 9));
+        assert_eq!(meta("name", "kind").as_deref(), Some("property"));
+        assert_eq!(meta("find", "kind").as_deref(), Some("method"));
+        assert!(!entities.iter().any(|e| e.name == "retries"));
+
+        assert_eq!(find("Handler").entity_type, EntityType::Type);
+        assert_eq!(meta("Handler", "generics").as_deref(), Some("T"));
+        assert_eq!(meta("y", "enclosing").as_deref(), Some("Point"));
+        assert_eq!(meta("Direction", "kind").as_deref(), Some("enum"));
+        assert_eq!(meta("Direction", "const").as_deref(), Some("true"));
+        assert_eq!(find("Down").entity_type, EntityType::Constant);
+        assert_eq!(meta("BaseStore", "abstract").as_deref(), Some("true"));
+        assert_eq!(meta("BaseStore", "generics").as_deref(), Some("T"));
+
+        assert_eq!(meta("Validation", "kind").as_deref(), Some("namespace"));
+        assert_eq!(meta("StringValidator", "enclosing").as_deref(), Some("Validation"));
+        assert_eq!(meta("isAcceptable", "enclosing").as_deref(), Some("StringValidator"));
+        assert_eq!(meta("validate", "enclosing").as_deref(), Some("Validation"));
+        assert_eq!(meta("validate", "doc").as_deref(), Some("Checks a value against the pattern."));
+        assert_eq!(meta("validate", "doc_params").as_deref(), Some("value: the text to check"));
+        assert_eq!(meta("validate", "doc_returns").as_deref(), Some("whether it matched"));
+        assert_eq!(meta("StringValidator", "doc"), None);
+        assert!(!entities.iter().any(|e| e.name == "pattern"));
+
+        let edges = |name: &str| {
+            let from = &find(name).id;
+            let mut edges: Vec<_> = relationships
+                .iter()
+                .filter(|r| &r.from_entity == from && r.relationship_type != RelationType::Contains)
+                .map(|r| (r.relationship_type.as_str(), r.to_entity.as_str()))
+                .collect();
+            edges.sort();
+            edges
+        };
+        assert_eq!(edges("Repository"), vec![("extends", "Disposable"), ("extends", "Readable")]);
+        assert_eq!(
+            edges("BaseStore"),
+            vec![("extends", "EventEmitter"), ("implements", "Iterable"), ("implements", "Repository")]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ambient_declarations() -> Result<()> {
+        let source = r#"declare module "express-serve-static-core" {
+    interface Request {
+        user?: User;
+    }
+}
+
+declare global {
+    interface Window { analytics: Analytics }
+}
+
+export declare function track(event: string): void;
+declare const VERSION: string;
+"#;
+        let (entities, _) = JavaScriptParser::new()?.parse(source, "types/index.d.ts")?;
+        let find = |name: &str| entities.iter().find(|e| e.name == name).unwrap_or_else(|| panic!("missing {}", name));
+
+        assert_eq!(find("express-serve-static-core").metadata.get("kind").map(String::as_str), Some("module"));
+        assert_eq!(find("Request").metadata.get("enclosing").map(String::as_str), Some("express-serve-static-core"));
+        assert_eq!(find("Window").metadata.get("enclosing").map(String::as_str), Some("global"));
+        assert_eq!(find("track").line_end, 11);
+        assert!(entities.iter().all(|e| e.metadata.get("ambient").map(String::as_str) == Some("true")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_modern_javascript_functions_and_exports() -> Result<()> {
+        let source = r#"const path = require('path');
+import {
+    readFile,
+    writeFile,
+} from 'fs/promises';
+
+export const fetchUser = async (id) => {
+    const inner = () => id;
+    return inner();
+};
+
+export default class UserService extends Base {
+    #cache = new Map();
+    static instance = null;
+    constructor(db) {
+        this.db = db;
+    }
+    async load(id) {
+        if (id) {
+            return this.db.get(id);
+        }
+    }
+    get size() {
+        return this.#cache.size;
+    }
+    handleClick = (event) => {
+        this.load(event.id);
+    };
+}
+
+const helpers = {
+    format(value) {
+        return String(value);
+    },
+    parse: function (text) {
+        return JSON.parse(text);
+    },
+};
+
+export { helpers, fetchUser as getUser };
+export * from './models';
+
+module.exports = {
+    helpers,
+    ping: () => 'pong',
+};
+"#;
+        let (entities, relationships) = JavaScriptParser::new()?.parse(source, "src/user.js")?;
+        let find = |name: &str| entities.iter().find(|e| e.name == name).unwrap_or_else(|| panic!("missing {}", name));
+        let meta = |name: &str, key: &str| find(name).metadata.get(key).cloned();
+        let exports: Vec<_> =
+            entities.iter().filter(|e| e.entity_type == EntityType::Export).map(|e| e.name.as_str()).collect();
+
+        assert_eq!(meta("path", "kind").as_deref(), Some("require"));
+        assert_eq!((find("fs/promises").line_start, find("fs/promises").line_end), (2, 5));
+        assert!(!entities.iter().any(|e| e.name == "readFile"));
+
+        assert_eq!(find("fetchUser").entity_type, EntityType::Function);
+        assert_eq!(meta("fetchUser", "kind").as_deref(), Some("arrow"));
+        assert_eq!(meta("fetchUser", "async").as_deref(), Some("true"));
+        assert_eq!(find("fetchUser").line_end, 10);
+        assert!(!entities.iter().any(|e| e.name == "inner"));
+
+        assert_eq!(meta("constructor", "kind").as_deref(), Some("constructor"));
+        assert_eq!(meta("load", "enclosing").as_deref(), Some("UserService"));
+        assert_eq!(meta("load", "async").as_deref(), Some("true"));
+        assert_eq!(meta("size", "kind").as_deref(), Some("getter"));
+        assert_eq!(meta("handleClick", "kind").as_deref(), Some("method"));
+        assert_eq!(meta("#cache", "visibility").as_deref(), Some("private"));
+        assert_eq!(meta("instance", "static").as_deref(), Some("true"));
+        assert!(!entities.iter().any(|e| e.name == "if" || e.name == "return"));
+
+        assert_eq!(meta("format", "enclosing").as_deref(), Some("helpers"));
+        assert_eq!(meta("parse", "kind").as_deref(), Some("method"));
+        assert_eq!(meta("ping", "enclosing").as_deref(), Some("default"));
+
+        assert_eq!(exports, vec!["fetchUser", "default", "helpers", "getUser", "*", "default", "helpers"]);
+        assert_eq!(meta("getUser", "local").as_deref(), Some("fetchUser"));
+        let default_export = entities.iter().find(|e| e.name == "default").unwrap();
+        assert!(relationships.iter().any(|r| r.from_entity == default_export.id
+            && r.to_entity == find("UserService").id
+            && r.relationship_type == RelationType::References));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_braces_in_regex_literals() -> Result<()> {
+        let source = r#"const open = /[{]/;
+
+function ratio(a, b) {
+    return /\{$/.test(a) ? a / b / 2 : [a].map(x => x.split(/[{]/, 2));
+}
+
+export function after() {
+    return 1;
+}
+"#;
+        let file = ParsedSource::new(&JavaScriptParser::new()?, "src/lex.js", source)?;
+        assert_eq!(file.find("ratio").line_end, 5);
+        assert_eq!(file.find("after").line_end, 9);
+        assert_eq!(file.meta("after", "enclosing"), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,408 @@
+use super::java::split_annotations;
+use super::parsed::ParsedFile;
+use super::signature::{record_signatures, SignatureStyle};
+use super::scope::{ScopeKind, ScopeTracker};
+use super::{line_entity, split_balanced, split_top_level, strip_generics};
+use crate::{CodeEntity, EntityType, RelationType, Relationship};
+use anyhow::Result;
+use regex::Regex;
+
+pub(crate) struct KotlinParser {
+    package_regex: Regex,
+    import_regex: Regex,
+    type_regex: Regex,
+    companion_regex: Regex,
+    typealias_regex: Regex,
+    constructor_prefix_regex: Regex,
+    function_regex: Regex,
+    property_regex: Regex,
+    parameter_property_regex: Regex,
+}
+
+impl KotlinParser {
+    pub(crate) fn new() -> Result<Self> {
+        Ok(Self {
+            package_regex: Regex::new(r"^\s*package\s+([\w.]+)")?,
+            import_regex: Regex::new(r"^\s*import\s+([\w.]+(?:\.\*)?)(?:\s+as\s+(\w+))?")?,
+            type_regex: Regex::new(
+                r"^\s*((?:(?:public|private|protected|internal|data|enum|sealed|abstract|open|inner|annotation|value|inline|final|expect|actual|fun)\s+)*)(class|interface|object)\s+(\w+)",
+            )?,
+            companion_regex: Regex::new(r"^\s*(?:(?:public|private|protected|internal)\s+)?companion\s+object(?:\s+(\w+))?")?,
+            typealias_regex: Regex::new(r"^\s*(?:(?:public|private|protected|internal|expect|actual)\s+)*typealias\s+(\w+)")?,
+            constructor_prefix_regex: Regex::new(r"^\s*(?:[\w@]+\s+)*constructor")?,
+            function_regex: Regex::new(
+                r"^\s*((?:(?:public|private|protected|internal|open|override|abstract|final|suspend|inline|operator|infix|tailrec|external|expect|actual)\s+)*)fun\s+(?:<[^>]*>\s*)?(?:([\w.]+(?:<[^>]*>)?)\.)?(\w+)\s*\(",
+            )?,
+            property_regex: Regex::new(
+                r"^\s*((?:(?:public|private|protected|internal|open|override|abstract|final|const|lateinit|expect|actual)\s+)*)(val|var)\s+(?:<[^>]*>\s*)?(?:([\w.]+)\.)?(\w+)",
+            )?,
+            parameter_property_regex: Regex::new(r"\b(val|var)\s+(\w+)")?,
+        })
+    }
+
+    pub(crate) fn parse(&self, content: &str, file_path: &str) -> Result<(Vec<CodeEntity>, Vec<Relationship>)> {
+        let mut parsed = ParsedFile::default();
+        let mut tracker = ScopeTracker::new();
+        let mut package = None;
+        let mut imports = Vec::new();
+        let mut annotations: Vec<String> = Vec::new();
+        // A class header whose primary constructor continues on the following lines
+        let mut open_header: Option<(usize, String)> = None;
+
+        for (line_num, line) in content.lines().enumerate() {
+            if let Some((index, mut header)) = open_header.take() {
+                header.push(' ');
+                header.push_str(line.trim());
+                if is_balanced(&header) {
+                    self.parse_header(&mut parsed, index, &header, file_path, line_num, line);
+                } else {
+                    open_header = Some((index, header));
+                }
+                for closed in tracker.process_line(line) {
+                    parsed.set_line_end(closed, line_num);
+                }
+                continue;
+            }
+
+            let trimmed = line.trim_start();
+            let enclosing = tracker
+                .current_body()
+                .filter(|scope| scope.kind == ScopeKind::Type)
+                .and_then(|scope| Some((scope.entity?, scope.name.clone())));
+            let is_comment = tracker.in_block_comment() || trimmed.starts_with("//") || trimmed.starts_with("/*") || trimmed.starts_with('*');
+
+            if !is_comment && (tracker.depth() == 0 || enclosing.is_some()) {
+                let (found, declaration) = split_annotations(line);
+                annotations.extend(found);
+
+                if !declaration.trim().is_empty() {
+                    let parent = enclosing.as_ref().map(|(parent, _)| *parent);
+                    let entity = if let Some(captures) = self.package_regex.captures(declaration) {
+                        package = Some(parsed.push(line_entity(&captures[1], EntityType::Module, file_path, line_num, line)));
+                        None
+                    } else if let Some(captures) = self.import_regex.captures(declaration) {
+                        let mut entity = line_entity(&captures[1], EntityType::Import, file_path, line_num, line);
+                        if let Some(alias) = captures.get(2) {
+                            entity = entity.with_metadata("alias".to_string(), alias.as_str().to_string());
+                        }
+                        imports.push(parsed.push(entity));
+                        None
+                    } else if let Some(captures) = self.typealias_regex.captures(declaration) {
+                        let index = parsed.push(
+                            line_entity(&captures[1], EntityType::Type, file_path, line_num, line)
+                                .with_metadata("kind".to_string(), "alias".to_string()),
+                        );
+                        match (parent, package) {
+                            (Some(parent), _) => parsed.contain(parent, index),
+                            (None, Some(package)) => parsed.link(package, index, RelationType::Contains),
+                            (None, None) => {}
+                        }
+                        Some(index)
+                    } else {
+                        match self.parse_type(&mut parsed, &mut tracker, declaration, parent, package, file_path, line_num, line) {
+                            Some((index, header)) if is_balanced(header) => {
+                                self.parse_header(&mut parsed, index, header, file_path, line_num, line);
+                                Some(index)
+                            }
+                            Some((index, header)) => {
+                                open_header = Some((index, header.trim().to_string()));
+                                Some(index)
+                            }
+                            None => self.parse_member(&mut parsed, &mut tracker, declaration, parent, file_path, line_num, line),
+                        }
+                    };
+
+                    if let Some(index) = entity {
+                        if !annotations.is_empty() {
+                            parsed.set_metadata(index, "annotations", annotations.join(","));
+                        }
+                    }
+                    annotations.clear();
+                }
+            }
+
+            for closed in tracker.process_line(line) {
+                parsed.set_line_end(closed, line_num);
+            }
+        }
+
+        if let Some(package) = package {
+            for import in imports {
+                parsed.link(package, import, RelationType::Imports);
+            }
+        }
+
+        record_signatures(&mut parsed.entities, content, SignatureStyle::Kotlin);
+        Ok(parsed.finish())
+    }
+
+    /// Classes (including data, enum and sealed classes), interfaces and objects.
+    /// Returns the entity and the header text following its name.
+    #[allow(clippy::too_many_arguments)]
+    fn parse_type<'a>(
+        &self,
+        parsed: &mut ParsedFile,
+        tracker: &mut ScopeTracker,
+        declaration: &'a str,
+        parent: Option<usize>,
+        package: Option<usize>,
+        file_path: &str,
+        line_num: usize,
+        line: &str,
+    ) -> Option<(usize, &'a str)> {
+        let (name, keyword, modifiers, end) = if let Some(captures) = self.type_regex.captures(declaration) {
+            let end = captures.get(0)?.end();
+            (captures[3].to_string(), captures[2].to_string(), captures[1].to_string(), end)
+        } else {
+            let captures = self.companion_regex.captures(declaration)?;
+            let name = captures.get(1).map(|m| m.as_str()).unwrap_or("Companion").to_string();
+            (name, "object".to_string(), "companion".to_string(), captures.get(0)?.end())
+        };
+
+        let has_modifier = |modifier: &str| modifiers.split_whitespace().any(|m| m == modifier);
+        let (entity_type, kind) = match keyword.as_str() {
+            "interface" => (EntityType::Interface, "interface"),
+            "object" if has_modifier("companion") => (EntityType::Class, "companion object"),
+            "object" => (EntityType::Class, "object"),
+            _ if has_modifier("data") => (EntityType::Class, "data class"),
+            _ if has_modifier("enum") => (EntityType::Class, "enum"),
+            _ if has_modifier("annotation") => (EntityType::Interface, "annotation"),
+            _ if has_modifier("sealed") => (EntityType::Class, "sealed class"),
+            _ => (EntityType::Class, "class"),
+        };
+
+        let index = parsed.push(
+            line_entity(&name, entity_type, file_path, line_num, line)
+                .with_metadata("kind".to_string(), kind.to_string()),
+        );
+        if has_modifier("abstract") {
+            parsed.set_metadata(index, "abstract", "true".to_string());
+        }
+        match (parent, package) {
+            (Some(parent), _) => parsed.contain(parent, index),
+            (None, Some(package)) => parsed.link(package, index, RelationType::Contains),
+            (None, None) => {}
+        }
+        tracker.expect_body(&name, index, ScopeKind::Type);
+
+        Some((index, &declaration[end..]))
+    }
+
+    /// Primary constructor properties and supertypes from a class header
+    fn parse_header(&self, parsed: &mut ParsedFile, index: usize, header: &str, file_path: &str, line_num: usize, line: &str) {
+        let mut rest = header;
+        if let Some((_, after_generics)) = split_balanced(rest, '<', '>') {
+            rest = after_generics;
+        }
+        if let Some(prefix) = self.constructor_prefix_regex.find(rest) {
+            rest = &rest[prefix.end()..];
+        }
+        // `val`/`var` parameters of the primary constructor are properties
+        if let Some((parameters, after_parameters)) = split_balanced(rest, '(', ')') {
+            for captures in self.parameter_property_regex.captures_iter(parameters) {
+                let property = parsed.push(
+                    line_entity(&captures[2], EntityType::Variable, file_path, line_num, line)
+                        .with_metadata("kind".to_string(), "property".to_string())
+                        .with_metadata("mutable".to_string(), (&captures[1] == "var").to_string()),
+                );
+                parsed.contain(index, property);
+            }
+            rest = after_parameters;
+        }
+
+        // Supertypes: a constructor call marks the superclass, the rest are interfaces
+        if let Some(supertypes) = rest.trim_start().strip_prefix(':') {
+            let supertypes = supertypes.split('{').next().unwrap_or(supertypes);
+            let supertypes = supertypes.split(" where ").next().unwrap_or(supertypes);
+            for supertype in split_top_level(supertypes) {
+                let supertype = supertype.split(" by ").next().unwrap_or(supertype);
+                let relationship_type = if parsed.entities[index].entity_type == EntityType::Interface || supertype.contains('(') {
+                    RelationType::Extends
+                } else {
+                    RelationType::Implements
+                };
+                let supertype = strip_generics(supertype.split('(').next().unwrap_or(supertype));
+                parsed.link_symbol(index, supertype, relationship_type);
+            }
+        }
+    }
+
+    /// Functions and properties, at the top level or in a type body
+    #[allow(clippy::too_many_arguments)]
+    fn parse_member(
+        &self,
+        parsed: &mut ParsedFile,
+        tracker: &mut ScopeTracker,
+        declaration: &str,
+        parent: Option<usize>,
+        file_path: &str,
+        line_num: usize,
+        line: &str,
+    ) -> Option<usize> {
+        if let Some(captures) = self.function_regex.captures(declaration) {
+            let name = &captures[3];
+            let mut entity = line_entity(name, EntityType::Function, file_path, line_num, line)
+                .with_metadata("kind".to_string(), if parent.is_some() { "method" } else { "function" }.to_string());
+            if let Some(receiver) = captures.get(2) {
+                entity = entity.with_metadata("receiver".to_string(), strip_generics(receiver.as_str()).to_string());
+            }
+            if captures[1].split_whitespace().any(|m| m == "suspend") {
+                entity = entity.with_metadata("async".to_string(), "true".to_string());
+            }
+            let index = parsed.push(entity);
+            if let Some(parent) = parent {
+                parsed.contain(parent, index);
+            }
+
+            // Expression bodies (`fun twice(x: Int) = x * 2`) have no braces to track
+            let rest = &declaration[captures.get(0)?.end()..];
+            if rest.contains('{') || !rest.contains('=') {
+                tracker.expect_body(name, index, ScopeKind::Function);
+            }
+            return Some(index);
+        }
+
+        let captures = self.property_regex.captures(declaration)?;
+        let entity_type = if captures[1].split_whitespace().any(|m| m == "const") {
+            EntityType::Constant
+        } else {
+            EntityType::Variable
+        };
+        let mut entity = line_entity(&captures[4], entity_type, file_path, line_num, line)
+            .with_metadata("kind".to_string(), "property".to_string())
+            .with_metadata("mutable".to_string(), (&captures[2] == "var").to_string());
+        if let Some(receiver) = captures.get(3) {
+            entity = entity.with_metadata("receiver".to_string(), receiver.as_str().to_string());
+        }
+        let index = parsed.push(entity);
+        if let Some(parent) = parent {
+            parsed.contain(parent, index);
+        }
+        Some(index)
+    }
+}
+
+fn is_balanced(header: &str) -> bool {
+    header.matches('(').count() <= header.matches(')').count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_support::ParsedSource;
+
+    const SOURCE: &str = r#"package com.example.orders
+
+import kotlinx.coroutines.flow.Flow
+import com.example.core.Logger as Log
+
+const val MAX_ORDERS = 100
+
+@Serializable
+data class Order(val id: Long, var total: Double) : Entity(id), Comparable<Order> {
+    override fun compareTo(other: Order): Int = total.compareTo(other.total)
+
+    companion object {
+        fun empty() = Order(0, 0.0)
+    }
+
+    inner class Line(val sku: String)
+}
+
+sealed interface Event : Named
+
+class OrderRepository @Inject constructor(private val db: Database) : Repository<Order> by db {
+    private val cache = mutableMapOf<Long, Order>()
+
+    @Throws(IOException::class)
+    suspend fun load(id: Long): Order {
+        val found = db.find(id)
+        fun local() {}
+        return found
+    }
+}
+
+fun String.toOrderId(): Long = toLong()
+
+class Invoice(
+    val number: String,
+    val order: Order,
+) : Document {
+    fun render() {}
+}
+"#;
+
+    #[test]
+    fn test_kotlin_entities_and_edges() -> Result<()> {
+        let file = ParsedSource::new(&KotlinParser::new()?, "src/Orders.kt", SOURCE)?;
+
+        assert_eq!(file.find("com.example.orders").entity_type, EntityType::Module);
+        assert_eq!(file.meta("com.example.core.Logger", "alias"), Some("Log"));
+        assert_eq!(file.edges("com.example.orders").len(), 2);
+        assert_eq!(file.find("MAX_ORDERS").entity_type, EntityType::Constant);
+
+        assert_eq!(file.meta("Order", "kind"), Some("data class"));
+        assert_eq!(file.meta("Order", "annotations"), Some("Serializable"));
+        assert_eq!((file.find("Order").line_start, file.find("Order").line_end), (9, 17));
+        assert_eq!(file.edges("Order"), vec![("extends", "Entity"), ("implements", "Comparable")]);
+        assert_eq!(file.meta("total", "enclosing"), Some("Order"));
+        assert_eq!(file.meta("total", "mutable"), Some("true"));
+        assert_eq!(file.meta("compareTo", "enclosing"), Some("Order"));
+        assert_eq!(file.meta("Companion", "enclosing"), Some("Order"));
+        assert_eq!(file.meta("empty", "enclosing"), Some("Companion"));
+        assert_eq!(file.meta("Line", "enclosing"), Some("Order"));
+
+        assert_eq!(file.find("Event").entity_type, EntityType::Interface);
+        assert_eq!(file.edges("Event"), vec![("extends", "Named")]);
+        assert_eq!(file.edges("OrderRepository"), vec![("implements", "Repository")]);
+        assert_eq!(file.meta("db", "enclosing"), Some("OrderRepository"));
+        assert_eq!(file.meta("cache", "enclosing"), Some("OrderRepository"));
+        assert_eq!(file.meta("load", "async"), Some("true"));
+        assert_eq!(file.meta("load", "annotations"), Some("Throws"));
+        assert!(!file.has("found") && !file.has("local"), "function bodies are skipped");
+        assert_eq!(file.meta("toOrderId", "receiver"), Some("String"));
+        assert_eq!(file.meta("number", "enclosing"), Some("Invoice"));
+        assert_eq!(file.meta("render", "enclosing"), Some("Invoice"));
+        assert_eq!(file.edges("Invoice"), vec![("implements", "Document")]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_kotlin_objects_aliases_and_strings() -> Result<()> {
+        let source = r#"package util
+
+fun <T> List<T>.second(): T = this[1]
+
+object Registry : Service {
+    val label = "${name} {"
+    private val raw = """
+        }
+    """
+    fun register() {}
+}
+
+typealias Handler = (String) -> Unit
+
+class Box<T : Any>(val value: T) where T : Comparable<T> {
+    private typealias Key = String
+}
+"#;
+        let file = ParsedSource::new(&KotlinParser::new()?, "src/Util.kt", source)?;
+        assert_eq!(file.meta("second", "receiver"), Some("List"));
+        assert_eq!(file.meta("Registry", "kind"), Some("object"));
+        assert_eq!(file.edges("Registry"), vec![("implements", "Service")]);
+        // Templates and raw strings keep their braces to themselves
+        assert_eq!(file.find("Registry").line_end, 11);
+        assert_eq!(file.members("Registry"), vec!["label", "raw", "register"]);
+
+        assert_eq!(file.find("Handler").entity_type, EntityType::Type);
+        assert_eq!(file.meta("Handler", "kind"), Some("alias"));
+        assert!(file.members("util").contains(&"Handler"));
+        // Bounds and `where` clauses are not supertypes
+        assert!(file.edges("Box").is_empty());
+        assert_eq!(file.members("Box"), vec!["Key", "value"]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,426 @@
+// Size and complexity metrics for every function and type a parser found,
+// measured over the entity's lines once its extent is known. Comments and
+// string literals are blanked out first so keywords inside them don't count.
+
+use crate::metrics::ratio;
+use crate::{CodeEntity, EntityMetrics, EntityType};
+
+/// How blocks are delimited when measuring nesting
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Blocks {
+    Braces,
+    Indentation,
+}
+
+/// The lexical details of a language that metrics depend on
+#[derive(Debug, Clone, Copy)]
+struct MetricsSyntax {
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    /// Keywords that each add a branch
+    branches: &'static [&'static str],
+    /// Operators that each add a branch, such as `&&`
+    branch_operators: &'static [&'static str],
+    /// `cond ? a : b`
+    ternary: bool,
+    /// Rust `match` arms (`=>`) each add a branch, less one per `match`
+    match_arms: bool,
+    /// Python triple-quoted strings and docstrings
+    triple_quotes: bool,
+    /// Whether `'` always starts a string rather than a char literal or lifetime
+    single_quoted_strings: bool,
+    /// Rust `r"..."` and `r#"..."#`, where `\` is not an escape
+    raw_strings: bool,
+    blocks: Blocks,
+}
+
+const C_FAMILY: MetricsSyntax = MetricsSyntax {
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    branches: &["if", "for", "foreach", "while", "case", "catch"],
+    branch_operators: &["&&", "||"],
+    ternary: true,
+    match_arms: false,
+    triple_quotes: false,
+    single_quoted_strings: false,
+    raw_strings: false,
+    blocks: Blocks::Braces,
+};
+
+impl MetricsSyntax {
+    fn for_language(language: &str) -> Self {
+        match language {
+            "rust" => MetricsSyntax {
+                branches: &["if", "for", "while"],
+                ternary: false,
+                match_arms: true,
+                raw_strings: true,
+                ..C_FAMILY
+            },
+            "python" => MetricsSyntax {
+                line_comments: &["#"],
+                block_comment: None,
+                branches: &["if", "elif", "for", "while", "except", "case", "and", "or"],
+                branch_operators: &[],
+                ternary: false,
+                triple_quotes: true,
+                single_quoted_strings: true,
+                blocks: Blocks::Indentation,
+                ..C_FAMILY
+            },
+            "ruby" => MetricsSyntax {
+                line_comments: &["#"],
+                block_comment: Some(("=begin", "=end")),
+                branches: &["if", "elsif", "unless", "while", "until", "for", "when", "rescue", "and", "or"],
+                single_quoted_strings: true,
+                blocks: Blocks::Indentation,
+                ..C_FAMILY
+            },
+            "go" => MetricsSyntax { ternary: false, ..C_FAMILY },
+            "kotlin" => MetricsSyntax {
+                branch_operators: &["&&", "||", "?:"],
+                ternary: false,
+                ..C_FAMILY
+            },
+            "swift" => MetricsSyntax {
+                branches: &["if", "guard", "for", "while", "case", "catch"],
+                ..C_FAMILY
+            },
+            "javascript" | "typescript" => MetricsSyntax { single_quoted_strings: true, ..C_FAMILY },
+            "php" => MetricsSyntax {
+                line_comments: &["//", "#"],
+                branches: &["if", "elseif", "for", "foreach", "while", "case", "catch"],
+                single_quoted_strings: true,
+                ..C_FAMILY
+            },
+            _ => C_FAMILY,
+        }
+    }
+}
+
+/// One source line with comments and string contents removed
+#[derive(Debug, Default)]
+struct CodeLine {
+    code: String,
+    has_comment: bool,
+    indent: usize,
+}
+
+/// Comment or string state carried from one line to the next
+#[derive(Debug, Default)]
+struct LexState {
+    /// The delimiter closing an open block comment or docstring
+    in_comment: Option<&'static str>,
+    /// The delimiter closing an open multi-line string
+    in_string: Option<&'static str>,
+}
+
+/// Fill in `metrics` for the functions and types among `entities`
+pub(crate) fn record_metrics(language: &str, entities: &mut [CodeEntity], content: &str) {
+    let syntax = MetricsSyntax::for_language(language);
+    let lines = strip_lines(content, &syntax);
+    for entity in entities.iter_mut() {
+        if matches!(entity.entity_type, EntityType::Function | EntityType::Class | EntityType::Interface | EntityType::Type) {
+            entity.metrics = Some(measure(entity, &lines, &syntax));
+        }
+    }
+}
+
+fn measure(entity: &CodeEntity, lines: &[CodeLine], syntax: &MetricsSyntax) -> EntityMetrics {
+    let start = entity.line_start.saturating_sub(1) as usize;
+    let end = (entity.line_end as usize).clamp(start + 1, lines.len().max(start + 1)).min(lines.len());
+    let span = lines.get(start..end).unwrap_or_default();
+
+    let lines_of_code = span.iter().filter(|line| !line.code.trim().is_empty()).count() as u32;
+    let comment_lines = span.iter().filter(|line| line.code.trim().is_empty() && line.has_comment).count() as u32;
+
+    let mut branches = 0;
+    let mut match_keywords = 0;
+    let mut match_arms = 0;
+    for line in span {
+        for word in words(&line.code) {
+            if syntax.branches.contains(&word) {
+                branches += 1;
+            } else if syntax.match_arms && word == "match" {
+                match_keywords += 1;
+            }
+        }
+        branches += syntax.branch_operators.iter().map(|op| line.code.matches(op).count() as u32).sum::<u32>();
+        if syntax.ternary {
+            branches += line.code.matches(" ? ").count() as u32;
+        }
+        if syntax.match_arms {
+            match_arms += line.code.matches("=>").count() as u32;
+        }
+    }
+    branches += match_arms.saturating_sub(match_keywords);
+
+    let max_nesting = match syntax.blocks {
+        Blocks::Braces => brace_nesting(span),
+        Blocks::Indentation => indent_nesting(span),
+    };
+
+    let param_count = entity
+        .signature
+        .as_ref()
+        .and_then(|signature| signature.params.as_ref())
+        .map(|params| params.iter().filter(|p| !is_receiver(&p.name)).count() as u32)
+        .unwrap_or(0);
+
+    EntityMetrics {
+        lines_of_code,
+        comment_lines,
+        comment_ratio: ratio(comment_lines, lines_of_code + comment_lines),
+        cyclomatic_complexity: 1 + branches,
+        max_nesting,
+        param_count,
+    }
+}
+
+fn is_receiver(name: &str) -> bool {
+    let name = name.trim_start_matches('&').trim_start_matches("mut ").trim();
+    let name = name.split_whitespace().last().unwrap_or(name);
+    matches!(name, "self" | "cls" | "this")
+}
+
+// Depth of the deepest `{` below the one opening the body
+fn brace_nesting(span: &[CodeLine]) -> u32 {
+    let mut depth = 0u32;
+    let mut deepest = 0u32;
+    for c in span.iter().flat_map(|line| line.code.chars()) {
+        match c {
+            '{' => {
+                depth += 1;
+                deepest = deepest.max(depth);
+            }
+            '}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    deepest.saturating_sub(1)
+}
+
+// Indentation levels below the first line of the body. Continuation lines
+// inside brackets don't start a statement and are skipped.
+fn indent_nesting(span: &[CodeLine]) -> u32 {
+    let Some(header) = span.first() else { return 0 };
+    let mut brackets = 0i32;
+    let mut statements = Vec::new();
+    for line in span {
+        if brackets <= 0 && !line.code.trim().is_empty() {
+            statements.push(line.indent);
+        }
+        for c in line.code.chars() {
+            match c {
+                '(' | '[' | '{' => brackets += 1,
+                ')' | ']' | '}' => brackets -= 1,
+                _ => {}
+            }
+        }
+    }
+    let Some(&body) = statements.iter().skip(1).find(|&&indent| indent > header.indent) else { return 0 };
+    let unit = body - header.indent;
+    statements.iter().skip(1).map(|&indent| (indent.saturating_sub(body) / unit) as u32).max().unwrap_or(0)
+}
+
+fn words(code: &str) -> impl Iterator<Item = &str> {
+    code.split(|c: char| !(c.is_alphanumeric() || c == '_')).filter(|w| !w.is_empty())
+}
+
+/// Each line of `content` with comments removed and string literals emptied
+pub(super) fn code_lines(language: &str, content: &str) -> Vec<String> {
+    strip_lines(content, &MetricsSyntax::for_language(language)).into_iter().map(|line| line.code).collect()
+}
+
+fn strip_lines(content: &str, syntax: &MetricsSyntax) -> Vec<CodeLine> {
+    let mut state = LexState::default();
+    content.lines().map(|line| strip_line(line, syntax, &mut state)).collect()
+}
+
+// Keeps the code of a line, with string literals reduced to empty quotes
+fn strip_line(line: &str, syntax: &MetricsSyntax, state: &mut LexState) -> CodeLine {
+    let indent = line.chars().take_while(|c| c.is_whitespace()).map(|c| if c == '\t' { 4 } else { 1 }).sum();
+    let mut result = CodeLine {
+        indent,
+        has_comment: state.in_comment.is_some(),
+        ..Default::default()
+    };
+    let mut rest = line;
+
+    while !rest.is_empty() {
+        if let Some(end) = state.in_comment {
+            result.has_comment = true;
+            match rest.find(end) {
+                Some(at) => {
+                    state.in_comment = None;
+                    rest = &rest[at + end.len()..];
+                }
+                None => break,
+            }
+            continue;
+        }
+        if let Some(end) = state.in_string {
+            match find_unescaped(rest, end) {
+                Some(at) => {
+                    state.in_string = None;
+                    result.code.push_str(end);
+                    rest = &rest[at + end.len()..];
+                }
+                None => break,
+            }
+            continue;
+        }
+
+        let c = rest.chars().next().unwrap();
+        if syntax.line_comments.iter().any(|prefix| rest.starts_with(prefix)) {
+            result.has_comment = true;
+            break;
+        }
+        if let Some((open, close)) = syntax.block_comment {
+            if rest.starts_with(open) {
+                result.has_comment = true;
+                state.in_comment = Some(close);
+                rest = &rest[open.len()..];
+                continue;
+            }
+        }
+        if syntax.triple_quotes {
+            if let Some(quotes) = ["\"\"\"", "'''"].into_iter().find(|q| rest.starts_with(q)) {
+                rest = &rest[quotes.len()..];
+                // A string standing alone is a docstring
+                if result.code.trim().is_empty() {
+                    result.has_comment = true;
+                    state.in_comment = Some(quotes);
+                } else {
+                    result.code.push_str(quotes);
+                    state.in_string = Some(quotes);
+                }
+                continue;
+            }
+        }
+
+        if syntax.raw_strings && c == 'r' && !result.code.ends_with(|c: char| c.is_alphanumeric() || c == '_') {
+            let hashes = rest[1..].len() - rest[1..].trim_start_matches('#').len();
+            if rest[1 + hashes..].starts_with('"') {
+                let closing = format!("\"{}", "#".repeat(hashes));
+                result.code.push_str("\"\"");
+                match rest[2 + hashes..].find(&closing) {
+                    Some(at) => rest = &rest[2 + hashes + at + closing.len()..],
+                    None => break,
+                }
+                continue;
+            }
+        }
+
+        let is_string = match c {
+            '"' => true,
+            '`' => true,
+            '\'' => syntax.single_quoted_strings || is_char_literal(rest),
+            _ => false,
+        };
+        if is_string {
+            let quote = &rest[..1];
+            result.code.push(c);
+            match find_unescaped(&rest[1..], quote) {
+                Some(at) => {
+                    result.code.push(c);
+                    rest = &rest[1 + at + 1..];
+                }
+                None if c == '`' => {
+                    state.in_string = Some("`");
+                    break;
+                }
+                None => break,
+            }
+            continue;
+        }
+
+        result.code.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    result
+}
+
+// `'a'` or `'\n'`, as opposed to a Rust lifetime like `'a`
+fn is_char_literal(text: &str) -> bool {
+    let mut chars = text.chars().skip(1);
+    match chars.next() {
+        Some('\\') => true,
+        Some(_) => chars.next() == Some('\''),
+        None => false,
+    }
+}
+
+fn find_unescaped(text: &str, delimiter: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (at, c) in text.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if text[at..].starts_with(delimiter) {
+            return Some(at);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::CodeParser;
+
+    #[test]
+    fn test_metrics_per_language() -> anyhow::Result<()> {
+        let parser = CodeParser::new()?;
+        let metrics = |path: &str, source: &str, name: &str| {
+            let (entities, _) = parser.parse_source(path, source).unwrap();
+            let entity = entities.into_iter().find(|e| e.name == name).unwrap();
+            let m = entity.metrics.unwrap();
+            (m.lines_of_code, m.comment_lines, m.cyclomatic_complexity, m.max_nesting, m.param_count)
+        };
+
+        let rust = r##"impl Store {
+    /// Look a key up
+    pub fn get(&self, key: &str, fallback: Option<u32>) -> u32 {
+        // "if" in a comment doesn't count
+        let label = "while || for";
+        let pattern = r#"{ if "x" || y"#;
+        if key.is_empty() && fallback.is_some() {
+            for c in key.chars() {
+                if c == 'x' { return 1; }
+            }
+        }
+        match fallback {
+            Some(v) => v,
+            None => 0,
+        }
+    }
+}
+"##;
+        assert_eq!(metrics("src/store.rs", rust, "get"), (13, 1, 6, 3, 2));
+
+        let python = r#"def load(path, retries=3):
+    """Load a file.
+
+    Retries on failure.
+    """
+    for attempt in range(retries):
+        try:
+            if path and attempt > 0:
+                return open(path)
+        except OSError:
+            pass
+"#;
+        assert_eq!(metrics("loader.py", python, "load"), (7, 4, 5, 3, 2));
+
+        let javascript = "function pick(a, b) {\n  // prefer a\n  return a ? a : b;\n}\n";
+        assert_eq!(metrics("pick.js", javascript, "pick"), (3, 1, 2, 0, 2));
+
+        // Lifetimes are not quotes: all three parameters are counted
+        let lifetimes = "fn longest<'a>(a: &'a str, b: &'a str, strict: bool) -> &'a str {\n    if strict && a.len() > b.len() { a } else { b }\n}\n";
+        assert_eq!(metrics("src/text.rs", lifetimes, "longest"), (3, 0, 3, 1, 3));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,544 @@
+use crate::config::build_globset;
+use crate::{CodeEntity, Relationship, EntityType, Project, ProjectConfig};
+use globset::GlobSet;
+use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Arc;
+
+mod builtin;
+mod calls;
+mod cpp;
+mod csharp;
+mod declarative;
+mod docs;
+mod go;
+mod java;
+mod javascript;
+mod kotlin;
+mod measure;
+mod parsed;
+mod php;
+mod python;
+mod qualified;
+mod ruby;
+mod rust;
+mod scope;
+mod signature;
+mod swift;
+
+pub(crate) use csharp::partial_type_key;
+pub use declarative::{DeclarativeParser, LanguageDefinition, PatternDefinition, LANGUAGES_DIR};
+
+/// Language names the parser can analyze
+pub const SUPPORTED_LANGUAGES: &[&str] = &["javascript", "typescript", "python", "rust", "go", "java", "kotlin", "c", "cpp", "csharp", "ruby", "php", "swift"];
+
+/// Build an entity spanning a single source line
+pub(crate) fn line_entity(name: &str, entity_type: EntityType, file_path: &str, line_num: usize, line: &str) -> CodeEntity {
+    CodeEntity::new(
+        name.to_string(),
+        entity_type,
+        file_path.to_string(),
+        line_num as u32 + 1,
+        line_num as u32 + 1,
+        0,
+        line.len() as u32,
+    )
+}
+
+/// Split `(a, (b)) rest` into the text inside the leading brackets and what follows
+/// them; `None` when the text does not start with `open` or it is not closed on this line
+pub(crate) fn split_balanced(text: &str, open: char, close: char) -> Option<(&str, &str)> {
+    let text = text.trim_start();
+    if !text.starts_with(open) {
+        return None;
+    }
+
+    let mut depth = 0;
+    for (index, c) in text.char_indices() {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            depth -= 1;
+            if depth == 0 {
+                return Some((&text[open.len_utf8()..index], &text[index + close.len_utf8()..]));
+            }
+        }
+    }
+    None
+}
+
+/// Split a comma-separated list, ignoring commas nested in `<>`, `()` or `[]`
+pub(crate) fn split_top_level(list: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    for (index, c) in list.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(list[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(list[start..].trim());
+    items.retain(|item| !item.is_empty());
+    items
+}
+
+/// `Map<K, V>` -> `Map`
+pub(crate) fn strip_generics(name: &str) -> &str {
+    name.split('<').next().unwrap_or(name).trim()
+}
+
+/// A parser for one language. Implement it to teach [`CodeParser`] a
+/// language it doesn't ship with, then add it with [`CodeParser::register`].
+pub trait LanguageParser: Send + Sync {
+    /// Name used in `[languages]` config mappings and analysis output
+    fn language(&self) -> &str;
+
+    /// File extensions, without the dot, parsed as this language
+    fn extensions(&self) -> Vec<String>;
+
+    /// Globs matched against project-relative paths, for files an extension
+    /// can't identify (e.g. `**/BUILD`); they take precedence over extensions
+    fn file_globs(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Recognize a file whose extension maps to no language, e.g. by its shebang
+    fn detect(&self, _content: &str) -> bool {
+        false
+    }
+
+    fn parse(&self, content: &str, file_path: &str) -> Result<(Vec<CodeEntity>, Vec<Relationship>)>;
+}
+
+pub struct CodeParser {
+    // Language name -> parser
+    parsers: HashMap<String, Arc<dyn LanguageParser>>,
+    // Registration order, so content detection is deterministic
+    languages: Vec<String>,
+
+    // File extension -> language name
+    extension_languages: HashMap<String, String>,
+    // Path globs -> language name, latest registration first
+    glob_languages: Vec<(GlobSet, String)>,
+}
+
+impl CodeParser {
+    pub fn new() -> Result<Self> {
+        Self::with_builtins(builtin::builtin_parsers(&[])?)
+    }
+
+    /// Create a parser with the extra extension mappings from a project config
+    pub fn with_config(config: &ProjectConfig) -> Result<Self> {
+        let mut parser = Self::with_builtins(builtin::builtin_parsers(&config.cpp.include_dirs)?)?;
+        for (extension, language) in &config.languages {
+            parser.map_extension(extension, language)?;
+        }
+        Ok(parser)
+    }
+
+    /// Create a parser for a project: the built-in languages, the ones defined
+    /// in `.aimemoryengine/languages/`, and the config's extension mappings
+    pub fn for_project(project: &Project) -> Result<Self> {
+        let mut parser = Self::with_builtins(builtin::builtin_parsers(&project.config.cpp.include_dirs)?)?;
+        for language in DeclarativeParser::load_dir(&project.memory_dir().join(LANGUAGES_DIR))? {
+            parser.register(language)?;
+        }
+        for (extension, language) in &project.config.languages {
+            parser.map_extension(extension, language)?;
+        }
+        Ok(parser)
+    }
+
+    fn with_builtins(builtins: Vec<Arc<dyn LanguageParser>>) -> Result<Self> {
+        let mut parser = Self {
+            parsers: HashMap::new(),
+            languages: Vec::new(),
+            extension_languages: HashMap::new(),
+            glob_languages: Vec::new(),
+        };
+        for builtin in builtins {
+            parser.register_shared(builtin)?;
+        }
+        Ok(parser)
+    }
+
+    /// Add a language, or replace the parser of one with the same name. Its
+    /// extensions take precedence over earlier mappings.
+    pub fn register<P: LanguageParser + 'static>(&mut self, parser: P) -> Result<()> {
+        self.register_shared(Arc::new(parser))
+    }
+
+    fn register_shared(&mut self, parser: Arc<dyn LanguageParser>) -> Result<()> {
+        let language = parser.language().to_lowercase();
+        if language.is_empty() || language.contains(char::is_whitespace) {
+            return Err(anyhow!("Invalid language name '{}': must be a single non-empty word", parser.language()));
+        }
+
+        for extension in parser.extensions() {
+            let extension = extension.trim_start_matches('.').to_lowercase();
+            self.extension_languages.insert(extension, language.clone());
+        }
+        let globs = parser.file_globs();
+        self.glob_languages.retain(|(_, existing)| existing != &language);
+        if !globs.is_empty() {
+            let globs = build_globset(&globs).map_err(|e| anyhow!("Language '{}': {}", language, e))?;
+            self.glob_languages.insert(0, (globs, language.clone()));
+        }
+        if !self.languages.contains(&language) {
+            self.languages.push(language.clone());
+        }
+        self.parsers.insert(language, parser);
+        Ok(())
+    }
+
+    /// Names of the registered languages, built-ins first
+    pub fn languages(&self) -> Vec<&str> {
+        self.languages.iter().map(|language| language.as_str()).collect()
+    }
+
+    /// Parse files with the given extension as `language`
+    pub fn map_extension(&mut self, extension: &str, language: &str) -> Result<()> {
+        let language = language.to_lowercase();
+        if !self.parsers.contains_key(&language) {
+            return Err(anyhow!(
+                "Unknown language '{}' for extension '{}' (supported: {})",
+                language,
+                extension,
+                self.languages.join(", ")
+            ));
+        }
+
+        let extension = extension.trim_start_matches('.').to_lowercase();
+        self.extension_languages.insert(extension, language);
+        Ok(())
+    }
+
+    /// Get the language a file would be parsed as, if any
+    pub fn language_for_path(&self, file_path: &str) -> Option<&str> {
+        if let Some((_, language)) = self.glob_languages.iter().find(|(globs, _)| globs.is_match(file_path)) {
+            return Some(language);
+        }
+
+        let extension = std::path::Path::new(file_path)
+            .extension()
+            .and_then(|ext| ext.to_str())?
+            .to_lowercase();
+
+        self.extension_languages.get(&extension).map(|lang| lang.as_str())
+    }
+
+    /// Get the language of a file by extension, falling back to asking each
+    /// parser to recognize the content
+    pub fn detect_language(&self, file_path: &str, content: &str) -> Option<&str> {
+        self.language_for_path(file_path).or_else(|| {
+            self.languages
+                .iter()
+                .find(|language| self.parsers[language.as_str()].detect(content))
+                .map(|language| language.as_str())
+        })
+    }
+
+    pub fn parse_file(&self, file_path: &str) -> Result<(Vec<CodeEntity>, Vec<Relationship>)> {
+        let content = fs::read_to_string(file_path)?;
+        self.parse_source(file_path, &content)
+    }
+
+    /// Parse already-loaded source text as if it came from `file_path`
+    pub fn parse_source(&self, file_path: &str, content: &str) -> Result<(Vec<CodeEntity>, Vec<Relationship>)> {
+        match self.detect_language(file_path, content) {
+            Some(language) => {
+                let (mut entities, mut relationships) = self.parsers[language].parse(content, file_path)?;
+                qualified::qualify_names(language, file_path, &mut entities, &relationships);
+                measure::record_metrics(language, &mut entities, content);
+                relationships.extend(calls::record_calls(language, &mut entities, content));
+                Ok((entities, relationships))
+            }
+            None => Ok((Vec::new(), Vec::new())),
+        }
+    }
+}
+
+/// Lookups shared by the language parsers' tests
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::LanguageParser;
+    use crate::{CodeEntity, EntityType, ProjectMemory, RelationType, Relationship};
+    use anyhow::Result;
+
+    /// One file's parse result, with entities looked up by name
+    pub(crate) struct ParsedSource {
+        pub entities: Vec<CodeEntity>,
+        pub relationships: Vec<Relationship>,
+    }
+
+    impl ParsedSource {
+        pub fn new(parser: &dyn LanguageParser, file_path: &str, content: &str) -> Result<Self> {
+            let (entities, relationships) = parser.parse(content, file_path)?;
+            Ok(Self { entities, relationships })
+        }
+
+        /// The first entity named `name`
+        pub fn find(&self, name: &str) -> &CodeEntity {
+            self.entities.iter().find(|e| e.name == name).unwrap_or_else(|| panic!("missing {}", name))
+        }
+
+        /// The entity named `name` of one type, e.g. a constructor rather than its class
+        pub fn find_typed(&self, name: &str, entity_type: EntityType) -> &CodeEntity {
+            self.entities
+                .iter()
+                .find(|e| e.name == name && e.entity_type == entity_type)
+                .unwrap_or_else(|| panic!("missing {} {}", entity_type.as_str(), name))
+        }
+
+        pub fn has(&self, name: &str) -> bool {
+            self.entities.iter().any(|e| e.name == name)
+        }
+
+        pub fn meta(&self, name: &str, key: &str) -> Option<&str> {
+            self.find(name).metadata.get(key).map(String::as_str)
+        }
+
+        /// `(type, target)` of every edge leaving `name` except `Contains`, sorted
+        pub fn edges(&self, name: &str) -> Vec<(&str, &str)> {
+            let from = &self.find(name).id;
+            let mut edges: Vec<_> = self
+                .relationships
+                .iter()
+                .filter(|r| &r.from_entity == from && r.relationship_type != RelationType::Contains)
+                .map(|r| (r.relationship_type.as_str(), r.to_entity.as_str()))
+                .collect();
+            edges.sort();
+            edges
+        }
+
+        /// Names of the entities directly inside `name`, sorted
+        pub fn members(&self, name: &str) -> Vec<&str> {
+            let parent = &self.find(name).id;
+            let mut members: Vec<&str> = self
+                .relationships
+                .iter()
+                .filter(|r| r.relationship_type == RelationType::Contains && &r.from_entity == parent)
+                .filter_map(|r| self.entities.iter().find(|e| e.id == r.to_entity))
+                .map(|e| e.name.as_str())
+                .collect();
+            members.sort();
+            members
+        }
+    }
+
+    /// Parse several files into one memory and resolve symbols across them
+    pub(crate) fn resolve_files(parser: &dyn LanguageParser, files: &[(&str, &str)]) -> Result<ProjectMemory> {
+        let mut memory = ProjectMemory::new("/project".to_string());
+        for (path, content) in files {
+            let (entities, relationships) = parser.parse(content, path)?;
+            entities.into_iter().for_each(|e| memory.add_entity(e));
+            relationships.into_iter().for_each(|r| memory.add_relationship(r));
+        }
+        memory.resolve_references();
+        Ok(memory)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_parse_simple_javascript() -> Result<()> {
+        let parser = CodeParser::new()?;
+
+        let mut temp_file = NamedTempFile::new()?;
+        writeln!(temp_file, "function testFunction() {{")?;
+        writeln!(temp_file, "  return 'hello';")?;
+        writeln!(temp_file, "}}")?;
+        writeln!(temp_file, "class TestClass {{}}")?;
+        writeln!(temp_file, "import React from 'react';")?;
+        writeln!(temp_file, "const myVar = 42;")?;
+
+        let temp_path = temp_file.path().with_extension("js");
+        fs::copy(temp_file.path(), &temp_path)?;
+
+        let (entities, _relationships) = parser.parse_file(temp_path.to_str().unwrap())?;
+
+        assert!(!entities.is_empty());
+
+        let functions: Vec<_> = entities.iter().filter(|e| e.entity_type == EntityType::Function).collect();
+        let classes: Vec<_> = entities.iter().filter(|e| e.entity_type == EntityType::Class).collect();
+        let imports: Vec<_> = entities.iter().filter(|e| e.entity_type == EntityType::Import).collect();
+        let variables: Vec<_> = entities.iter().filter(|e| e.entity_type == EntityType::Variable).collect();
+
+        assert_eq!(functions.len(), 1);
+        assert_eq!(functions[0].name, "testFunction");
+
+        assert_eq!(classes.len(), 1);
+        assert_eq!(classes[0].name, "TestClass");
+
+        assert_eq!(imports.len(), 1);
+        assert_eq!(imports[0].name, "react");
+
+        assert_eq!(variables.len(), 1);
+        assert_eq!(variables[0].name, "myVar");
+
+        fs::remove_file(temp_path)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rust_parsing() -> Result<()> {
+        let parser = CodeParser::new()?;
+
+        let rust_content = r#"
+use std::collections::HashMap;
+use anyhow::Result;
+
+pub struct MemoryEngine {
+    entities: HashMap<String, Entity>,
+}
+
+pub trait Analyzer {
+    fn analyze(&self) -> Result<()>;
+}
+
+pub enum EntityType {
+    Function,
+    Struct,
+    Trait,
+}
+
+impl MemoryEngine {
+    pub fn new() -> Self {
+        Self {
+            entities: HashMap::new(),
+        }
+    }
+
+    pub fn add_entity(&mut self, entity: Entity) {
+        self.entities.insert(entity.id.clone(), entity);
+    }
+}
+
+pub fn create_parser() -> Result<CodeParser> {
+    CodeParser::new()
+}
+
+pub const MAX_ENTITIES: usize = 1000;
+
+pub mod storage {
+    pub fn save_data() {}
+}
+"#;
+
+        let temp_path = "test_rust_parsing.rs";
+        fs::write(temp_path, rust_content)?;
+
+        let (entities, _relationships) = parser.parse_file(temp_path)?;
+
+        // Verify we found the expected entities
+        let use_statements: Vec<_> = entities.iter().filter(|e| e.entity_type == EntityType::Import).collect();
+        let structs: Vec<_> = entities.iter().filter(|e| e.entity_type == EntityType::Class && e.name == "MemoryEngine").collect();
+        let traits: Vec<_> = entities.iter().filter(|e| e.entity_type == EntityType::Interface && e.name == "Analyzer").collect();
+        let enums: Vec<_> = entities.iter().filter(|e| e.entity_type == EntityType::Class && e.name == "EntityType").collect();
+        let impls: Vec<_> = entities.iter().filter(|e| e.entity_type == EntityType::Module && e.name == "impl MemoryEngine").collect();
+        let functions: Vec<_> = entities.iter().filter(|e| e.entity_type == EntityType::Function).collect();
+        let constants: Vec<_> = entities.iter().filter(|e| e.entity_type == EntityType::Constant && e.name == "MAX_ENTITIES").collect();
+        let modules: Vec<_> = entities.iter().filter(|e| e.entity_type == EntityType::Module && !e.name.starts_with("impl ")).collect();
+
+        assert!(use_statements.len() >= 2, "Should find use statements");
+        assert_eq!(structs.len(), 1, "Should find MemoryEngine struct");
+        assert_eq!(traits.len(), 1, "Should find Analyzer trait");
+        assert_eq!(enums.len(), 1, "Should find EntityType enum");
+        assert_eq!(impls.len(), 1, "Should find MemoryEngine impl block");
+        assert!(functions.len() >= 3, "Should find functions (new, add_entity, create_parser)");
+        assert_eq!(constants.len(), 1, "Should find MAX_ENTITIES constant");
+        assert_eq!(modules.len(), 1, "Should find storage module");
+
+        fs::remove_file(temp_path)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_extension_mapping() -> Result<()> {
+        let mut config = ProjectConfig::default();
+        config.languages.insert(".mjs".to_string(), "javascript".to_string());
+        config.languages.insert("pyi".to_string(), "Python".to_string());
+        let parser = CodeParser::with_config(&config)?;
+
+        assert_eq!(parser.language_for_path("lib/util.mjs"), Some("javascript"));
+        assert_eq!(parser.language_for_path("stubs/os.pyi"), Some("python"));
+        assert_eq!(parser.language_for_path("main.RS"), Some("rust"));
+        assert_eq!(parser.language_for_path("README.md"), None);
+
+        let (entities, _) = parser.parse_source("stubs/os.pyi", "def getcwd() -> str: ...\n")?;
+        assert_eq!(entities.len(), 1);
+        assert_eq!(entities[0].name, "getcwd");
+
+        config.languages.insert("foo".to_string(), "cobol".to_string());
+        assert!(CodeParser::with_config(&config).is_err());
+
+        Ok(())
+    }
+
+    struct IniParser;
+
+    impl LanguageParser for IniParser {
+        fn language(&self) -> &str {
+            "ini"
+        }
+
+        fn extensions(&self) -> Vec<String> {
+            vec!["ini".to_string(), ".cfg".to_string()]
+        }
+
+        fn detect(&self, content: &str) -> bool {
+            content.starts_with("; ini")
+        }
+
+        fn parse(&self, content: &str, file_path: &str) -> Result<(Vec<CodeEntity>, Vec<Relationship>)> {
+            let sections = content
+                .lines()
+                .enumerate()
+                .filter_map(|(line_num, line)| {
+                    let name = line.strip_prefix('[')?.strip_suffix(']')?;
+                    Some(line_entity(name, EntityType::Module, file_path, line_num, line))
+                })
+                .collect();
+            Ok((sections, Vec::new()))
+        }
+    }
+
+    #[test]
+    fn test_register_custom_language() -> Result<()> {
+        let mut parser = CodeParser::new()?;
+        assert!(parser.map_extension("conf", "ini").is_err());
+
+        parser.register(IniParser)?;
+        parser.map_extension("conf", "ini")?;
+        assert_eq!(parser.languages().last(), Some(&"ini"));
+        assert_eq!(parser.language_for_path("setup.cfg"), Some("ini"));
+        assert_eq!(parser.language_for_path("app.conf"), Some("ini"));
+
+        let (entities, _) = parser.parse_source("config/app.ini", "[server]\nport = 80\n[database]\n")?;
+        let names: Vec<_> = entities.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["server", "database"]);
+
+        // Files without a known extension are recognized by their content
+        assert_eq!(parser.detect_language("bin/settings", "; ini\n[paths]\n"), Some("ini"));
+        assert_eq!(parser.detect_language("bin/deploy", "#!/usr/bin/env python3\nimport os\n"), Some("python"));
+        assert_eq!(parser.detect_language("bin/notes", "just text"), None);
+        assert_eq!(parser.parse_source("bin/settings", "; ini\n[paths]\n")?.0.len(), 1);
+
+        Ok(())
+    }
+}
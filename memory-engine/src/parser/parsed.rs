@@ -0,0 +1,54 @@
+use crate::{CodeEntity, RelationType, Relationship};
+
+/// Entities and relationships collected while parsing one file. Entities are
+/// addressed by their index so parsers can link them before ids matter.
+#[derive(Debug, Default)]
+pub(crate) struct ParsedFile {
+    pub entities: Vec<CodeEntity>,
+    pub relationships: Vec<Relationship>,
+}
+
+impl ParsedFile {
+    pub fn push(&mut self, entity: CodeEntity) -> usize {
+        self.entities.push(entity);
+        self.entities.len() - 1
+    }
+
+    pub fn set_metadata(&mut self, index: usize, key: &str, value: String) {
+        self.entities[index].metadata.insert(key.to_string(), value);
+    }
+
+    pub fn set_line_end(&mut self, index: usize, line_num: usize) {
+        self.entities[index].line_end = line_num as u32 + 1;
+    }
+
+    /// Relationship between two entities of this file
+    pub fn link(&mut self, from: usize, to: usize, relationship_type: RelationType) {
+        self.relationships.push(Relationship::new(
+            self.entities[from].id.clone(),
+            self.entities[to].id.clone(),
+            relationship_type,
+        ));
+    }
+
+    /// Relationship to a symbol that is resolved across files later
+    pub fn link_symbol(&mut self, from: usize, symbol: &str, relationship_type: RelationType) -> &mut Relationship {
+        self.relationships.push(Relationship::to_symbol(
+            self.entities[from].id.clone(),
+            symbol,
+            relationship_type,
+        ));
+        self.relationships.last_mut().unwrap()
+    }
+
+    /// Record `child` as a member of `parent`
+    pub fn contain(&mut self, parent: usize, child: usize) {
+        let parent_name = self.entities[parent].name.clone();
+        self.set_metadata(child, "enclosing", parent_name);
+        self.link(parent, child, RelationType::Contains);
+    }
+
+    pub fn finish(self) -> (Vec<CodeEntity>, Vec<Relationship>) {
+        (self.entities, self.relationships)
+    }
+}
@@ -0,0 +1,384 @@
+use super::parsed::ParsedFile;
+use super::signature::{record_signatures, SignatureStyle};
+use super::scope::{ScopeKind, ScopeTracker};
+use super::{line_entity, split_top_level};
+use crate::{CodeEntity, EntityType, RelationType, Relationship};
+use anyhow::Result;
+use regex::Regex;
+
+pub(crate) struct PhpParser {
+    namespace_regex: Regex,
+    use_regex: Regex,
+    require_regex: Regex,
+    type_regex: Regex,
+    heritage_regex: Regex,
+    function_regex: Regex,
+    trait_use_regex: Regex,
+    property_regex: Regex,
+    const_regex: Regex,
+    case_regex: Regex,
+}
+
+impl PhpParser {
+    pub(crate) fn new() -> Result<Self> {
+        Ok(Self {
+            namespace_regex: Regex::new(r"^\s*namespace\s+([\w\\]+)\s*(\{)?")?,
+            use_regex: Regex::new(r"^\s*use\s+(?:(function|const)\s+)?([^;{]+(?:\{[^}]*\})?)\s*;")?,
+            require_regex: Regex::new(r#"^\s*(require|require_once|include|include_once)\s*\(?\s*(?:__DIR__\s*\.\s*)?['"]([^'"]+)['"]"#)?,
+            type_regex: Regex::new(r"^\s*((?:(?:abstract|final|readonly)\s+)*)(class|interface|trait|enum)\s+(\w+)")?,
+            heritage_regex: Regex::new(r"\b(extends|implements)\b")?,
+            function_regex: Regex::new(r"^\s*((?:(?:public|private|protected|static|abstract|final)\s+)*)function\s+&?(\w+)\s*\(")?,
+            trait_use_regex: Regex::new(r"^\s*use\s+([\w\\]+(?:\s*,\s*[\w\\]+)*)\s*[;{]")?,
+            property_regex: Regex::new(r"^\s*((?:(?:public|private|protected|static|readonly|var)\s+)+)(?:\??[\w\\|]+\s+)?\$(\w+)")?,
+            const_regex: Regex::new(r"^\s*(?:(?:public|private|protected|final)\s+)*const\s+(?:\w+\s+)?(\w+)\s*=")?,
+            case_regex: Regex::new(r"^\s*case\s+(\w+)\s*[=;]")?,
+        })
+    }
+
+    pub(crate) fn parse(&self, content: &str, file_path: &str) -> Result<(Vec<CodeEntity>, Vec<Relationship>)> {
+        let mut parsed = ParsedFile::default();
+        let mut tracker = ScopeTracker::new().with_single_quoted_strings().with_heredocs();
+        // `namespace App\Http;` applies until the next namespace statement
+        let mut namespace: Option<(usize, String)> = None;
+
+        for (line_num, line) in content.lines().enumerate() {
+            let trimmed = line.trim_start();
+            let enclosing = tracker
+                .current_body()
+                .and_then(|scope| Some((scope.entity?, scope.name.clone(), scope.kind)));
+            let is_comment = tracker.in_block_comment()
+                || tracker.in_heredoc()
+                || trimmed.starts_with("//")
+                || trimmed.starts_with('#')
+                || trimmed.starts_with("/*")
+                || trimmed.starts_with('*');
+            let at_file_level = tracker.depth() == 0 || matches!(enclosing, Some((_, _, ScopeKind::Namespace)));
+
+            if !is_comment && at_file_level {
+                let namespace_index = namespace.as_ref().map(|(index, _)| *index);
+
+                if let Some(captures) = self.namespace_regex.captures(line) {
+                    let name = &captures[1];
+                    if let Some((index, _)) = namespace.take() {
+                        parsed.set_line_end(index, line_num.saturating_sub(1));
+                    }
+                    let index = parsed.push(
+                        line_entity(name, EntityType::Module, file_path, line_num, line)
+                            .with_metadata("kind".to_string(), "namespace".to_string()),
+                    );
+                    if captures.get(2).is_some() {
+                        tracker.expect_body(name, index, ScopeKind::Namespace);
+                    }
+                    namespace = Some((index, name.to_string()));
+                } else if let Some(captures) = self.use_regex.captures(line) {
+                    for (imported, alias) in expand_use(&captures[2]) {
+                        let mut entity = line_entity(&imported, EntityType::Import, file_path, line_num, line);
+                        if let Some(alias) = alias {
+                            entity = entity.with_metadata("alias".to_string(), alias);
+                        }
+                        if let Some(kind) = captures.get(1) {
+                            entity = entity.with_metadata("kind".to_string(), kind.as_str().to_string());
+                        }
+                        let index = parsed.push(entity);
+                        if let Some(namespace) = namespace_index {
+                            parsed.link(namespace, index, RelationType::Imports);
+                        }
+                    }
+                } else if let Some(captures) = self.require_regex.captures(line) {
+                    let index = parsed.push(
+                        line_entity(&captures[2], EntityType::Import, file_path, line_num, line)
+                            .with_metadata("kind".to_string(), captures[1].to_string()),
+                    );
+                    if let Some(namespace) = namespace_index {
+                        parsed.link(namespace, index, RelationType::Imports);
+                    }
+                } else if let Some(captures) = self.type_regex.captures(line) {
+                    self.parse_type(&mut parsed, &mut tracker, &captures, line, namespace.as_ref(), file_path, line_num);
+                } else if let Some(captures) = self.function_regex.captures(line) {
+                    let index = parsed.push(
+                        line_entity(&captures[2], EntityType::Function, file_path, line_num, line)
+                            .with_metadata("kind".to_string(), "function".to_string()),
+                    );
+                    if let Some(namespace) = namespace_index {
+                        parsed.contain(namespace, index);
+                    }
+                    tracker.expect_body(&captures[2], index, ScopeKind::Function);
+                }
+            } else if let (false, Some((parent, _, ScopeKind::Type))) = (is_comment, &enclosing) {
+                self.parse_member(&mut parsed, &mut tracker, *parent, file_path, line_num, line);
+            }
+
+            for closed in tracker.process_line(line) {
+                parsed.set_line_end(closed, line_num);
+            }
+        }
+
+        if let Some((index, _)) = namespace {
+            if parsed.entities[index].line_end == parsed.entities[index].line_start {
+                parsed.set_line_end(index, content.lines().count().saturating_sub(1));
+            }
+        }
+
+        record_signatures(&mut parsed.entities, content, SignatureStyle::Php);
+        Ok(parsed.finish())
+    }
+
+    /// Classes, interfaces, traits and enums
+    #[allow(clippy::too_many_arguments)]
+    fn parse_type(
+        &self,
+        parsed: &mut ParsedFile,
+        tracker: &mut ScopeTracker,
+        captures: &regex::Captures,
+        line: &str,
+        namespace: Option<&(usize, String)>,
+        file_path: &str,
+        line_num: usize,
+    ) {
+        let keyword = &captures[2];
+        let name = &captures[3];
+        let entity_type = if keyword == "interface" { EntityType::Interface } else { EntityType::Class };
+
+        let mut entity = line_entity(name, entity_type.clone(), file_path, line_num, line)
+            .with_metadata("kind".to_string(), keyword.to_string());
+        for modifier in ["abstract", "final", "readonly"] {
+            if captures[1].split_whitespace().any(|m| m == modifier) {
+                entity = entity.with_metadata(modifier.to_string(), "true".to_string());
+            }
+        }
+        if let Some((_, namespace)) = namespace {
+            entity = entity.with_metadata("namespace".to_string(), namespace.clone());
+        }
+        let index = parsed.push(entity);
+        if let Some((namespace, _)) = namespace {
+            parsed.contain(*namespace, index);
+        }
+        tracker.expect_body(name, index, ScopeKind::Type);
+
+        let rest = &line[captures.get(0).map(|m| m.end()).unwrap_or(0)..];
+        let heritage = rest.split('{').next().unwrap_or(rest);
+        let keywords: Vec<_> = self.heritage_regex.find_iter(heritage).collect();
+        for (position, found) in keywords.iter().enumerate() {
+            let end = keywords.get(position + 1).map(|next| next.start()).unwrap_or(heritage.len());
+            // An interface may extend several interfaces
+            let relationship_type = match found.as_str() {
+                "extends" => RelationType::Extends,
+                _ => RelationType::Implements,
+            };
+            for parent in split_top_level(&heritage[found.end()..end]) {
+                parsed.link_symbol(index, parent.trim_start_matches('\\'), relationship_type.clone());
+            }
+        }
+    }
+
+    /// Trait uses, methods, properties, constants and enum cases in a type body
+    fn parse_member(&self, parsed: &mut ParsedFile, tracker: &mut ScopeTracker, parent: usize, file_path: &str, line_num: usize, line: &str) {
+        if let Some(captures) = self.trait_use_regex.captures(line) {
+            for used in captures[1].split(',') {
+                parsed
+                    .link_symbol(parent, used.trim().trim_start_matches('\\'), RelationType::Uses)
+                    .metadata
+                    .insert("mixin".to_string(), "use".to_string());
+            }
+            return;
+        }
+
+        let (name, entity_type, kind, modifiers) = if let Some(captures) = self.function_regex.captures(line) {
+            let kind = if &captures[2] == "__construct" { "constructor" } else { "method" };
+            (captures[2].to_string(), EntityType::Function, kind, captures[1].to_string())
+        } else if let Some(captures) = self.const_regex.captures(line) {
+            (captures[1].to_string(), EntityType::Constant, "constant", String::new())
+        } else if let Some(captures) = self.case_regex.captures(line) {
+            (captures[1].to_string(), EntityType::Constant, "case", String::new())
+        } else if let Some(captures) = self.property_regex.captures(line) {
+            (captures[2].to_string(), EntityType::Variable, "property", captures[1].to_string())
+        } else {
+            return;
+        };
+
+        let mut entity = line_entity(&name, entity_type.clone(), file_path, line_num, line)
+            .with_metadata("kind".to_string(), kind.to_string());
+        for modifier in ["static", "abstract"] {
+            if modifiers.split_whitespace().any(|m| m == modifier) {
+                entity = entity.with_metadata(modifier.to_string(), "true".to_string());
+            }
+        }
+        let index = parsed.push(entity);
+        parsed.contain(parent, index);
+        if entity_type == EntityType::Function {
+            tracker.expect_body(&name, index, ScopeKind::Function);
+        }
+    }
+}
+
+/// `App\{Models\User, Post as P}` -> `App\Models\User`, `App\Post` (aliased `P`)
+fn expand_use(clause: &str) -> Vec<(String, Option<String>)> {
+    let (prefix, items) = match clause.split_once('{') {
+        Some((prefix, items)) => (prefix.trim(), items.trim_end().trim_end_matches('}')),
+        None => ("", clause),
+    };
+
+    items
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            let (name, alias) = match item.split_once(" as ") {
+                Some((name, alias)) => (name.trim(), Some(alias.trim().to_string())),
+                None => (item, None),
+            };
+            (format!("{}{}", prefix, name).trim_start_matches('\\').to_string(), alias)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_support::{resolve_files, ParsedSource};
+    use crate::RelationshipQuery;
+
+    const CONTROLLER: &str = r#"<?php
+
+namespace App\Http\Controllers;
+
+use App\Models\{Order, Invoice as Bill};
+use function App\Support\money;
+require_once __DIR__ . '/../helpers.php';
+
+#[Route('/orders')]
+final class OrderController extends Controller implements HasMiddleware, \JsonSerializable
+{
+    use AuthorizesRequests, Loggable;
+
+    public const PER_PAGE = 20;
+    private static ?Cache $cache = null;
+
+    public function __construct(private OrderService $orders)
+    {
+    }
+
+    public static function middleware(): array
+    {
+        return ['auth' => fn () => "{$this->orders}"];
+    }
+
+    abstract protected function render(Order $order): string;
+}
+
+enum Status: string
+{
+    case Open = 'open';
+    case Closed = 'closed';
+}
+"#;
+
+    const LOGGABLE: &str = r#"<?php
+namespace App\Support;
+
+trait Loggable
+{
+    public function log(string $message): void
+    {
+    }
+}
+"#;
+
+    #[test]
+    fn test_php_entities() -> Result<()> {
+        let file = ParsedSource::new(&PhpParser::new()?, "app/Http/Controllers/OrderController.php", CONTROLLER)?;
+
+        assert_eq!(file.meta("App\\Http\\Controllers", "kind"), Some("namespace"));
+        assert_eq!(file.find("App\\Models\\Order").entity_type, EntityType::Import);
+        assert_eq!(file.meta("App\\Models\\Invoice", "alias"), Some("Bill"));
+        assert_eq!(file.meta("App\\Support\\money", "kind"), Some("function"));
+        assert_eq!(file.meta("/../helpers.php", "kind"), Some("require_once"));
+
+        assert_eq!(file.meta("OrderController", "final"), Some("true"));
+        assert_eq!(file.meta("OrderController", "namespace"), Some("App\\Http\\Controllers"));
+        assert_eq!((file.find("OrderController").line_start, file.find("OrderController").line_end), (10, 27));
+        assert_eq!(file.find("PER_PAGE").entity_type, EntityType::Constant);
+        assert_eq!(file.meta("cache", "static"), Some("true"));
+        assert_eq!(file.meta("__construct", "kind"), Some("constructor"));
+        assert_eq!((file.find("middleware").line_start, file.find("middleware").line_end), (21, 24));
+        assert_eq!(file.meta("render", "abstract"), Some("true"));
+        assert_eq!(file.meta("Open", "kind"), Some("case"));
+        assert_eq!(file.meta("Closed", "enclosing"), Some("Status"));
+
+        assert_eq!(
+            file.edges("OrderController"),
+            vec![
+                ("extends", "Controller"),
+                ("implements", "HasMiddleware"),
+                ("implements", "JsonSerializable"),
+                ("uses", "AuthorizesRequests"),
+                ("uses", "Loggable"),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_php_heredocs_and_anonymous_classes() -> Result<()> {
+        let source = r#"<?php
+namespace App;
+
+interface Exporter extends Countable, Stringable
+{
+    public function export(): ?string;
+}
+
+class Report implements Exporter
+{
+    private string $open = '{ not a block';
+
+    public function render(): string
+    {
+        $html = <<<HTML
+            <div>{$this->title}</div> }
+            class Fake {
+            HTML;
+        $raw = <<<'EOT'
+        }
+        EOT;
+        $handler = new class {
+            public function handle() {}
+        };
+        return $html;
+    }
+
+    public function count(): int { return 0; }
+}
+"#;
+        let file = ParsedSource::new(&PhpParser::new()?, "src/Report.php", source)?;
+        assert_eq!(file.edges("Exporter"), vec![("extends", "Countable"), ("extends", "Stringable")]);
+        assert_eq!(file.members("Exporter"), vec!["export"]);
+        // Heredoc, nowdoc and single-quoted braces leave the class open
+        assert!(!file.has("Fake"));
+        assert_eq!((file.find("render").line_start, file.find("render").line_end), (13, 26));
+        assert_eq!(file.find("Report").line_end, 29);
+        assert!(!file.has("handle"), "anonymous class members are not type members");
+        assert_eq!(file.members("Report"), vec!["count", "open", "render"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_trait_uses_resolve_across_files() -> Result<()> {
+        let memory = resolve_files(
+            &PhpParser::new()?,
+            &[("app/Http/Controllers/OrderController.php", CONTROLLER), ("app/Support/Loggable.php", LOGGABLE)],
+        )?;
+
+        let loggable = memory.find_entities_by_name("Loggable").into_iter().find(|e| e.entity_type == EntityType::Class).unwrap();
+        assert_eq!(loggable.metadata.get("kind").map(String::as_str), Some("trait"));
+        let uses = memory.find_relationships(&RelationshipQuery::new().to_entity(loggable.id.clone()));
+        assert_eq!(uses.len(), 2);
+        let user = uses.iter().find(|r| r.relationship_type == RelationType::Uses).unwrap();
+        assert_eq!(memory.entities[&user.from_entity].name, "OrderController");
+        assert_eq!(memory.relationships.iter().filter(|r| r.is_resolved() && r.target_name().is_some()).count(), 1);
+
+        Ok(())
+    }
+}
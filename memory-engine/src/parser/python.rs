@@ -0,0 +1,424 @@
+use super::docs::parse_docstring;
+use super::parsed::ParsedFile;
+use super::signature::{record_signatures, SignatureStyle};
+use super::{line_entity, split_balanced, split_top_level};
+use crate::{CodeEntity, EntityType, RelationType, Relationship};
+use anyhow::Result;
+use regex::Regex;
+
+/// A `def` or `class` whose indented body is open
+#[derive(Debug)]
+struct PythonBlock {
+    indent: usize,
+    entity: usize,
+    is_class: bool,
+}
+
+/// Lexical state carried between physical lines so a statement spanning
+/// several lines (brackets, triple-quoted strings, `\`) is read as one
+#[derive(Debug, Default)]
+struct LineState {
+    depth: usize,
+    string: Option<&'static str>,
+    continued: bool,
+}
+
+impl LineState {
+    fn in_statement(&self) -> bool {
+        self.depth > 0 || self.string.is_some() || self.continued
+    }
+
+    fn scan(&mut self, line: &str) {
+        let chars: Vec<char> = line.chars().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            if let Some(delimiter) = self.string {
+                if chars[i] == '\\' {
+                    i += 1;
+                } else if starts_with(&chars, i, delimiter) {
+                    self.string = None;
+                    i += delimiter.len() - 1;
+                }
+                i += 1;
+                continue;
+            }
+            match chars[i] {
+                '#' => break,
+                '"' | '\'' if starts_with(&chars, i, triple(chars[i])) => {
+                    self.string = Some(triple(chars[i]));
+                    i += 2;
+                }
+                quote @ ('"' | '\'') => {
+                    i += 1;
+                    while i < chars.len() && chars[i] != quote {
+                        i += if chars[i] == '\\' { 2 } else { 1 };
+                    }
+                }
+                '(' | '[' | '{' => self.depth += 1,
+                ')' | ']' | '}' => self.depth = self.depth.saturating_sub(1),
+                _ => {}
+            }
+            i += 1;
+        }
+        self.continued = self.string.is_none() && line.trim_end().ends_with('\\');
+    }
+}
+
+fn triple(quote: char) -> &'static str {
+    if quote == '"' { "\"\"\"" } else { "'''" }
+}
+
+fn starts_with(chars: &[char], start: usize, delimiter: &str) -> bool {
+    delimiter.chars().enumerate().all(|(offset, c)| chars.get(start + offset) == Some(&c))
+}
+
+pub(crate) struct PythonParser {
+    def_regex: Regex,
+    class_regex: Regex,
+    decorator_regex: Regex,
+    import_regex: Regex,
+    from_import_regex: Regex,
+    all_regex: Regex,
+    string_regex: Regex,
+}
+
+impl PythonParser {
+    pub(crate) fn new() -> Result<Self> {
+        Ok(Self {
+            def_regex: Regex::new(r"^(async\s+)?def\s+(\w+)")?,
+            class_regex: Regex::new(r"^class\s+(\w+)")?,
+            decorator_regex: Regex::new(r"^@\s*([\w.]+)")?,
+            import_regex: Regex::new(r"^import\s+(.+)")?,
+            from_import_regex: Regex::new(r"^from\s+(\S+)\s+import\s+(.+)")?,
+            all_regex: Regex::new(r"(?s)^__all__\s*(?::[^=]*)?\+?=\s*(.*)")?,
+            string_regex: Regex::new(r#"['"](\w+)['"]"#)?,
+        })
+    }
+
+    pub(crate) fn parse(&self, content: &str, file_path: &str) -> Result<(Vec<CodeEntity>, Vec<Relationship>)> {
+        let mut parsed = ParsedFile::default();
+        let mut blocks: Vec<PythonBlock> = Vec::new();
+        let mut state = LineState::default();
+        let mut decorators: Vec<String> = Vec::new();
+        let mut exports: Vec<usize> = Vec::new();
+        // A block just opened, whose first statement may be its docstring
+        let mut awaiting_docstring: Option<usize> = None;
+
+        // The logical line being read: start line, indentation and joined text
+        let mut statement: Option<(usize, usize, String)> = None;
+        let mut last_code_line = 0;
+        let lines: Vec<&str> = content.lines().collect();
+
+        for (line_num, line) in lines.iter().enumerate() {
+            let trimmed = line.trim();
+            if !state.in_statement() {
+                if trimmed.is_empty() || trimmed.starts_with('#') {
+                    continue;
+                }
+                // A dedent closes every body indented at least as deep
+                let indent = line.len() - line.trim_start().len();
+                while blocks.last().map(|block| block.indent >= indent).unwrap_or(false) {
+                    let block = blocks.pop().unwrap();
+                    parsed.set_line_end(block.entity, last_code_line);
+                }
+                statement = Some((line_num, indent, trimmed.to_string()));
+            } else if let Some((_, _, text)) = &mut statement {
+                text.push('\n');
+                text.push_str(trimmed);
+            }
+
+            state.scan(line);
+            last_code_line = line_num;
+            if state.in_statement() {
+                continue;
+            }
+
+            let Some((start, indent, text)) = statement.take() else { continue };
+            let parent = blocks.last().map(|block| (block.entity, block.is_class));
+            let first_line = lines[start];
+
+            if let Some(index) = awaiting_docstring.take().filter(|&index| parent.map(|(entity, _)| entity) == Some(index)) {
+                // Re-read the raw lines: the joined text has lost the docstring's indentation
+                if let Some(docstring) = string_literal(&lines[start..=line_num].join("\n")) {
+                    parse_docstring(docstring).apply(&mut parsed, index);
+                    continue;
+                }
+            }
+
+            if let Some(captures) = self.decorator_regex.captures(&text) {
+                decorators.push(captures[1].to_string());
+                continue;
+            }
+
+            let declared = if let Some(captures) = self.def_regex.captures(&text) {
+                let kind = match parent {
+                    Some((_, true)) if &captures[2] == "__init__" => "constructor",
+                    Some((_, true)) => "method",
+                    Some((_, false)) => "nested",
+                    None => "function",
+                };
+                let index = parsed.push(
+                    line_entity(&captures[2], EntityType::Function, file_path, start, first_line)
+                        .with_metadata("kind".to_string(), kind.to_string()),
+                );
+                if captures.get(1).is_some() {
+                    parsed.set_metadata(index, "async", "true".to_string());
+                }
+                if decorators.iter().any(|d| d == "staticmethod" || d == "classmethod") {
+                    parsed.set_metadata(index, "static", "true".to_string());
+                }
+                if decorators.iter().any(|d| d == "property" || d.ends_with(".setter") || d.ends_with(".getter")) {
+                    parsed.set_metadata(index, "kind", "property".to_string());
+                }
+                Some((index, false))
+            } else if let Some(captures) = self.class_regex.captures(&text) {
+                let index = parsed.push(
+                    line_entity(&captures[1], EntityType::Class, file_path, start, first_line)
+                        .with_metadata("kind".to_string(), "class".to_string()),
+                );
+                self.link_bases(&mut parsed, index, &text[captures.get(0).map(|m| m.end()).unwrap_or(0)..]);
+                Some((index, true))
+            } else {
+                if parent.is_none() {
+                    self.parse_module_statement(&mut parsed, &mut exports, file_path, start, line_num, first_line, &text);
+                } else if let Some(index) = self.parse_import(&mut parsed, file_path, start, first_line, &text) {
+                    // Imports inside functions are still dependencies of the module
+                    parsed.set_line_end(index, line_num);
+                }
+                None
+            };
+
+            if let Some((index, is_class)) = declared {
+                let name = &parsed.entities[index].name;
+                if name.starts_with('_') && !name.ends_with("__") {
+                    parsed.set_metadata(index, "visibility", "private".to_string());
+                }
+                if !decorators.is_empty() {
+                    parsed.set_metadata(index, "decorators", decorators.join(","));
+                }
+                if let Some((parent, _)) = parent {
+                    parsed.contain(parent, index);
+                }
+                parsed.set_line_end(index, line_num);
+                // `def f(): return 1` has its whole body on the header line
+                if text.trim_end().ends_with(':') {
+                    blocks.push(PythonBlock { indent, entity: index, is_class });
+                    awaiting_docstring = Some(index);
+                }
+            }
+            decorators.clear();
+        }
+
+        for block in blocks {
+            parsed.set_line_end(block.entity, last_code_line);
+        }
+
+        // `__all__` names the module's public API
+        for export in exports {
+            let name = parsed.entities[export].name.clone();
+            let target = parsed
+                .entities
+                .iter()
+                .position(|e| e.name == name && e.entity_type != EntityType::Export && !e.metadata.contains_key("enclosing"));
+            if let Some(target) = target {
+                parsed.link(export, target, RelationType::References);
+            }
+        }
+
+        record_signatures(&mut parsed.entities, content, SignatureStyle::Python);
+        Ok(parsed.finish())
+    }
+
+    /// Imports and `__all__` at module level
+    #[allow(clippy::too_many_arguments)]
+    fn parse_module_statement(
+        &self,
+        parsed: &mut ParsedFile,
+        exports: &mut Vec<usize>,
+        file_path: &str,
+        start: usize,
+        end: usize,
+        first_line: &str,
+        text: &str,
+    ) {
+        if let Some(captures) = self.all_regex.captures(text) {
+            for name in self.string_regex.captures_iter(&captures[1]) {
+                let index = parsed.push(
+                    line_entity(&name[1], EntityType::Export, file_path, start, first_line)
+                        .with_metadata("kind".to_string(), "all".to_string()),
+                );
+                parsed.set_line_end(index, end);
+                exports.push(index);
+            }
+        } else if let Some(index) = self.parse_import(parsed, file_path, start, first_line, text) {
+            parsed.set_line_end(index, end);
+        }
+    }
+
+    /// One Import per imported name; returns the last one pushed
+    fn parse_import(&self, parsed: &mut ParsedFile, file_path: &str, start: usize, first_line: &str, text: &str) -> Option<usize> {
+        // Comments end at their own line, so drop them before joining
+        let text = text.lines().map(|line| line.split('#').next().unwrap_or_default()).collect::<Vec<_>>().join(" ").replace('\\', " ");
+        let mut last = None;
+
+        if let Some(captures) = self.from_import_regex.captures(&text) {
+            let module = &captures[1];
+            let names = &captures[2];
+            let names = split_balanced(names, '(', ')').map(|(inner, _)| inner).unwrap_or(names);
+            for name in names.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+                let (name, alias) = split_alias(name);
+                let separator = if module.ends_with('.') { "" } else { "." };
+                let mut entity = line_entity(&format!("{}{}{}", module, separator, name), EntityType::Import, file_path, start, first_line)
+                    .with_metadata("module".to_string(), module.to_string());
+                if let Some(alias) = alias {
+                    entity = entity.with_metadata("alias".to_string(), alias.to_string());
+                }
+                last = Some(parsed.push(entity));
+            }
+        } else if let Some(captures) = self.import_regex.captures(&text) {
+            for name in captures[1].split(',').map(str::trim).filter(|name| !name.is_empty()) {
+                let (name, alias) = split_alias(name);
+                let mut entity = line_entity(name, EntityType::Import, file_path, start, first_line);
+                if let Some(alias) = alias {
+                    entity = entity.with_metadata("alias".to_string(), alias.to_string());
+                }
+                last = Some(parsed.push(entity));
+            }
+        }
+        last
+    }
+
+    // `class Admin(User, metaclass=ABCMeta)` extends `User`
+    fn link_bases(&self, parsed: &mut ParsedFile, index: usize, rest: &str) {
+        let rest = rest.trim_start();
+        let rest = if rest.starts_with('[') { split_balanced(rest, '[', ']').map(|(_, after)| after).unwrap_or(rest) } else { rest };
+        let Some((bases, _)) = split_balanced(rest.trim_start(), '(', ')') else { return };
+        for base in split_top_level(bases) {
+            let base = base.split('[').next().unwrap_or_default().trim();
+            if base.is_empty() || base.contains('=') || base == "object" || base.starts_with('*') {
+                continue;
+            }
+            parsed.link_symbol(index, base, RelationType::Extends);
+        }
+    }
+}
+
+/// The contents of a statement that is only a string literal, e.g. a docstring
+fn string_literal(text: &str) -> Option<&str> {
+    let text = text.trim();
+    let text = text.trim_start_matches(['r', 'R', 'u', 'U']);
+    ["\"\"\"", "'''", "\"", "'"].iter().find_map(|quote| {
+        text.strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+            .filter(|inner| quote.len() == 3 || !inner.contains(quote))
+    })
+}
+
+fn split_alias(name: &str) -> (&str, Option<&str>) {
+    match name.split_once(" as ") {
+        Some((name, alias)) => (name.trim(), Some(alias.trim())),
+        None => (name, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_python_classes_methods_and_decorators() -> Result<()> {
+        let source = r#"import os, sys as system
+from typing import (  # noqa
+    Any,
+    Optional as Opt,
+)
+from . import utils
+
+__all__ = [
+    "UserService",
+    "load",
+]
+
+class UserService(BaseService, metaclass=ABCMeta):
+    """Service docs.
+
+    def not_a_function():
+    """
+
+    class Meta:
+        ordering = ["name"]
+
+    def __init__(self, db):
+        self.db = db
+
+    @staticmethod
+    @cache(maxsize=10)
+    def build(
+        config,
+    ):
+        return UserService(config)
+
+    @property
+    def size(self):
+        def helper():
+            return 1
+        return helper()
+
+    async def _fetch(self, user_id):
+        """Fetch a user.
+
+        Args:
+            user_id: Id of the user.
+        """
+        import json
+        return json.loads(await self.db.get(user_id))
+
+
+async def load(path): return path
+"#;
+        let (entities, relationships) = PythonParser::new()?.parse(source, "app/services.py")?;
+        let find = |name: &str| {
+            entities
+                .iter()
+                .find(|e| e.name == name && e.entity_type != EntityType::Export)
+                .unwrap_or_else(|| panic!("missing {}", name))
+        };
+        let meta = |name: &str, key: &str| find(name).metadata.get(key).cloned();
+        let imports: Vec<_> = entities.iter().filter(|e| e.entity_type == EntityType::Import).map(|e| e.name.as_str()).collect();
+
+        assert_eq!(imports, vec!["os", "sys", "typing.Any", "typing.Optional", ".utils", "json"]);
+        assert_eq!(meta("sys", "alias").as_deref(), Some("system"));
+        assert_eq!(meta("typing.Optional", "alias").as_deref(), Some("Opt"));
+
+        assert_eq!((find("UserService").line_start, find("UserService").line_end), (13, 45));
+        assert!(!entities.iter().any(|e| e.name == "not_a_function"));
+        assert_eq!(meta("Meta", "enclosing").as_deref(), Some("UserService"));
+        assert!(!entities.iter().any(|e| e.name == "ordering"));
+        assert_eq!(meta("__init__", "kind").as_deref(), Some("constructor"));
+        assert_eq!(meta("build", "decorators").as_deref(), Some("staticmethod,cache"));
+        assert_eq!(meta("build", "static").as_deref(), Some("true"));
+        assert_eq!((find("build").line_start, find("build").line_end), (27, 30));
+        assert_eq!(meta("size", "kind").as_deref(), Some("property"));
+        assert_eq!(meta("helper", "kind").as_deref(), Some("nested"));
+        assert_eq!(meta("helper", "enclosing").as_deref(), Some("size"));
+        assert_eq!(meta("_fetch", "async").as_deref(), Some("true"));
+        assert_eq!(meta("_fetch", "visibility").as_deref(), Some("private"));
+        assert_eq!(meta("load", "kind").as_deref(), Some("function"));
+        assert_eq!(find("load").line_end, 48);
+        assert!(meta("UserService", "doc").unwrap().starts_with("Service docs."));
+        assert_eq!(meta("_fetch", "doc").as_deref(), Some("Fetch a user."));
+        assert_eq!(meta("_fetch", "doc_params").as_deref(), Some("user_id: Id of the user."));
+        assert_eq!(meta("Meta", "doc"), None);
+
+        let exports: Vec<_> = entities.iter().filter(|e| e.entity_type == EntityType::Export).collect();
+        assert_eq!(exports.len(), 2);
+        assert!(relationships.iter().any(|r| r.from_entity == exports[0].id
+            && r.to_entity == find("UserService").id
+            && r.relationship_type == RelationType::References));
+        assert!(relationships.iter().any(|r| r.from_entity == find("UserService").id
+            && r.to_entity == "BaseService"
+            && r.relationship_type == RelationType::Extends));
+
+        Ok(())
+    }
+}
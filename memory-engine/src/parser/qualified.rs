@@ -0,0 +1,209 @@
+// Qualified names: each entity's path through the file's modules, types and
+// functions, prefixed the way its language names a file or package, e.g.
+// `crate::storage::MemoryStorage::new`, `pkg.module.Class.method` or
+// `src/utils.ts#formatDate`.
+
+use crate::{CodeEntity, EntityType, RelationType, Relationship};
+use std::collections::HashMap;
+
+/// How a language names the unit a file belongs to
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NameScheme {
+    /// `crate::dir::file::Type::item`
+    Crate,
+    /// `package.module.Class.method`
+    PythonModule,
+    /// The declared package or namespace: `com.example.Repo.find`
+    Package,
+    /// `dir/file.ext#Class.method`
+    File,
+}
+
+impl NameScheme {
+    fn for_language(language: &str) -> Self {
+        match language {
+            "rust" => NameScheme::Crate,
+            "python" => NameScheme::PythonModule,
+            "java" | "kotlin" | "go" | "csharp" => NameScheme::Package,
+            _ => NameScheme::File,
+        }
+    }
+
+    fn separator(&self) -> &'static str {
+        match self {
+            NameScheme::Crate => "::",
+            _ => ".",
+        }
+    }
+}
+
+/// Set `qualified_name` on every entity parsed from `file_path`
+pub(crate) fn qualify_names(language: &str, file_path: &str, entities: &mut [CodeEntity], relationships: &[Relationship]) {
+    let scheme = NameScheme::for_language(language);
+    let separator = scheme.separator();
+
+    let index_of: HashMap<&str, usize> = entities.iter().enumerate().map(|(index, e)| (e.id.as_str(), index)).collect();
+    let mut parents: HashMap<usize, usize> = HashMap::new();
+    for relationship in relationships.iter().filter(|r| r.relationship_type == RelationType::Contains) {
+        if let (Some(&parent), Some(&child)) = (index_of.get(relationship.from_entity.as_str()), index_of.get(relationship.to_entity.as_str())) {
+            parents.entry(child).or_insert(parent);
+        }
+    }
+
+    // A package declaration names everything else in the file
+    let package = match scheme {
+        NameScheme::Package => entities
+            .iter()
+            .find(|e| e.entity_type == EntityType::Module && !e.metadata.contains_key("kind"))
+            .map(|e| e.name.clone()),
+        _ => None,
+    };
+    let prefix = match scheme {
+        NameScheme::Crate => Some(rust_module_path(file_path)),
+        NameScheme::PythonModule => Some(python_module_path(file_path)),
+        NameScheme::Package => package.clone(),
+        NameScheme::File => None,
+    };
+
+    let qualified: Vec<String> = (0..entities.len())
+        .map(|index| {
+            let entity = &entities[index];
+            // Imports and exports already name what they refer to
+            if matches!(entity.entity_type, EntityType::Import | EntityType::Export) || package.as_deref() == Some(entity.name.as_str()) {
+                return entity.name.clone();
+            }
+
+            let mut segments = vec![entity.name.clone()];
+            // C++ out-of-line definitions (`double Shape::area()`) sit in a namespace
+            // or file, not in the class they are qualified with
+            let owner = entity.metadata.get("enclosing");
+            if let (Some(owner), Some(&parent)) = (owner, parents.get(&index)) {
+                let parent = &entities[parent];
+                if *owner != parent.name && scope_segment(parent).as_ref() != Some(owner) {
+                    segments.push(owner.clone());
+                }
+            }
+            let mut current = index;
+            let mut depth = 0;
+            while let Some(&parent) = parents.get(&current) {
+                // The package is already the prefix
+                if package.as_deref() != Some(entities[parent].name.as_str()) {
+                    if let Some(segment) = scope_segment(&entities[parent]) {
+                        segments.push(segment);
+                    }
+                }
+                current = parent;
+                depth += 1;
+                if depth > entities.len() {
+                    break;
+                }
+            }
+            // Go methods and other members linked only by name
+            if current == index {
+                if let Some(owner) = entity.metadata.get("enclosing").or_else(|| entity.metadata.get("receiver")) {
+                    segments.push(owner.clone());
+                }
+            }
+            segments.reverse();
+            let path = segments.join(separator);
+
+            match (&prefix, scheme) {
+                (Some(prefix), _) if !prefix.is_empty() => format!("{}{}{}", prefix, separator, path),
+                (_, NameScheme::File) => format!("{}#{}", file_path, path),
+                _ => path,
+            }
+        })
+        .collect();
+
+    for (entity, qualified_name) in entities.iter_mut().zip(qualified) {
+        entity.qualified_name = qualified_name;
+    }
+}
+
+/// The name a parent contributes to its members' paths, if any
+fn scope_segment(parent: &CodeEntity) -> Option<String> {
+    match parent.metadata.get("kind").map(String::as_str) {
+        // Members of `impl Foo` belong to `Foo`
+        Some("impl") => parent.metadata.get("self_type").cloned(),
+        // The unit a C/C++ file forms is already named by the path
+        Some("header" | "source") if parent.entity_type == EntityType::Module => None,
+        _ => Some(parent.name.clone()),
+    }
+}
+
+/// `memory-engine/src/parser/mod.rs` -> `crate::parser`
+fn rust_module_path(file_path: &str) -> String {
+    let path = file_path.trim_end_matches(".rs");
+    let path = match path.rfind("src/") {
+        Some(at) if at == 0 || path[..at].ends_with('/') => &path[at + 4..],
+        _ => path,
+    };
+    let mut segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    if segments.last() == Some(&"mod") || (segments.len() == 1 && matches!(segments[0], "lib" | "main")) {
+        segments.pop();
+    }
+    std::iter::once("crate").chain(segments).collect::<Vec<_>>().join("::")
+}
+
+/// `src/app/services.py` -> `app.services`; a package's `__init__.py` is the package
+fn python_module_path(file_path: &str) -> String {
+    let path = file_path.trim_end_matches(".py");
+    let path = path.strip_prefix("src/").unwrap_or(path);
+    let mut segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    if segments.last() == Some(&"__init__") {
+        segments.pop();
+    }
+    segments.join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::CodeParser;
+
+    #[test]
+    fn test_qualified_names_per_language() -> anyhow::Result<()> {
+        let parser = CodeParser::new()?;
+        let qualified = |path: &str, source: &str| -> Vec<String> {
+            let (entities, _) = parser.parse_source(path, source).unwrap();
+            entities.into_iter().map(|e| e.qualified_name).collect()
+        };
+
+        let rust = "pub struct MemoryStorage;\nimpl MemoryStorage {\n    pub fn new() -> Self { Self }\n}\nmod tests {\n    fn check() {}\n}\n";
+        assert_eq!(
+            qualified("memory-engine/src/storage.rs", rust),
+            vec![
+                "crate::storage::MemoryStorage",
+                "crate::storage::impl MemoryStorage",
+                "crate::storage::MemoryStorage::new",
+                "crate::storage::tests",
+                "crate::storage::tests::check",
+            ]
+        );
+        assert_eq!(qualified("src/lib.rs", "fn root() {}\n"), vec!["crate::root"]);
+        assert_eq!(qualified("src/parser/mod.rs", "fn parse() {}\n"), vec!["crate::parser::parse"]);
+
+        let python = "import os\nclass Service:\n    def run(self):\n        pass\n";
+        assert_eq!(qualified("src/pkg/module.py", python), vec!["os", "pkg.module.Service", "pkg.module.Service.run"]);
+
+        let typescript = "export function formatDate(d: Date) {}\nclass Clock {\n    tick() {}\n}\n";
+        assert_eq!(qualified("src/utils.ts", typescript), vec!["src/utils.ts#formatDate", "formatDate", "src/utils.ts#Clock", "src/utils.ts#Clock.tick"]);
+
+        let java = "package com.example;\nclass Repo {\n    void find() {}\n}\n";
+        assert_eq!(qualified("Repo.java", java), vec!["com.example", "com.example.Repo", "com.example.Repo.find"]);
+
+        let go = "package storage\n\ntype Store struct {\n}\n\nfunc (s *Store) Read() {}\n";
+        assert_eq!(qualified("store.go", go), vec!["storage", "storage.Store", "storage.Store.Read"]);
+
+        // Out-of-line definitions keep the class they are qualified with
+        let header = "namespace geo {\nclass Shape {\npublic:\n  Shape();\n  double area();\n};\n}\n";
+        assert!(qualified("src/shape.h", header).contains(&"src/shape.h#geo.Shape.area".to_string()));
+        let source = "#include \"shape.h\"\nnamespace geo {\nShape::Shape() {}\ndouble Shape::area() {\n  return 0;\n}\n}\n";
+        let names = qualified("src/util.cpp", source);
+        assert!(names.contains(&"src/util.cpp#geo.Shape.Shape".to_string()));
+        assert!(names.contains(&"src/util.cpp#geo.Shape.area".to_string()));
+        let global = qualified("src/area.cpp", "double Shape::area() {\n  return 0;\n}\n");
+        assert!(global.contains(&"src/area.cpp#Shape.area".to_string()));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,407 @@
+use super::line_entity;
+use super::parsed::ParsedFile;
+use super::signature::{record_signatures, SignatureStyle};
+use crate::{CodeEntity, EntityType, RelationType, Relationship};
+use anyhow::Result;
+use regex::Regex;
+
+/// A `module`, `class`, `def` or control-flow block waiting for its `end`
+#[derive(Debug)]
+struct RubyBlock {
+    entity: Option<usize>, // `None` for `if`, `do` and other anonymous blocks
+    is_type: bool,
+    singleton: bool, // `class << self`
+}
+
+pub(crate) struct RubyParser {
+    module_regex: Regex,
+    class_regex: Regex,
+    singleton_regex: Regex,
+    def_regex: Regex,
+    endless_def_regex: Regex,
+    mixin_regex: Regex,
+    require_regex: Regex,
+    attr_regex: Regex,
+    constant_regex: Regex,
+    heredoc_regex: Regex,
+}
+
+impl RubyParser {
+    pub(crate) fn new() -> Result<Self> {
+        Ok(Self {
+            module_regex: Regex::new(r"^\s*module\s+((?:\w+::)*)(\w+)")?,
+            class_regex: Regex::new(r"^\s*class\s+((?:\w+::)*)(\w+)(?:\s*<\s*([\w:]+))?")?,
+            singleton_regex: Regex::new(r"^\s*class\s*<<\s*self\b")?,
+            def_regex: Regex::new(r"^\s*(?:(private|protected|public)\s+)?def\s+(self\.)?(\w+[?!=]?|[\[\]<>=+\-*/%!~]+)")?,
+            endless_def_regex: Regex::new(r"^\s*(?:\w+\s+)?def\s+(?:self\.)?\w+[?!]?\s*(?:\([^)]*\))?\s*=[^=~>]")?,
+            mixin_regex: Regex::new(r"^\s*(include|extend|prepend)\s+([\w:]+(?:\s*,\s*[\w:]+)*)")?,
+            require_regex: Regex::new(r#"^\s*(require|require_relative|load|autoload\s+:\w+,)\s*\(?\s*['"]([^'"]+)['"]"#)?,
+            attr_regex: Regex::new(r"^\s*attr_(reader|writer|accessor)\s+(.+)")?,
+            constant_regex: Regex::new(r"^\s*([A-Z][A-Z0-9_]*)\s*=[^=~]")?,
+            heredoc_regex: Regex::new(r#"<<[~-]?['"]?([A-Z_][A-Z0-9_]*)"#)?,
+        })
+    }
+
+    pub(crate) fn parse(&self, content: &str, file_path: &str) -> Result<(Vec<CodeEntity>, Vec<Relationship>)> {
+        let mut parsed = ParsedFile::default();
+        let mut blocks: Vec<RubyBlock> = Vec::new();
+        let mut heredoc: Option<String> = None;
+        let mut in_block_comment = false;
+
+        for (line_num, line) in content.lines().enumerate() {
+            let trimmed = line.trim();
+
+            if let Some(terminator) = &heredoc {
+                if trimmed == terminator {
+                    heredoc = None;
+                }
+                continue;
+            }
+            if in_block_comment {
+                in_block_comment = !line.starts_with("=end");
+                continue;
+            }
+            if line.starts_with("=begin") {
+                in_block_comment = true;
+                continue;
+            }
+
+            // Members belong to the innermost module or class, looking through
+            // `do` and control blocks but not into method bodies
+            let container = blocks.iter().rev().find(|block| block.entity.is_some());
+            let in_method = container.map(|block| !block.is_type).unwrap_or(false);
+            let parent = container.filter(|block| block.is_type).and_then(|block| block.entity);
+            let singleton = blocks.iter().rev().take_while(|block| block.entity.is_none()).any(|block| block.singleton);
+
+            // The entity whose block the first opener on this line starts
+            let mut declared: Option<(usize, bool)> = None;
+            let mut singleton_opened = false;
+            let mut endless_def = false;
+
+            if !in_method {
+                if self.singleton_regex.is_match(line) {
+                    singleton_opened = true;
+                } else if let Some(captures) = self.module_regex.captures(line) {
+                    let index = parsed.push(
+                        line_entity(&captures[2], EntityType::Module, file_path, line_num, line)
+                            .with_metadata("kind".to_string(), "module".to_string()),
+                    );
+                    self.set_namespace(&mut parsed, index, &blocks, &captures[1]);
+                    if let Some(parent) = parent {
+                        parsed.contain(parent, index);
+                    }
+                    declared = Some((index, true));
+                } else if let Some(captures) = self.class_regex.captures(line) {
+                    let index = parsed.push(
+                        line_entity(&captures[2], EntityType::Class, file_path, line_num, line)
+                            .with_metadata("kind".to_string(), "class".to_string()),
+                    );
+                    self.set_namespace(&mut parsed, index, &blocks, &captures[1]);
+                    if let Some(parent) = parent {
+                        parsed.contain(parent, index);
+                    }
+                    if let Some(superclass) = captures.get(3) {
+                        parsed.link_symbol(index, superclass.as_str(), RelationType::Extends);
+                    }
+                    declared = Some((index, true));
+                } else if let Some(captures) = self.def_regex.captures(line) {
+                    let index = parsed.push(
+                        line_entity(&captures[3], EntityType::Function, file_path, line_num, line)
+                            .with_metadata("kind".to_string(), if parent.is_some() { "method" } else { "function" }.to_string()),
+                    );
+                    if captures.get(2).is_some() || singleton {
+                        parsed.set_metadata(index, "static", "true".to_string());
+                    }
+                    if let Some(visibility) = captures.get(1) {
+                        parsed.set_metadata(index, "visibility", visibility.as_str().to_string());
+                    }
+                    if let Some(parent) = parent {
+                        parsed.contain(parent, index);
+                    }
+                    // `def name = expr` has no `end`
+                    endless_def = self.endless_def_regex.is_match(line);
+                    if !endless_def {
+                        declared = Some((index, false));
+                    }
+                } else if let Some(captures) = self.require_regex.captures(line) {
+                    parsed.push(
+                        line_entity(&captures[2], EntityType::Import, file_path, line_num, line)
+                            .with_metadata("kind".to_string(), captures[1].split_whitespace().next().unwrap_or("require").to_string()),
+                    );
+                } else if let Some(parent) = parent {
+                    self.parse_member(&mut parsed, parent, file_path, line_num, line);
+                }
+            }
+
+            if let Some(captures) = self.heredoc_regex.captures(line) {
+                // The string body starts on the next line
+                heredoc = Some(captures[1].to_string());
+            }
+            let code = strip_strings_and_comments(line);
+
+            let mut first_opener = true;
+            for token in block_tokens(&code).into_iter().skip(endless_def as usize) {
+                match token {
+                    BlockToken::Open => {
+                        let (entity, is_type) = if first_opener { declared.take().unzip() } else { (None, None) };
+                        blocks.push(RubyBlock {
+                            entity,
+                            is_type: is_type.unwrap_or(false),
+                            singleton: first_opener && singleton_opened,
+                        });
+                        first_opener = false;
+                    }
+                    BlockToken::End => {
+                        if let Some(index) = blocks.pop().and_then(|block| block.entity) {
+                            parsed.set_line_end(index, line_num);
+                        }
+                    }
+                }
+            }
+        }
+
+        record_signatures(&mut parsed.entities, content, SignatureStyle::Ruby);
+        Ok(parsed.finish())
+    }
+
+    /// Mixins, attribute accessors and constants in a module or class body
+    fn parse_member(&self, parsed: &mut ParsedFile, parent: usize, file_path: &str, line_num: usize, line: &str) {
+        if let Some(captures) = self.mixin_regex.captures(line) {
+            for module in captures[2].split(',') {
+                parsed
+                    .link_symbol(parent, module.trim(), RelationType::Uses)
+                    .metadata
+                    .insert("mixin".to_string(), captures[1].to_string());
+            }
+        } else if let Some(captures) = self.attr_regex.captures(line) {
+            for attribute in captures[2].split(',') {
+                let Some(name) = attribute.trim().strip_prefix(':') else { continue };
+                let index = parsed.push(
+                    line_entity(name, EntityType::Variable, file_path, line_num, line)
+                        .with_metadata("kind".to_string(), "attribute".to_string())
+                        .with_metadata("access".to_string(), captures[1].to_string()),
+                );
+                parsed.contain(parent, index);
+            }
+        } else if let Some(captures) = self.constant_regex.captures(line) {
+            let index = parsed.push(line_entity(&captures[1], EntityType::Constant, file_path, line_num, line));
+            parsed.contain(parent, index);
+        }
+    }
+
+    // `class Admin::User` nested in `module Shop` lives in `Shop::Admin`
+    fn set_namespace(&self, parsed: &mut ParsedFile, index: usize, blocks: &[RubyBlock], prefix: &str) {
+        let mut path: Vec<String> = blocks
+            .iter()
+            .filter(|block| block.is_type)
+            .filter_map(|block| block.entity)
+            .map(|entity| parsed.entities[entity].name.clone())
+            .collect();
+        path.extend(prefix.split("::").filter(|segment| !segment.is_empty()).map(str::to_string));
+        if !path.is_empty() {
+            parsed.set_metadata(index, "namespace", path.join("::"));
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum BlockToken {
+    Open,
+    End,
+}
+
+// Keywords that open a block only when they start a statement; `return if x` is a modifier
+const STATEMENT_OPENERS: &[&str] = &["if", "unless", "while", "until", "case", "begin", "for"];
+
+/// Block openers and `end`s on a line of code with strings and comments removed
+fn block_tokens(code: &str) -> Vec<BlockToken> {
+    let mut tokens = Vec::new();
+    // `while cond do` takes an optional `do` that opens nothing
+    let mut loop_header = false;
+    let bytes = code.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if !(bytes[i].is_ascii_alphabetic() || bytes[i] == b'_') {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || matches!(bytes[i], b'_' | b'?' | b'!')) {
+            i += 1;
+        }
+        let word = &code[start..i];
+        let before = code[..start].trim_end().chars().last();
+        // `obj.class`, `:end` and `if:` keys are not keywords
+        if matches!(before, Some('.') | Some(':')) && !code[..start].ends_with("::") || code[i..].starts_with(':') && !code[i..].starts_with("::") {
+            continue;
+        }
+        let starts_statement = matches!(before, None | Some(';') | Some('=') | Some('(') | Some(',') | Some('[') | Some('|') | Some('&'));
+
+        match word {
+            "end" => tokens.push(BlockToken::End),
+            "do" if !loop_header => tokens.push(BlockToken::Open),
+            // Never modifiers, but may follow `private` or `protected`
+            "def" | "class" | "module" => tokens.push(BlockToken::Open),
+            "while" | "until" | "for" if starts_statement => {
+                loop_header = true;
+                tokens.push(BlockToken::Open);
+            }
+            word if starts_statement && STATEMENT_OPENERS.contains(&word) => tokens.push(BlockToken::Open),
+            _ => {}
+        }
+    }
+
+    tokens
+}
+
+// Blank out string contents and drop a trailing `#` comment
+fn strip_strings_and_comments(line: &str) -> String {
+    let mut code = String::with_capacity(line.len());
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(_) if c == '\\' => {
+                chars.next();
+            }
+            Some(q) if c == q => {
+                quote = None;
+                code.push(c);
+            }
+            Some(_) => {}
+            None if c == '#' => break,
+            None if c == '"' || c == '\'' || c == '`' => {
+                quote = Some(c);
+                code.push(c);
+            }
+            None => code.push(c),
+        }
+    }
+
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_support::ParsedSource;
+
+    #[test]
+    fn test_ruby_entities() -> Result<()> {
+        let source = r#"require "json"
+require_relative "../concerns/auditable"
+
+module Shop
+  class Order < ApplicationRecord
+    include Auditable, Comparable
+    extend Searchable
+    MAX_ITEMS = 50
+    attr_reader :total, :status
+
+    def self.recent(limit = 10)
+      where(status: :open).limit(limit)
+    end
+
+    class << self
+      def build(attrs) = new(attrs)
+    end
+
+    def add(item)
+      return if item.nil?
+      items.each do |existing|
+        raise "duplicate #{item}" if existing == item
+      end
+      @total += item.price unless item.free?
+    end
+
+    private def recalculate; end
+  end
+end
+"#;
+        let file = ParsedSource::new(&RubyParser::new()?, "app/models/order.rb", source)?;
+
+        assert_eq!(file.meta("../concerns/auditable", "kind"), Some("require_relative"));
+        assert_eq!(file.find("Shop").entity_type, EntityType::Module);
+        assert_eq!((file.find("Shop").line_start, file.find("Shop").line_end), (4, 29));
+        assert_eq!((file.find("Order").line_start, file.find("Order").line_end), (5, 28));
+        assert_eq!(file.meta("Order", "namespace"), Some("Shop"));
+        assert_eq!(file.find("MAX_ITEMS").entity_type, EntityType::Constant);
+        assert_eq!(file.meta("status", "access"), Some("reader"));
+
+        assert_eq!(file.meta("recent", "static"), Some("true"));
+        assert_eq!(file.find("recent").line_end, 13);
+        assert_eq!(file.meta("build", "static"), Some("true"));
+        assert_eq!(file.meta("add", "enclosing"), Some("Order"));
+        assert_eq!((file.find("add").line_start, file.find("add").line_end), (19, 25));
+        assert_eq!(file.meta("add", "static"), None);
+        assert_eq!(file.meta("recalculate", "visibility"), Some("private"));
+        assert!(!file.has("existing"));
+
+        // Mixins are `uses` edges that remember how they were mixed in
+        let order = &file.find("Order").id;
+        let mut edges: Vec<_> = file
+            .relationships
+            .iter()
+            .filter(|r| &r.from_entity == order && r.relationship_type != RelationType::Contains)
+            .map(|r| (r.relationship_type.as_str(), r.to_entity.as_str(), r.metadata.get("mixin").map(String::as_str)))
+            .collect();
+        edges.sort();
+        assert_eq!(
+            edges,
+            vec![
+                ("extends", "ApplicationRecord", None),
+                ("uses", "Auditable", Some("include")),
+                ("uses", "Comparable", Some("include")),
+                ("uses", "Searchable", Some("extend")),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ruby_one_line_methods_heredocs_and_nested_names() -> Result<()> {
+        let source = r#"module Util
+  class Parser::Json < Base
+    def helper; end
+    def parse(input, strict: true, **opts)
+      text = <<~SQL
+        class Fake
+        end
+      SQL
+      input
+    end
+
+    def self.call(*) = new.call
+  end
+end
+"#;
+        let file = ParsedSource::new(&RubyParser::new()?, "lib/util.rb", source)?;
+        assert_eq!(file.meta("Json", "namespace"), Some("Util::Parser"));
+        assert_eq!(file.edges("Json"), vec![("extends", "Base")]);
+        assert_eq!((file.find("helper").line_start, file.find("helper").line_end), (3, 3));
+        // Heredoc bodies declare nothing and close nothing
+        assert!(!file.has("Fake"));
+        assert_eq!((file.find("parse").line_start, file.find("parse").line_end), (4, 10));
+        assert_eq!(file.meta("call", "static"), Some("true"));
+        assert_eq!(file.find("Json").line_end, 13);
+        assert_eq!(file.members("Json"), vec!["call", "helper", "parse"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_block_tokens_skip_modifiers_and_strings() {
+        let code = |line: &str| block_tokens(&strip_strings_and_comments(line));
+
+        assert_eq!(code("  x = 1 if ready? # end"), vec![]);
+        assert_eq!(code("  result = if ready? then 1 else 2 end"), vec![BlockToken::Open, BlockToken::End]);
+        assert_eq!(code("  while running do"), vec![BlockToken::Open]);
+        assert_eq!(code("  items.each do |item|"), vec![BlockToken::Open]);
+        assert_eq!(code("  puts \"class end def\", obj.class, :end"), vec![]);
+        assert_eq!(code("  validates :name, if: :active?"), vec![]);
+        assert_eq!(code("  def call; end"), vec![BlockToken::Open, BlockToken::End]);
+    }
+}
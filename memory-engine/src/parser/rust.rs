@@ -0,0 +1,681 @@
+use super::docs::{parse_rustdoc, PendingDoc};
+use super::parsed::ParsedFile;
+use super::signature::{record_signatures, SignatureStyle};
+use super::scope::{ScopeKind, ScopeTracker};
+use super::{line_entity, split_balanced, strip_generics};
+use crate::{CodeEntity, EntityType, RelationType, Relationship};
+use anyhow::Result;
+use regex::Regex;
+use std::collections::BTreeMap;
+
+/// `#[...]` attributes waiting for the item they annotate
+#[derive(Debug, Default)]
+struct Attributes {
+    names: Vec<String>,
+    cfg: Vec<String>,
+    derives: Vec<String>,
+    test: bool,
+}
+
+impl Attributes {
+    fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    fn apply(&self, parsed: &mut ParsedFile, index: usize) {
+        if self.is_empty() {
+            return;
+        }
+        parsed.set_metadata(index, "attributes", self.names.join(","));
+        if !self.cfg.is_empty() {
+            parsed.set_metadata(index, "cfg", self.cfg.join(" && "));
+        }
+        if !self.derives.is_empty() {
+            parsed.set_metadata(index, "derives", self.derives.join(","));
+        }
+        if self.test {
+            parsed.set_metadata(index, "test", "true".to_string());
+        }
+    }
+}
+
+/// A `use` declaration spanning several lines
+#[derive(Debug)]
+struct OpenUse {
+    start: usize,
+    first_line: String,
+    visibility: String,
+    text: String,
+}
+
+pub(crate) struct RustParser {
+    use_regex: Regex,
+    mod_regex: Regex,
+    type_regex: Regex,
+    impl_regex: Regex,
+    function_regex: Regex,
+    value_regex: Regex,
+    macro_regex: Regex,
+    extern_block_regex: Regex,
+    extern_crate_regex: Regex,
+    variant_regex: Regex,
+    receiver_regex: Regex,
+}
+
+impl RustParser {
+    pub(crate) fn new() -> Result<Self> {
+        Ok(Self {
+            use_regex: Regex::new(r"^\s*(pub(?:\([^)]*\))?\s+)?use\s+(.*)")?,
+            mod_regex: Regex::new(r"^\s*(pub(?:\([^)]*\))?\s+)?mod\s+(\w+)\s*(;)?")?,
+            type_regex: Regex::new(r"^\s*(pub(?:\([^)]*\))?\s+)?((?:(?:unsafe|auto)\s+)*)(struct|enum|union|trait|type)\s+(\w+)")?,
+            impl_regex: Regex::new(r"^\s*(?:unsafe\s+)?impl\b")?,
+            function_regex: Regex::new(
+                r#"^\s*(pub(?:\([^)]*\))?\s+)?((?:(?:default|const|async|unsafe|extern(?:\s+"[^"]*")?)\s+)*)fn\s+(\w+)"#,
+            )?,
+            value_regex: Regex::new(r"^\s*(pub(?:\([^)]*\))?\s+)?(const|static)\s+(mut\s+)?(\w+)\s*:")?,
+            macro_regex: Regex::new(r"^\s*macro_rules!\s*(\w+)")?,
+            extern_block_regex: Regex::new(r#"^\s*(?:unsafe\s+)?extern\s+(?:"[^"]*"\s*)?\{"#)?,
+            extern_crate_regex: Regex::new(r"^\s*(?:pub(?:\([^)]*\))?\s+)?extern\s+crate\s+(\w+)(?:\s+as\s+(\w+))?")?,
+            variant_regex: Regex::new(r"^\s*([A-Z]\w*)\s*(?:[,({=]|$)")?,
+            receiver_regex: Regex::new(r"^\s*(?:&\s*(?:'\w+\s+)?)?(?:mut\s+)?self\b")?,
+        })
+    }
+
+    pub(crate) fn parse(&self, content: &str, file_path: &str) -> Result<(Vec<CodeEntity>, Vec<Relationship>)> {
+        let mut parsed = ParsedFile::default();
+        let mut tracker = ScopeTracker::new().with_rust_strings();
+        let mut attributes = Attributes::default();
+        let mut open_use: Option<OpenUse> = None;
+        let mut docs = PendingDoc::default();
+        // `//!` lines document the inline module they appear in
+        let mut module_docs: BTreeMap<usize, PendingDoc> = BTreeMap::new();
+
+        for (line_num, line) in content.lines().enumerate() {
+            if let Some(open) = &mut open_use {
+                open.text.push(' ');
+                open.text.push_str(line.trim());
+                if open.text.contains(';') {
+                    self.push_use(&mut parsed, file_path, open.start, line_num, &open.visibility, &open.text, &open.first_line);
+                    open_use = None;
+                }
+                tracker.process_line(line);
+                continue;
+            }
+
+            let trimmed = line.trim_start();
+            let enclosing = tracker
+                .current_body()
+                .filter(|scope| scope.kind != ScopeKind::Function)
+                .and_then(|scope| Some((scope.entity?, scope.kind)));
+            let is_comment = tracker.in_block_comment() || tracker.in_string() || trimmed.starts_with("//") || trimmed.starts_with("/*") || trimmed.starts_with('*');
+            docs.feed(line, true);
+            if let (Some(text), Some((parent, ScopeKind::Namespace))) = (trimmed.strip_prefix("//!"), enclosing) {
+                module_docs.entry(parent).or_default().push(text.strip_prefix(' ').unwrap_or(text));
+            }
+
+            if !is_comment && !trimmed.is_empty() {
+                let item = strip_attributes(trimmed, &mut attributes);
+                if !item.trim().is_empty() {
+                    let index = match enclosing {
+                        None if tracker.at_top_level() => {
+                            self.parse_item(&mut parsed, &mut tracker, &mut open_use, None, file_path, line_num, line, item)
+                        }
+                        Some((parent, ScopeKind::Namespace)) => {
+                            self.parse_item(&mut parsed, &mut tracker, &mut open_use, Some(parent), file_path, line_num, line, item)
+                        }
+                        Some((parent, ScopeKind::Type)) => {
+                            self.parse_member(&mut parsed, &mut tracker, &mut open_use, parent, file_path, line_num, line, item)
+                        }
+                        _ => None,
+                    };
+                    match index {
+                        Some(index) => {
+                            attributes.apply(&mut parsed, index);
+                            parse_rustdoc(&docs.take()).apply(&mut parsed, index);
+                        }
+                        None => docs.clear(),
+                    }
+                    attributes = Attributes::default();
+                }
+            }
+
+            for closed in tracker.process_line(line) {
+                parsed.set_line_end(closed, line_num);
+            }
+        }
+
+        for (index, mut doc) in module_docs {
+            parse_rustdoc(&doc.take()).apply(&mut parsed, index);
+        }
+
+        record_signatures(&mut parsed.entities, content, SignatureStyle::Rust);
+        // Associated functions without a `self` receiver are called on the type;
+        // the full signature is needed as parameters often start on the next line
+        for entity in &mut parsed.entities {
+            let Some(params) = entity.signature.as_mut().and_then(|s| s.params.as_ref()) else { continue };
+            if entity.metadata.get("kind").map(String::as_str) == Some("method")
+                && !params.first().map(|p| self.receiver_regex.is_match(&p.name)).unwrap_or(false)
+            {
+                entity.metadata.insert("static".to_string(), "true".to_string());
+                if let Some(signature) = entity.signature.as_mut() {
+                    signature.modifiers.push("static".to_string());
+                }
+            }
+        }
+        Ok(parsed.finish())
+    }
+
+    /// Items at file level, in a `mod` block, or in an `impl`/`trait` body
+    #[allow(clippy::too_many_arguments)]
+    fn parse_item(
+        &self,
+        parsed: &mut ParsedFile,
+        tracker: &mut ScopeTracker,
+        open_use: &mut Option<OpenUse>,
+        parent: Option<usize>,
+        file_path: &str,
+        line_num: usize,
+        line: &str,
+        item: &str,
+    ) -> Option<usize> {
+        let parent_kind = parent.and_then(|parent| parsed.entities[parent].metadata.get("kind").cloned());
+        let in_impl = parent_kind.as_deref() == Some("impl") || parent_kind.as_deref() == Some("trait");
+
+        let (index, visibility) = if let Some(captures) = self.use_regex.captures(item) {
+            let visibility = captures.get(1).map(|m| m.as_str().trim().to_string()).unwrap_or_default();
+            if captures[2].contains(';') {
+                return self.push_use(parsed, file_path, line_num, line_num, &visibility, &captures[2], line);
+            }
+            *open_use = Some(OpenUse {
+                start: line_num,
+                first_line: line.to_string(),
+                visibility,
+                text: captures[2].to_string(),
+            });
+            return None;
+        } else if let Some(captures) = self.extern_crate_regex.captures(item) {
+            let mut entity = line_entity(&captures[1], EntityType::Import, file_path, line_num, line)
+                .with_metadata("kind".to_string(), "crate".to_string());
+            if let Some(alias) = captures.get(2) {
+                entity = entity.with_metadata("alias".to_string(), alias.as_str().to_string());
+            }
+            return Some(parsed.push(entity));
+        } else if self.extern_block_regex.is_match(item) {
+            // Foreign functions are declared at the level of the block
+            tracker.expect_anonymous_body(ScopeKind::Namespace);
+            return None;
+        } else if let Some(captures) = self.mod_regex.captures(item) {
+            let kind = if captures.get(3).is_some() { "declaration" } else { "inline" };
+            let index = parsed.push(
+                line_entity(&captures[2], EntityType::Module, file_path, line_num, line)
+                    .with_metadata("kind".to_string(), kind.to_string()),
+            );
+            if captures.get(3).is_none() {
+                tracker.expect_body(&captures[2], index, ScopeKind::Namespace);
+            }
+            (index, captures.get(1))
+        } else if let Some(captures) = self.macro_regex.captures(item) {
+            let index = parsed.push(
+                line_entity(&captures[1], EntityType::Function, file_path, line_num, line)
+                    .with_metadata("kind".to_string(), "macro".to_string()),
+            );
+            tracker.expect_body(&captures[1], index, ScopeKind::Function);
+            (index, None)
+        } else if let Some(captures) = self.function_regex.captures(item) {
+            let name = &captures[3];
+            let rest = &item[captures.get(0)?.end()..];
+            let kind = if in_impl { "method" } else { "function" };
+            let index = parsed.push(
+                line_entity(name, EntityType::Function, file_path, line_num, line).with_metadata("kind".to_string(), kind.to_string()),
+            );
+            for modifier in captures[2].split_whitespace() {
+                match modifier {
+                    "const" | "async" | "unsafe" => parsed.set_metadata(index, modifier, "true".to_string()),
+                    "extern" => parsed.set_metadata(index, "abi", "C".to_string()),
+                    abi if abi.starts_with('"') => parsed.set_metadata(index, "abi", abi.trim_matches('"').to_string()),
+                    _ => {}
+                }
+            }
+            self.record_generics(parsed, index, rest);
+            tracker.expect_body(name, index, ScopeKind::Function);
+            (index, captures.get(1))
+        } else if let Some(captures) = self.type_regex.captures(item) {
+            let name = &captures[4];
+            let rest = &item[captures.get(0)?.end()..];
+            let (entity_type, kind) = match &captures[3] {
+                "trait" => (EntityType::Interface, "trait"),
+                "type" => (EntityType::Type, "alias"),
+                kind => (EntityType::Class, kind),
+            };
+            let index = parsed.push(
+                line_entity(name, entity_type, file_path, line_num, line).with_metadata("kind".to_string(), kind.to_string()),
+            );
+            if captures[2].split_whitespace().any(|m| m == "unsafe") {
+                parsed.set_metadata(index, "unsafe", "true".to_string());
+            }
+            let after_generics = self.record_generics(parsed, index, rest);
+            if kind == "trait" {
+                // `trait Store: Send + Sync` names supertraits
+                if let Some(bounds) = after_generics.trim_start().strip_prefix(':') {
+                    let bounds = bounds.split(['{', ';']).next().unwrap_or_default();
+                    let bounds = bounds.split(" where ").next().unwrap_or_default();
+                    for bound in bounds.split('+').map(|b| strip_generics(b.trim())).filter(|b| !b.is_empty() && !b.starts_with('\'') && !b.starts_with('?')) {
+                        parsed.link_symbol(index, bound, RelationType::Extends);
+                    }
+                }
+            }
+            if kind != "alias" {
+                tracker.expect_body(name, index, ScopeKind::Type);
+            }
+            (index, captures.get(1))
+        } else if self.impl_regex.is_match(item) {
+            (self.parse_impl(parsed, tracker, file_path, line_num, line, item)?, None)
+        } else if let Some(captures) = self.value_regex.captures(item) {
+            let name = &captures[4];
+            if name == "_" {
+                return None;
+            }
+            let index = if &captures[2] == "const" {
+                parsed.push(line_entity(name, EntityType::Constant, file_path, line_num, line))
+            } else {
+                let index = parsed.push(
+                    line_entity(name, EntityType::Variable, file_path, line_num, line)
+                        .with_metadata("kind".to_string(), "static".to_string()),
+                );
+                if captures.get(3).is_some() {
+                    parsed.set_metadata(index, "mutable", "true".to_string());
+                }
+                index
+            };
+            (index, captures.get(1))
+        } else {
+            return None;
+        };
+
+        if let Some(visibility) = visibility {
+            parsed.set_metadata(index, "visibility", visibility.as_str().trim().to_string());
+        }
+        if let Some(parent) = parent {
+            parsed.contain(parent, index);
+            // Methods belong to the type an `impl` block is for
+            if let Some(self_type) = parsed.entities[parent].metadata.get("self_type").cloned() {
+                parsed.set_metadata(index, "enclosing", self_type);
+            }
+        }
+        Some(index)
+    }
+
+    /// Enum variants, or the items of an `impl` or `trait` body
+    #[allow(clippy::too_many_arguments)]
+    fn parse_member(
+        &self,
+        parsed: &mut ParsedFile,
+        tracker: &mut ScopeTracker,
+        open_use: &mut Option<OpenUse>,
+        parent: usize,
+        file_path: &str,
+        line_num: usize,
+        line: &str,
+        item: &str,
+    ) -> Option<usize> {
+        match parsed.entities[parent].metadata.get("kind").map(String::as_str) {
+            Some("enum") => {
+                let captures = self.variant_regex.captures(item)?;
+                let index = parsed.push(
+                    line_entity(&captures[1], EntityType::Constant, file_path, line_num, line)
+                        .with_metadata("kind".to_string(), "variant".to_string()),
+                );
+                // Struct-like variants list fields we don't record
+                if item.trim_end().ends_with('{') {
+                    tracker.expect_body(&captures[1], index, ScopeKind::Function);
+                }
+                parsed.contain(parent, index);
+                Some(index)
+            }
+            Some("impl") | Some("trait") => self.parse_item(parsed, tracker, open_use, Some(parent), file_path, line_num, line, item),
+            _ => None,
+        }
+    }
+
+    /// `impl<T> Display for Wrapper<T>` becomes a module named `impl Wrapper`,
+    /// contained by `Wrapper` and implementing `Display`
+    fn parse_impl(
+        &self,
+        parsed: &mut ParsedFile,
+        tracker: &mut ScopeTracker,
+        file_path: &str,
+        line_num: usize,
+        line: &str,
+        item: &str,
+    ) -> Option<usize> {
+        let rest = item.trim_start().trim_start_matches("unsafe").trim_start().strip_prefix("impl")?;
+        let (generics, rest) = match split_balanced(rest, '<', '>') {
+            Some((generics, rest)) => (Some(generics.trim()), rest),
+            None => (None, rest),
+        };
+        let header = rest.split('{').next().unwrap_or_default();
+        let header = header.split(" where ").next().unwrap_or_default().trim();
+        let (trait_name, self_type) = match header.split_once(" for ") {
+            Some((trait_name, self_type)) => (Some(trait_name.trim()), self_type.trim()),
+            None => (None, header),
+        };
+        let self_type = type_name(self_type);
+        if self_type.is_empty() {
+            return None;
+        }
+
+        let index = parsed.push(
+            line_entity(&format!("impl {}", self_type), EntityType::Module, file_path, line_num, line)
+                .with_metadata("kind".to_string(), "impl".to_string())
+                .with_metadata("self_type".to_string(), self_type.to_string()),
+        );
+        if let Some(generics) = generics.filter(|g| !g.is_empty()) {
+            parsed.set_metadata(index, "generics", generics.to_string());
+        }
+        // The self type may be declared in another module
+        let member_of = Relationship::member_of(parsed.entities[index].id.clone(), self_type);
+        parsed.relationships.push(member_of);
+        if let Some(trait_name) = trait_name {
+            // `impl !Send for T` opts out rather than implements
+            if let Some(negative) = trait_name.strip_prefix('!') {
+                parsed.set_metadata(index, "negative", type_name(negative).to_string());
+            } else {
+                let trait_name = type_name(trait_name);
+                parsed.set_metadata(index, "trait", trait_name.to_string());
+                parsed.link_symbol(index, trait_name, RelationType::Implements);
+            }
+        }
+        tracker.expect_body(&format!("impl {}", self_type), index, ScopeKind::Type);
+        Some(index)
+    }
+
+    /// Stores `<T: Clone>` as `generics` and returns the text after it
+    fn record_generics<'a>(&self, parsed: &mut ParsedFile, index: usize, rest: &'a str) -> &'a str {
+        match split_balanced(rest, '<', '>') {
+            Some((generics, after)) => {
+                parsed.set_metadata(index, "generics", generics.trim().to_string());
+                after
+            }
+            None => rest,
+        }
+    }
+
+    /// One Import per path in `use a::{b, c::{d, e as f}};`
+    #[allow(clippy::too_many_arguments)]
+    fn push_use(
+        &self,
+        parsed: &mut ParsedFile,
+        file_path: &str,
+        start: usize,
+        end: usize,
+        visibility: &str,
+        text: &str,
+        first_line: &str,
+    ) -> Option<usize> {
+        let tree = text.split(';').next().unwrap_or_default();
+        let mut last = None;
+        for (path, alias) in expand_use_tree("", tree) {
+            let mut entity = line_entity(&path, EntityType::Import, file_path, start, first_line);
+            if let Some(alias) = alias {
+                entity = entity.with_metadata("alias".to_string(), alias);
+            }
+            // `pub use` re-exports the path from this module
+            if !visibility.is_empty() {
+                entity = entity.with_metadata("visibility".to_string(), visibility.to_string());
+            }
+            let index = parsed.push(entity);
+            parsed.set_line_end(index, end);
+            last = Some(index);
+        }
+        last
+    }
+}
+
+// `&'a mut crate::Wrapper<T>` -> `Wrapper`
+fn type_name(text: &str) -> &str {
+    let text = text.trim().trim_start_matches('&').trim_start();
+    let text = if text.starts_with('\'') { text.split_once(' ').map(|(_, t)| t).unwrap_or(text) } else { text };
+    let text = text.trim_start().trim_start_matches("mut ").trim_start().trim_start_matches("dyn ");
+    strip_generics(text).rsplit("::").next().unwrap_or_default().trim()
+}
+
+/// Strips leading `#[...]` attributes from an item line, collecting them
+fn strip_attributes<'a>(line: &'a str, attributes: &mut Attributes) -> &'a str {
+    let mut rest = line.trim_start();
+    // Inner attributes like `#![allow(...)]` apply to the enclosing module
+    while rest.starts_with("#[") || rest.starts_with("#![") {
+        let inner = rest.starts_with("#!");
+        let Some((attribute, after)) = split_balanced(&rest[1 + inner as usize..], '[', ']') else { return "" };
+        rest = after.trim_start();
+        if inner {
+            continue;
+        }
+
+        let attribute = attribute.trim();
+        let name_end = attribute.find(|c: char| c == '(' || c == '=' || c.is_whitespace()).unwrap_or(attribute.len());
+        let name = &attribute[..name_end];
+        let args = split_balanced(&attribute[name_end..], '(', ')').map(|(args, _)| args.trim());
+        match (name, args) {
+            ("cfg", Some(args)) => attributes.cfg.push(args.to_string()),
+            ("derive", Some(args)) => attributes.derives.extend(args.split(',').map(|d| d.trim().to_string()).filter(|d| !d.is_empty())),
+            _ => {}
+        }
+        if name == "test" || name.ends_with("::test") || name == "bench" {
+            attributes.test = true;
+        }
+        attributes.names.push(name.to_string());
+    }
+    rest
+}
+
+/// Flatten a use tree into full paths with their aliases; `self` names the prefix itself
+fn expand_use_tree(prefix: &str, tree: &str) -> Vec<(String, Option<String>)> {
+    let tree = tree.trim();
+    let join = |path: &str| match (prefix.is_empty(), path) {
+        (_, "self") => prefix.to_string(),
+        (true, _) => path.to_string(),
+        (false, _) => format!("{}::{}", prefix, path),
+    };
+
+    if let Some(open) = tree.find('{') {
+        let group_prefix = join(tree[..open].trim().trim_end_matches("::"));
+        let group = tree[open + 1..].trim_end().strip_suffix('}').unwrap_or(&tree[open + 1..]);
+        let group_prefix = if tree[..open].trim().is_empty() { prefix.to_string() } else { group_prefix };
+        return split_use_group(group).into_iter().flat_map(|item| expand_use_tree(&group_prefix, item)).collect();
+    }
+
+    let (path, alias) = match tree.split_once(" as ") {
+        Some((path, alias)) => (path.trim(), Some(alias.trim().to_string())),
+        None => (tree, None),
+    };
+    if path.is_empty() {
+        return Vec::new();
+    }
+    vec![(join(path), alias)]
+}
+
+// Commas inside nested `{}` groups belong to the inner group
+fn split_use_group(group: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, c) in group.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(&group[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&group[start..]);
+    items.into_iter().map(str::trim).filter(|item| !item.is_empty()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_support::ParsedSource;
+
+    #[test]
+    fn test_rust_items_and_attributes() -> Result<()> {
+        let source = r#"#![allow(dead_code)]
+use std::{
+    collections::{HashMap, HashSet as Set},
+    io::{self, Read},
+};
+pub use crate::storage::MemoryStorage;
+extern crate serde as json;
+
+#[macro_export]
+macro_rules! entity {
+    ($name:expr) => { Entity::new($name) };
+}
+
+pub(crate) type Index = HashMap<String, usize>;
+pub static mut COUNTER: u32 = 0;
+const MAX: usize = 10;
+
+/// A drawable shape.
+#[derive(Debug, Clone)]
+// not part of the docs
+pub enum Shape<'a> {
+    Circle(f64),
+    Named { name: &'a str },
+    Empty,
+}
+
+pub trait Store: Send + Sync {
+    fn get(&self, id: &str) -> Option<String>;
+    fn open() -> Self;
+}
+
+impl<'a> std::fmt::Display for Shape<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "shape")
+    }
+}
+
+pub(crate) async unsafe fn load<T: Clone>(items: &[T]) {}
+/// The largest batch.
+///
+/// # Returns
+///
+/// Always [`MAX`].
+pub const fn limit() -> usize { MAX }
+
+extern "C" {
+    fn abs(x: i32) -> i32;
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests.
+    #[test]
+    fn test_load() {
+        fn helper() {}
+    }
+}
+"#;
+        let (entities, relationships) = RustParser::new()?.parse(source, "src/lib.rs")?;
+        let find = |name: &str| entities.iter().find(|e| e.name == name).unwrap_or_else(|| panic!("missing {}", name));
+        let meta = |name: &str, key: &str| find(name).metadata.get(key).cloned();
+        let imports: Vec<_> = entities.iter().filter(|e| e.entity_type == EntityType::Import).map(|e| e.name.as_str()).collect();
+
+        assert_eq!(
+            imports,
+            vec!["std::collections::HashMap", "std::collections::HashSet", "std::io", "std::io::Read", "crate::storage::MemoryStorage", "serde"]
+        );
+        assert_eq!(meta("std::collections::HashSet", "alias").as_deref(), Some("Set"));
+        assert_eq!(find("std::io::Read").line_end, 5);
+        assert_eq!(meta("crate::storage::MemoryStorage", "visibility").as_deref(), Some("pub"));
+
+        assert_eq!(meta("entity", "kind").as_deref(), Some("macro"));
+        assert_eq!(meta("entity", "attributes").as_deref(), Some("macro_export"));
+        assert_eq!(find("Index").entity_type, EntityType::Type);
+        assert_eq!(meta("Index", "visibility").as_deref(), Some("pub(crate)"));
+        assert_eq!(meta("COUNTER", "mutable").as_deref(), Some("true"));
+        assert_eq!(find("MAX").entity_type, EntityType::Constant);
+
+        assert_eq!(meta("Shape", "kind").as_deref(), Some("enum"));
+        assert_eq!(meta("Shape", "derives").as_deref(), Some("Debug,Clone"));
+        assert_eq!(meta("Shape", "generics").as_deref(), Some("'a"));
+        let variants: Vec<_> = entities.iter().filter(|e| e.metadata.get("kind").map(String::as_str) == Some("variant")).map(|e| e.name.as_str()).collect();
+        assert_eq!(variants, vec!["Circle", "Named", "Empty"]);
+
+        assert_eq!(find("Store").entity_type, EntityType::Interface);
+        assert_eq!(meta("get", "enclosing").as_deref(), Some("Store"));
+        assert_eq!(meta("open", "static").as_deref(), Some("true"));
+        assert_eq!(meta("impl Shape", "trait").as_deref(), Some("Display"));
+        assert_eq!(meta("fmt", "enclosing").as_deref(), Some("Shape"));
+        assert_eq!(meta("fmt", "static"), None);
+
+        assert_eq!(meta("load", "visibility").as_deref(), Some("pub(crate)"));
+        assert_eq!(meta("load", "async").as_deref(), Some("true"));
+        assert_eq!(meta("load", "unsafe").as_deref(), Some("true"));
+        assert_eq!(meta("load", "generics").as_deref(), Some("T: Clone"));
+        assert_eq!(meta("limit", "const").as_deref(), Some("true"));
+        assert_eq!(meta("limit", "doc").as_deref(), Some("The largest batch."));
+        assert_eq!(meta("limit", "doc_returns").as_deref(), Some("Always [`MAX`]."));
+        assert_eq!(meta("Shape", "doc").as_deref(), Some("A drawable shape."));
+        assert_eq!(meta("Circle", "doc"), None);
+        assert_eq!(meta("tests", "doc").as_deref(), Some("Unit tests."));
+        assert_eq!(find("abs").metadata.get("enclosing"), None);
+
+        assert_eq!(meta("tests", "cfg").as_deref(), Some("test"));
+        assert_eq!(meta("test_load", "test").as_deref(), Some("true"));
+        assert_eq!(meta("test_load", "enclosing").as_deref(), Some("tests"));
+        assert!(!entities.iter().any(|e| e.name == "helper"));
+
+        let edges = |name: &str| {
+            let from = &find(name).id;
+            let mut edges: Vec<_> = relationships
+                .iter()
+                .filter(|r| &r.from_entity == from && r.relationship_type != RelationType::Contains)
+                .map(|r| (r.relationship_type.as_str(), r.to_entity.as_str()))
+                .collect();
+            edges.sort();
+            edges
+        };
+        assert_eq!(edges("Store"), vec![("extends", "Send"), ("extends", "Sync")]);
+        assert_eq!(find("impl Shape").entity_type, EntityType::Module);
+        assert_eq!(edges("impl Shape"), vec![("implements", "Display")]);
+        let member_of = relationships.iter().find(|r| r.from_entity == find("impl Shape").id && r.relationship_type == RelationType::Contains).unwrap();
+        assert_eq!(member_of.target_name(), Some("Shape"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_braces_in_raw_and_multiline_strings() -> Result<()> {
+        let source = r##"pub struct Lexer;
+
+impl Lexer {
+    pub fn new() -> Self {
+        let _open = Regex::new(r#"^\s*\{"#);
+        let _close = br"}";
+        Self
+    }
+
+    fn usage() -> &'static str {
+        "usage: lexer {
+    --input <file>
+}"
+    }
+
+    fn parse(&self) {}
+}
+
+fn after() {}
+"##;
+        let file = ParsedSource::new(&RustParser::new()?, "src/lexer.rs", source)?;
+        assert_eq!(file.find("new").line_end, 8);
+        assert_eq!(file.find("usage").line_end, 14);
+        assert_eq!(file.meta("parse", "enclosing"), Some("Lexer"));
+        assert_eq!(file.find("after").metadata.get("enclosing"), None);
+        assert!(!file.has("input"));
+        Ok(())
+    }
+}
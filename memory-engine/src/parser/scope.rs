@@ -0,0 +1,465 @@
+// Brace tracking shared by the parsers for C-family languages.
+//
+// The regex parsers work line by line; this keeps just enough lexical state
+// (comments, string literals) to count braces reliably, so a parser can tell
+// which type a member is declared in and on which line a body ends.
+
+/// What kind of declaration a braced body belongs to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ScopeKind {
+    Namespace,
+    Type,
+    Function,
+}
+
+/// A declaration whose body is currently open
+#[derive(Debug, Clone)]
+pub(crate) struct OpenScope {
+    pub name: String,
+    pub entity: Option<usize>, // Index into the parser's entity list; `None` for anonymous blocks
+    pub kind: ScopeKind,
+    depth: usize,
+}
+
+#[derive(Debug, Clone)]
+struct PendingScope {
+    name: String,
+    entity: Option<usize>,
+    kind: ScopeKind,
+    lines_waited: usize,
+}
+
+// A declaration line like `int main()` may have its `{` a line or two later
+const MAX_LINES_BEFORE_BODY: usize = 3;
+
+/// How a string literal escapes its closing delimiter
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Escape {
+    Backslash,
+    DoubledQuote, // C# verbatim strings: `@"say ""hi"""`
+    None,         // Raw strings
+}
+
+/// A string literal still open at the end of a line
+#[derive(Debug, Clone)]
+struct OpenString {
+    close: String,
+    escape: Escape,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct ScopeTracker {
+    depth: usize,
+    scopes: Vec<OpenScope>,
+    pending: Option<PendingScope>,
+    in_block_comment: bool,
+    open_string: Option<OpenString>,
+    single_quoted_strings: bool,
+    rust_strings: bool,
+    verbatim_strings: bool,
+    regex_literals: bool,
+    heredocs: bool,
+    open_heredoc: Option<String>,
+}
+
+impl ScopeTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Treat `'...'` as a string literal rather than a char literal (JavaScript)
+    pub fn with_single_quoted_strings(mut self) -> Self {
+        self.single_quoted_strings = true;
+        self
+    }
+
+    /// Skip raw strings like `r#"..."#` and let `"..."` strings span lines (Rust)
+    pub fn with_rust_strings(mut self) -> Self {
+        self.rust_strings = true;
+        self
+    }
+
+    /// Skip verbatim strings like `@"C:\"`, where only `""` escapes a quote (C#)
+    pub fn with_verbatim_strings(mut self) -> Self {
+        self.verbatim_strings = true;
+        self
+    }
+
+    /// Skip regex literals like `/[{]/` where an expression is expected (JavaScript)
+    pub fn with_regex_literals(mut self) -> Self {
+        self.regex_literals = true;
+        self
+    }
+
+    /// Skip heredoc and nowdoc bodies, from `<<<EOT` to the line closing `EOT` (PHP)
+    pub fn with_heredocs(mut self) -> Self {
+        self.heredocs = true;
+        self
+    }
+
+    /// Brace depth before the current line is processed
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Whether the current line starts inside a `/* */` comment
+    pub fn in_block_comment(&self) -> bool {
+        self.in_block_comment
+    }
+
+    /// Whether the current line starts inside a string literal
+    pub fn in_string(&self) -> bool {
+        self.open_string.is_some()
+    }
+
+    /// Whether the current line is inside a heredoc body
+    pub fn in_heredoc(&self) -> bool {
+        self.open_heredoc.is_some()
+    }
+
+    /// The next `{` opens the body of this entity
+    pub fn expect_body(&mut self, name: &str, entity: usize, kind: ScopeKind) {
+        self.pending = Some(PendingScope {
+            name: name.to_string(),
+            entity: Some(entity),
+            kind,
+            lines_waited: 0,
+        });
+    }
+
+    /// The next `{` opens a block without an entity, such as an anonymous
+    /// namespace or `extern "C"`; declarations inside belong to the outer scope
+    pub fn expect_anonymous_body(&mut self, kind: ScopeKind) {
+        self.pending = Some(PendingScope {
+            name: String::new(),
+            entity: None,
+            kind,
+            lines_waited: 0,
+        });
+    }
+
+    /// Innermost open scope of any kind
+    pub fn innermost(&self) -> Option<&OpenScope> {
+        self.scopes.last()
+    }
+
+    /// The scope whose body the current line sits directly in, i.e. not inside
+    /// an unnamed block, lambda or initializer nested within it. Anonymous
+    /// namespaces are looked through; other anonymous bodies hide their parent.
+    pub fn current_body(&self) -> Option<&OpenScope> {
+        let mut depth = self.depth;
+        for scope in self.scopes.iter().rev() {
+            if scope.depth != depth {
+                return None;
+            }
+            if scope.entity.is_some() {
+                return Some(scope);
+            }
+            if scope.kind != ScopeKind::Namespace {
+                return None;
+            }
+            depth -= 1;
+        }
+        None
+    }
+
+    /// Names of the open namespace and type scopes, outermost first
+    pub fn path(&self) -> Vec<&str> {
+        self.scopes
+            .iter()
+            .filter(|scope| scope.entity.is_some() && scope.kind != ScopeKind::Function)
+            .map(|scope| scope.name.as_str())
+            .collect()
+    }
+
+    /// Whether the current line is at file level, looking through anonymous blocks
+    pub fn at_top_level(&self) -> bool {
+        self.scopes.len() >= self.depth
+            && self.scopes[..self.depth]
+                .iter()
+                .enumerate()
+                .all(|(index, scope)| scope.entity.is_none() && scope.depth == index + 1)
+    }
+
+    /// Consume one line, returning the entity indexes whose bodies closed on it
+    pub fn process_line(&mut self, line: &str) -> Vec<usize> {
+        let mut closed = Vec::new();
+        let chars: Vec<char> = line.chars().collect();
+        let mut i = 0;
+
+        // The closing label may be indented and followed by `;`, `,` or `)`
+        if let Some(label) = &self.open_heredoc {
+            let indent = chars.iter().take_while(|c| c.is_whitespace()).count();
+            let after = chars.get(indent + label.chars().count());
+            if !starts_with(&chars, indent, label) || after.is_some_and(|c| c.is_alphanumeric() || *c == '_') {
+                return closed;
+            }
+            i = indent + label.chars().count();
+            self.open_heredoc = None;
+        }
+
+        while i < chars.len() {
+            let c = chars[i];
+            let next = chars.get(i + 1).copied();
+
+            if self.in_block_comment {
+                if c == '*' && next == Some('/') {
+                    self.in_block_comment = false;
+                    i += 1;
+                }
+                i += 1;
+                continue;
+            }
+
+            if let Some(string) = &self.open_string {
+                match string_end(&chars, i, string) {
+                    Some(end) => {
+                        self.open_string = None;
+                        i = end;
+                        continue;
+                    }
+                    None => break,
+                }
+            }
+
+            match c {
+                '/' if next == Some('/') => break,
+                '#' if i == 0 || chars[..i].iter().all(|c| c.is_whitespace()) => {
+                    // Preprocessor lines and PHP/shell comments hold no braces we care about
+                    break;
+                }
+                '/' if next == Some('*') => {
+                    self.in_block_comment = true;
+                    i += 1;
+                }
+                // A `/` with no closing `/` on the line is division after all
+                '/' if self.regex_literals && expects_expression(&chars[..i]) => {
+                    if let Some(end) = regex_end(&chars, i + 1) {
+                        i = end;
+                    }
+                }
+                // Java text blocks and Kotlin raw strings
+                '"' if starts_with(&chars, i, TRIPLE_QUOTE) => {
+                    i = self.skip_literal(&chars, i + TRIPLE_QUOTE.len(), TRIPLE_QUOTE, Escape::Backslash);
+                }
+                // `r"..."`, `r#"..."#` and byte or C string variants like `br"..."`
+                'r' if self.rust_strings && !is_word_before(&chars, raw_prefix_start(&chars, i)) => {
+                    let hashes = chars[i + 1..].iter().take_while(|c| **c == '#').count();
+                    if chars.get(i + 1 + hashes) == Some(&'"') {
+                        let close = format!("\"{}", "#".repeat(hashes));
+                        i = self.skip_literal(&chars, i + hashes + 2, &close, Escape::None);
+                    }
+                }
+                '<' if self.heredocs && starts_with(&chars, i, "<<<") => {
+                    let label: String = chars[i + 3..]
+                        .iter()
+                        .skip_while(|c| c.is_whitespace() || **c == '\'' || **c == '"')
+                        .take_while(|c| c.is_alphanumeric() || **c == '_')
+                        .collect();
+                    if !label.is_empty() {
+                        // The body starts on the next line
+                        self.open_heredoc = Some(label);
+                        break;
+                    }
+                    i += 2;
+                }
+                // `@"..."`, `$@"..."` and `@$"..."`
+                '@' | '$' if self.verbatim_strings && is_verbatim_start(&chars, i) => {
+                    let quote = if chars[i + 1] == '"' { i + 1 } else { i + 2 };
+                    i = self.skip_literal(&chars, quote + 1, "\"", Escape::DoubledQuote);
+                }
+                '"' if self.rust_strings => i = self.skip_literal(&chars, i + 1, "\"", Escape::Backslash),
+                '"' => i = skip_string(&chars, i, '"'),
+                '`' => i = self.skip_literal(&chars, i + 1, "`", Escape::Backslash),
+                // Only a char literal when it closes shortly; Rust lifetimes and
+                // generics like 'a must not swallow the rest of the line
+                '\'' if self.single_quoted_strings || next == Some('\\') || chars.get(i + 2) == Some(&'\'') => {
+                    i = skip_string(&chars, i, '\'');
+                }
+                '{' => {
+                    self.depth += 1;
+                    if let Some(pending) = self.pending.take() {
+                        self.scopes.push(OpenScope {
+                            name: pending.name,
+                            entity: pending.entity,
+                            kind: pending.kind,
+                            depth: self.depth,
+                        });
+                    }
+                }
+                '}' => {
+                    while self.scopes.last().map(|s| s.depth >= self.depth).unwrap_or(false) {
+                        closed.extend(self.scopes.pop().unwrap().entity);
+                    }
+                    self.depth = self.depth.saturating_sub(1);
+                }
+                ';' => {
+                    // A declaration ending in `;` has no body
+                    self.pending = None;
+                }
+                _ => {}
+            }
+
+            i += 1;
+        }
+
+        if let Some(pending) = &mut self.pending {
+            pending.lines_waited += 1;
+            if pending.lines_waited > MAX_LINES_BEFORE_BODY {
+                self.pending = None;
+            }
+        }
+
+        closed
+    }
+
+    // Skip a string literal whose body starts at `start`, returning the index of
+    // its last character; one left open continues on the next line
+    fn skip_literal(&mut self, chars: &[char], start: usize, close: &str, escape: Escape) -> usize {
+        let string = OpenString { close: close.to_string(), escape };
+        match string_end(chars, start, &string) {
+            Some(end) => end - 1,
+            None => {
+                self.open_string = Some(string);
+                chars.len()
+            }
+        }
+    }
+}
+
+const TRIPLE_QUOTE: &str = "\"\"\"";
+
+fn starts_with(chars: &[char], start: usize, delimiter: &str) -> bool {
+    delimiter.chars().enumerate().all(|(offset, c)| chars.get(start + offset) == Some(&c))
+}
+
+// Whether a `/` after `before` starts a regex rather than dividing, as in
+// `= /x/`, `(/x/` or `return /x/`
+fn expects_expression(before: &[char]) -> bool {
+    let is_word = |c: &char| c.is_alphanumeric() || *c == '_' || *c == '$';
+    let end = before.iter().rposition(|c| !c.is_whitespace()).map_or(0, |i| i + 1);
+    match before[..end].last() {
+        None => true,
+        Some(c) if is_word(c) => {
+            let start = before[..end].iter().rposition(|c| !is_word(c)).map_or(0, |i| i + 1);
+            let word: String = before[start..end].iter().collect();
+            matches!(word.as_str(), "return" | "typeof" | "case" | "in" | "of" | "delete" | "void" | "throw" | "yield" | "await" | "else" | "do")
+        }
+        Some(c) => "=(,:[!&|?{};+-*%>~^".contains(*c),
+    }
+}
+
+// Index of the `/` closing a regex literal whose body starts at `start`
+fn regex_end(chars: &[char], start: usize) -> Option<usize> {
+    let mut in_class = false;
+    let mut i = start;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            '[' => in_class = true,
+            ']' => in_class = false,
+            '/' if !in_class => return Some(i),
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+// `a` in `bar(` or `_r"` is part of a name, not a string prefix
+fn is_word_before(chars: &[char], i: usize) -> bool {
+    i > 0 && (chars[i - 1].is_alphanumeric() || chars[i - 1] == '_')
+}
+
+fn is_verbatim_start(chars: &[char], i: usize) -> bool {
+    matches!(
+        (chars[i], chars.get(i + 1), chars.get(i + 2)),
+        ('@', Some('"'), _) | ('@', Some('$'), Some('"')) | ('$', Some('@'), Some('"'))
+    )
+}
+
+// Start of `r`, `br` or `cr` before a raw string's quote
+fn raw_prefix_start(chars: &[char], r: usize) -> usize {
+    match r.checked_sub(1).map(|i| chars[i]) {
+        Some('b' | 'c') => r - 1,
+        _ => r,
+    }
+}
+
+// Index just past the string's closing delimiter, or `None` if it runs past the line
+fn string_end(chars: &[char], start: usize, string: &OpenString) -> Option<usize> {
+    let mut i = start;
+    while i < chars.len() {
+        let escape = match string.escape {
+            Escape::Backslash => chars[i] == '\\',
+            Escape::DoubledQuote => starts_with(chars, i, "\"\""),
+            Escape::None => false,
+        };
+        if escape {
+            i += 2;
+            continue;
+        }
+        if starts_with(chars, i, &string.close) {
+            return Some(i + string.close.chars().count());
+        }
+        i += 1;
+    }
+    None
+}
+
+// Returns the index of the closing quote (or the end of the line)
+fn skip_string(chars: &[char], start: usize, quote: char) -> usize {
+    let mut i = start + 1;
+    while i < chars.len() {
+        if chars[i] == '\\' {
+            i += 2;
+            continue;
+        }
+        if chars[i] == quote {
+            return i;
+        }
+        i += 1;
+    }
+    chars.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_scopes_and_line_ends() {
+        let source = [
+            "class Outer {",              // 0
+            "  String s = \"}{\"; // }",  // 1
+            "  class Inner {",            // 2
+            "    void run()",             // 3
+            "    {",                      // 4
+            "    }",                      // 5
+            "  }",                        // 6
+            "  abstract void stop();",    // 7
+            "}",                          // 8
+        ];
+
+        let mut tracker = ScopeTracker::new();
+        let mut closed_at = Vec::new();
+        for (line_num, line) in source.iter().enumerate() {
+            match line_num {
+                0 => tracker.expect_body("Outer", 0, ScopeKind::Type),
+                2 => {
+                    assert_eq!(tracker.innermost().map(|s| s.name.as_str()), Some("Outer"));
+                    tracker.expect_body("Inner", 1, ScopeKind::Type);
+                }
+                3 => tracker.expect_body("run", 2, ScopeKind::Function),
+                7 => tracker.expect_body("stop", 3, ScopeKind::Function),
+                _ => {}
+            }
+            for entity in tracker.process_line(line) {
+                closed_at.push((entity, line_num));
+            }
+        }
+
+        assert_eq!(closed_at, vec![(2, 5), (1, 6), (0, 8)]);
+        assert_eq!(tracker.depth(), 0);
+    }
+}
@@ -0,0 +1,835 @@
+// Declaration signatures: parameters, return types and fields read back from
+// the source of each function and type a parser found. Parsers locate
+// declarations line by line; this pass re-reads the full declaration, which
+// may span several lines, once the entity's extent is known.
+
+use super::split_balanced;
+use crate::{CodeEntity, EntityType, Parameter, Signature};
+
+/// How a language writes parameters, return types and fields
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum SignatureStyle {
+    /// `name: Type`, `-> Ret`, fields in the struct body
+    Rust,
+    /// `name: Type = default`, `-> Ret`, annotated class attributes
+    Python,
+    /// `name?: Type = default`, `: Ret` (JavaScript and TypeScript)
+    JavaScript,
+    /// `val name: Type = default`, `: Ret`, properties in the primary constructor
+    Kotlin,
+    /// `label name: Type = default`, `-> Ret`
+    Swift,
+    /// `a, b int`, results after the parameters, fields in the struct body
+    Go,
+    /// `Type name = default`, return type before the name (Java, C#, C, C++)
+    CFamily,
+    /// `Type $name = default`, `: Ret`
+    Php,
+    /// Untyped `a, b = 1, key:`
+    Ruby,
+}
+
+// A parameter list longer than this is not worth chasing
+const MAX_DECLARATION_LINES: usize = 40;
+
+const VISIBILITY: &[&str] = &["public", "private", "protected", "internal", "fileprivate", "open"];
+
+/// Keywords kept as signature modifiers
+const MODIFIERS: &[&str] = &[
+    "abstract", "async", "const", "constexpr", "extern", "final", "inline", "mutating", "native", "override", "sealed",
+    "static", "suspend", "synchronized", "unsafe", "virtual",
+];
+
+/// Keywords that may precede a C-family return type without being part of it
+const DECLARATION_KEYWORDS: &[&str] = &[
+    "abstract", "async", "constexpr", "default", "explicit", "extern", "final", "friend", "inline", "native", "new",
+    "override", "partial", "readonly", "sealed", "static", "synchronized", "transient", "unsafe", "virtual", "volatile",
+];
+
+/// Fill in `signature` for the functions and types among `entities`
+pub(crate) fn record_signatures(entities: &mut [CodeEntity], content: &str, style: SignatureStyle) {
+    let lines: Vec<&str> = content.lines().collect();
+    for index in 0..entities.len() {
+        let entity = &entities[index];
+        let signature = match entity.entity_type {
+            EntityType::Function => function_signature(&lines, entity, style),
+            EntityType::Class | EntityType::Interface | EntityType::Type => type_signature(&lines, entities, entity, style),
+            _ => None,
+        };
+        if signature.is_some() {
+            entities[index].signature = signature;
+        }
+    }
+}
+
+fn function_signature(lines: &[&str], entity: &CodeEntity, style: SignatureStyle) -> Option<Signature> {
+    let text = declaration_text(lines, entity, style);
+    let name_at = find_word(&text, &entity.name)?;
+    let prefix = &text[..name_at];
+    let mut after = text[name_at + entity.name.len()..].trim_start_matches(['?', '!']).trim_start();
+    let mut generics = entity.metadata.get("generics").cloned();
+
+    if style == SignatureStyle::JavaScript {
+        after = skip_function_expression(after);
+    }
+    let (open, close) = if style == SignatureStyle::Go { ('[', ']') } else { ('<', '>') };
+    if let Some((inner, rest)) = split_balanced(after, open, close) {
+        generics.get_or_insert_with(|| inner.trim().to_string());
+        after = rest.trim_start();
+    }
+
+    let (params, rest) = match split_balanced(after, '(', ')') {
+        Some((params, rest)) => (parse_params(params, style), rest),
+        // `def name` and `x => x * 2` leave out the parentheses; `def name; end` has none
+        None if style == SignatureStyle::Ruby => (parse_params(after.split(';').next().unwrap_or_default(), style), ""),
+        None if style == SignatureStyle::JavaScript && after.contains("=>") => {
+            (parse_params(after.split("=>").next().unwrap_or_default(), style), "")
+        }
+        None => return None,
+    };
+
+    let is_macro = entity.metadata.get("kind").map(String::as_str) == Some("macro");
+    let mut signature = Signature {
+        visibility: visibility(prefix, entity),
+        modifiers: modifiers(prefix, entity, style),
+        params: Some(params),
+        return_type: if is_macro { None } else { return_type(prefix, rest, style) },
+        ..Default::default()
+    };
+    // Java and Kotlin put type parameters before the name: `public <T> T first(...)`
+    let leading_generics = style == SignatureStyle::Kotlin || (style == SignatureStyle::CFamily && entity.file_path.ends_with(".java"));
+    if leading_generics && generics.is_none() {
+        generics = leading_type_parameters(prefix);
+    }
+    signature.generics = generics.filter(|g| !g.is_empty());
+    Some(signature)
+}
+
+// `<T>` right after the modifiers, as in `public static <T> List<T>` or `fun <T>`;
+// a `<` later on belongs to the return or receiver type
+fn leading_type_parameters(prefix: &str) -> Option<String> {
+    let mut rest = strip_annotations(prefix);
+    loop {
+        if rest.starts_with('<') {
+            return split_balanced(rest, '<', '>').map(|(inner, _)| inner.trim().to_string());
+        }
+        let end = rest.find(char::is_whitespace)?;
+        let word = &rest[..end];
+        if !(VISIBILITY.contains(&word) || DECLARATION_KEYWORDS.contains(&word) || MODIFIERS.contains(&word) || word == "fun") {
+            return None;
+        }
+        rest = rest[end..].trim_start();
+    }
+}
+
+fn type_signature(lines: &[&str], entities: &[CodeEntity], entity: &CodeEntity, style: SignatureStyle) -> Option<Signature> {
+    let kind = entity.metadata.get("kind").map(String::as_str).unwrap_or_default();
+    if kind == "impl" || kind == "alias" || kind == "namespace" {
+        return None;
+    }
+    let header = lines.get(entity.line_start.saturating_sub(1) as usize).copied().unwrap_or_default();
+    let prefix = find_word(header, &entity.name).map(|at| &header[..at]).unwrap_or_default();
+
+    let fields = match style {
+        SignatureStyle::Rust if kind == "struct" || kind == "union" => rust_fields(lines, entity),
+        SignatureStyle::Go if kind == "struct" => go_fields(lines, entity),
+        SignatureStyle::Python => python_fields(lines, entity),
+        _ => {
+            let mut fields = if style == SignatureStyle::Kotlin { constructor_properties(lines, entity) } else { Vec::new() };
+            fields.extend(member_fields(lines, entities, entity, style));
+            fields
+        }
+    };
+
+    let signature = Signature {
+        visibility: visibility(prefix, entity),
+        modifiers: modifiers(prefix, entity, style),
+        generics: entity.metadata.get("generics").cloned(),
+        fields,
+        ..Default::default()
+    };
+    (signature != Signature::default()).then_some(signature)
+}
+
+/// The declaration's lines joined with spaces, up to where its parameter list
+/// has closed and no continuation is pending
+fn declaration_text(lines: &[&str], entity: &CodeEntity, style: SignatureStyle) -> String {
+    let start = entity.line_start.saturating_sub(1) as usize;
+    let mut text = String::new();
+    let mut depth = 0i32;
+    for line in lines.iter().skip(start).take(MAX_DECLARATION_LINES) {
+        let line = strip_comment(line, style).trim();
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        text.push_str(line);
+        for c in line.chars() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => {}
+            }
+        }
+        if depth <= 0 && !line.ends_with([',', '(', '<']) {
+            break;
+        }
+    }
+    text
+}
+
+// Trailing comments inside a multi-line parameter list; quotes are counted so
+// `"http://..."` defaults survive
+fn strip_comment(line: &str, style: SignatureStyle) -> &str {
+    let marker = if style == SignatureStyle::Python || style == SignatureStyle::Ruby { "#" } else { "//" };
+    let mut search = 0;
+    while let Some(found) = line[search..].find(marker) {
+        let at = search + found;
+        let single_quotes_closed = !single_quoted_strings(style) || line[..at].matches('\'').count().is_multiple_of(2);
+        if line[..at].matches('"').count().is_multiple_of(2) && single_quotes_closed {
+            return &line[..at];
+        }
+        search = at + marker.len();
+    }
+    line
+}
+
+/// Byte offset of `word` in `text` where it is not part of a longer identifier
+fn find_word(text: &str, word: &str) -> Option<usize> {
+    if word.is_empty() {
+        return None;
+    }
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    let mut search = 0;
+    while let Some(found) = text[search..].find(word) {
+        let at = search + found;
+        let before = text[..at].chars().next_back();
+        let after = text[at + word.len()..].chars().next();
+        if !before.map(is_ident).unwrap_or(false) && !after.map(is_ident).unwrap_or(false) {
+            return Some(at);
+        }
+        search = at + word.len();
+    }
+    None
+}
+
+// `name = async function* (` / `name: (a) =>` / `name = <T>(a: T) =>` up to the parameters
+fn skip_function_expression(text: &str) -> &str {
+    let Some(rest) = text.strip_prefix('=').or_else(|| text.strip_prefix(':')) else { return text };
+    let mut rest = rest.trim_start();
+    for keyword in ["async", "function"] {
+        if let Some(after) = rest.strip_prefix(keyword).filter(|after| !after.starts_with(|c: char| c.is_alphanumeric() || c == '_')) {
+            rest = after.trim_start().trim_start_matches('*').trim_start();
+        }
+    }
+    // A named function expression: `= function inner(`
+    let word_end = rest.find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$')).unwrap_or(rest.len());
+    if word_end > 0 && rest[word_end..].trim_start().starts_with(['(', '<']) {
+        rest = rest[word_end..].trim_start();
+    }
+    rest
+}
+
+fn single_quoted_strings(style: SignatureStyle) -> bool {
+    matches!(style, SignatureStyle::Python | SignatureStyle::JavaScript | SignatureStyle::Php | SignatureStyle::Ruby)
+}
+
+/// Whether the character at `position` starts a string. Elsewhere `'` only starts
+/// a char literal that closes within two characters, so `&'a str` is a lifetime.
+fn opens_quote(chars: &[(usize, char)], position: usize, style: SignatureStyle) -> bool {
+    let at = |offset: usize| chars.get(position + offset).map(|&(_, c)| c);
+    match chars[position].1 {
+        '"' | '`' => true,
+        '\'' => single_quoted_strings(style) || at(1) == Some('\\') || at(2) == Some('\''),
+        _ => false,
+    }
+}
+
+/// Split on commas outside brackets and quotes; `->` and `=>` don't close `<`
+fn split_params(list: &str, style: SignatureStyle) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut depth = 0i32;
+    let mut quote: Option<char> = None;
+    let mut start = 0;
+    let mut previous = ' ';
+    let chars: Vec<(usize, char)> = list.char_indices().collect();
+    for (position, &(index, c)) in chars.iter().enumerate() {
+        match (quote, c) {
+            (Some(q), _) if c == q && previous != '\\' => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'' | '`') if opens_quote(&chars, position, style) => quote = Some(c),
+            (None, '(' | '[' | '{' | '<') => depth += 1,
+            (None, '>') if previous == '-' || previous == '=' => {}
+            (None, ')' | ']' | '}' | '>') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                items.push(list[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+        previous = c;
+    }
+    items.push(list[start..].trim());
+    items.retain(|item| !item.is_empty());
+    items
+}
+
+/// Position of the first `target` outside brackets; `=` skips `==`, `=>`, `<=` and friends
+fn find_top_level(text: &str, target: char, style: SignatureStyle) -> Option<usize> {
+    let mut depth = 0i32;
+    let mut quote: Option<char> = None;
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    for (position, &(index, c)) in chars.iter().enumerate() {
+        let previous = position.checked_sub(1).map(|p| chars[p].1).unwrap_or(' ');
+        let next = chars.get(position + 1).map(|&(_, c)| c).unwrap_or(' ');
+        match (quote, c) {
+            (Some(q), _) if c == q && previous != '\\' => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'' | '`') if opens_quote(&chars, position, style) => quote = Some(c),
+            (None, _) if c == target && depth == 0 => {
+                let operator = target == '=' && (matches!(next, '=' | '>') || matches!(previous, '=' | '!' | '<' | '>'));
+                let path = target == ':' && (next == ':' || previous == ':');
+                if !operator && !path {
+                    return Some(index);
+                }
+            }
+            (None, '(' | '[' | '{' | '<') => depth += 1,
+            (None, '>') if previous == '-' || previous == '=' => {}
+            (None, ')' | ']' | '}' | '>') => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn split_default(item: &str, style: SignatureStyle) -> (&str, Option<&str>) {
+    match find_top_level(item, '=', style) {
+        Some(at) => (item[..at].trim(), Some(item[at + 1..].trim())),
+        None => (item.trim(), None),
+    }
+}
+
+fn parse_params(list: &str, style: SignatureStyle) -> Vec<Parameter> {
+    let items = split_params(list, style);
+    match style {
+        SignatureStyle::Go => go_params(&items),
+        _ => items.iter().filter_map(|item| parse_param(item, style)).collect(),
+    }
+}
+
+fn parse_param(item: &str, style: SignatureStyle) -> Option<Parameter> {
+    let item = strip_annotations(item);
+    match style {
+        SignatureStyle::CFamily => typed_param(item),
+        SignatureStyle::Php => php_param(item),
+        SignatureStyle::Ruby => {
+            // `key:` and `key: 1` are keyword arguments
+            let (name, default) = match item.split_once(':').filter(|(name, _)| !name.contains('=')) {
+                Some((name, default)) => (name.trim(), Some(default)),
+                None => split_default(item, style),
+            };
+            Some(Parameter::new(name, None, default))
+        }
+        // `*` and `/` only separate keyword-only and positional-only parameters
+        SignatureStyle::Python if item == "*" || item == "/" => None,
+        _ => colon_param(item, style),
+    }
+}
+
+// `mut name: Type = default`, `readonly name?: Type`, `_ label: Type`, `&mut self`
+fn colon_param(item: &str, style: SignatureStyle) -> Option<Parameter> {
+    let (declaration, default) = split_default(item, style);
+    let (name, type_name) = match find_top_level(declaration, ':', style) {
+        Some(at) => (declaration[..at].trim(), Some(declaration[at + 1..].trim())),
+        None => (declaration, None),
+    };
+    let name = if type_name.is_some() && !name.starts_with(['{', '[', '(']) {
+        // Modifiers and argument labels come before the binding's own name
+        name.rsplit(char::is_whitespace).next().unwrap_or(name)
+    } else {
+        name
+    };
+    let name = name.trim_end_matches('?');
+    (!name.is_empty()).then(|| Parameter::new(name, type_name, default))
+}
+
+// `final Map<K, V> name = default`, `const char *argv[]`, `String... args`
+fn typed_param(item: &str) -> Option<Parameter> {
+    let (declaration, default) = split_default(item, SignatureStyle::CFamily);
+    let declaration = declaration.strip_prefix("final ").unwrap_or(declaration).trim();
+    if declaration.is_empty() || declaration == "void" {
+        return None;
+    }
+    // Array brackets after the name belong to the type
+    let (declaration, array) = match declaration.find('[').filter(|_| declaration.ends_with(']')) {
+        Some(at) if at > 0 && !declaration[..at].ends_with(char::is_whitespace) => (&declaration[..at], &declaration[at..]),
+        _ => (declaration, ""),
+    };
+    let name_start = declaration.rfind(|c: char| !(c.is_alphanumeric() || c == '_')).map(|at| at + 1).unwrap_or(0);
+    let (type_name, name) = declaration.split_at(name_start);
+    if type_name.trim().is_empty() {
+        // An unnamed parameter in a prototype: `int`
+        return Some(Parameter::new("", Some(&format!("{}{}", name, array)), default));
+    }
+    Some(Parameter::new(name, Some(&format!("{}{}", type_name.trim(), array)), default))
+}
+
+// `?int $x = 5`, `string ...$rest`, `private OrderService $orders`
+fn php_param(item: &str) -> Option<Parameter> {
+    let (declaration, default) = split_default(item, SignatureStyle::Php);
+    let at = declaration.find('$')?;
+    let name = &declaration[at..];
+    let type_words: Vec<&str> = declaration[..at]
+        .split_whitespace()
+        .filter(|word| !VISIBILITY.contains(word) && *word != "readonly")
+        .collect();
+    let type_name = type_words.join(" ");
+    let (type_name, prefix) = match type_name.strip_suffix("...").or_else(|| type_name.strip_suffix('&')) {
+        Some(stripped) => (stripped.trim().to_string(), &type_name[stripped.len()..]),
+        None => (type_name.clone(), ""),
+    };
+    Some(Parameter::new(&format!("{}{}", prefix, name), Some(&type_name), default))
+}
+
+// Consecutive names share the type written after the last: `a, b int`
+fn go_params(items: &[&str]) -> Vec<Parameter> {
+    // `(int, error)` and `(string)` list types only
+    if items.iter().all(|item| !item.contains(char::is_whitespace) || item.starts_with("func")) {
+        return items.iter().map(|item| Parameter::new("", Some(item), None)).collect();
+    }
+    let mut params: Vec<Parameter> = Vec::new();
+    let mut untyped = Vec::new();
+    for item in items {
+        match item.split_once(char::is_whitespace) {
+            Some((name, type_name)) => {
+                for pending in untyped.drain(..) {
+                    params.push(Parameter::new(pending, Some(type_name), None));
+                }
+                params.push(Parameter::new(name, Some(type_name), None));
+            }
+            None => untyped.push(*item),
+        }
+    }
+    params.extend(untyped.into_iter().map(|name| Parameter::new(name, None, None)));
+    params
+}
+
+/// Drops `@Annotation(...)`, `[Attribute]` and `#[attr]` from the start of a declaration
+fn strip_annotations(item: &str) -> &str {
+    let mut rest = item.trim();
+    loop {
+        if let Some(after) = rest.strip_prefix('@') {
+            let end = after.find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.')).unwrap_or(after.len());
+            rest = after[end..].trim_start();
+            if let Some((_, after)) = split_balanced(rest, '(', ')') {
+                rest = after.trim_start();
+            }
+        } else if let Some((_, after)) = split_balanced(rest.trim_start_matches('#'), '[', ']').filter(|_| rest.starts_with(['[', '#'])) {
+            rest = after.trim_start();
+        } else {
+            return rest;
+        }
+    }
+}
+
+fn return_type(prefix: &str, rest: &str, style: SignatureStyle) -> Option<String> {
+    let until = |text: &str, stops: &[char]| -> String {
+        let end = stops.iter().filter_map(|&stop| find_top_level(text, stop, style)).min().unwrap_or(text.len());
+        let text = &text[..end];
+        let text = text.split(" where ").next().unwrap_or(text);
+        text.trim().to_string()
+    };
+    let rest = rest.trim_start();
+    let returns = match style {
+        SignatureStyle::Rust => rest.strip_prefix("->").map(|ret| until(ret, &['{', ';'])),
+        SignatureStyle::Python => rest.strip_prefix("->").map(|ret| until(ret, &[':'])),
+        // `async throws -> T`
+        SignatureStyle::Swift => rest.find("->").map(|at| until(&rest[at + 2..], &['{'])),
+        SignatureStyle::JavaScript => rest.strip_prefix(':').map(|ret| {
+            let ret = until(ret, &['{', ';']);
+            ret.strip_suffix("=>").map(|ret| ret.trim().to_string()).unwrap_or(ret)
+        }),
+        SignatureStyle::Kotlin => rest.strip_prefix(':').map(|ret| until(ret, &['{', '='])),
+        SignatureStyle::Php => rest.strip_prefix(':').map(|ret| until(ret, &['{', ';'])),
+        SignatureStyle::Go => Some(until(rest, &['{'])),
+        SignatureStyle::CFamily => {
+            // C++ trailing return types: `auto f() -> T`
+            if let Some(ret) = rest.strip_prefix("const").unwrap_or(rest).trim_start().strip_prefix("->") {
+                return Some(until(ret, &['{', ';'])).filter(|ret| !ret.is_empty());
+            }
+            Some(c_return_type(prefix))
+        }
+        SignatureStyle::Ruby => None,
+    };
+    returns.filter(|ret| !ret.is_empty())
+}
+
+// Whatever is left of `public static <T> List<T> Foo::` once keywords,
+// annotations, type parameters and the qualifier are removed
+fn c_return_type(prefix: &str) -> String {
+    let prefix = strip_annotations(prefix.trim());
+    let prefix = prefix.trim_end_matches('~');
+    // `Foo::bar` and `Foo<T>::bar` qualify the name
+    let prefix = match prefix.trim_end().strip_suffix("::") {
+        Some(qualified) => {
+            let start = qualified.rfind(char::is_whitespace).map(|at| at + 1).unwrap_or(0);
+            &qualified[..start]
+        }
+        None => prefix,
+    };
+    let mut words = Vec::new();
+    let mut rest = prefix.trim();
+    while !rest.is_empty() {
+        if rest.starts_with('<') && words.is_empty() {
+            // Java type parameters precede the return type
+            rest = split_balanced(rest, '<', '>').map(|(_, after)| after.trim_start()).unwrap_or("");
+            continue;
+        }
+        let end = word_end(rest);
+        let word = &rest[..end];
+        if !(words.is_empty() && (VISIBILITY.contains(&word) || DECLARATION_KEYWORDS.contains(&word))) {
+            words.push(word);
+        }
+        rest = rest[end..].trim_start();
+    }
+    words.join(" ")
+}
+
+// A word, keeping `List<Map<K, V>>` and `int[]` together
+fn word_end(text: &str) -> usize {
+    let mut depth = 0i32;
+    for (index, c) in text.char_indices() {
+        match c {
+            '<' | '[' | '(' => depth += 1,
+            '>' | ']' | ')' => depth -= 1,
+            c if c.is_whitespace() && depth <= 0 => return index,
+            _ => {}
+        }
+    }
+    text.len()
+}
+
+fn visibility(prefix: &str, entity: &CodeEntity) -> Option<String> {
+    if let Some(visibility) = entity.metadata.get("visibility") {
+        return Some(visibility.clone());
+    }
+    prefix.split_whitespace().find(|word| VISIBILITY.contains(word)).map(str::to_string)
+}
+
+fn modifiers(prefix: &str, entity: &CodeEntity, style: SignatureStyle) -> Vec<String> {
+    let mut modifiers: Vec<String> = Vec::new();
+    let flagged = MODIFIERS.iter().filter(|m| entity.metadata.get(**m).map(String::as_str) == Some("true"));
+    // `export const f = () => {}` binds a function rather than modifying it
+    let keyword = |word: &&str| MODIFIERS.contains(word) && !(style == SignatureStyle::JavaScript && *word == "const");
+    for modifier in prefix.split_whitespace().filter(keyword).chain(flagged.copied()) {
+        if !modifiers.iter().any(|m| m == modifier) {
+            modifiers.push(modifier.to_string());
+        }
+    }
+    modifiers
+}
+
+/// Lines strictly inside the entity's body, with their brace depth relative to it
+fn body_lines<'a>(lines: &[&'a str], entity: &CodeEntity) -> Vec<(i32, &'a str)> {
+    let start = entity.line_start.saturating_sub(1) as usize;
+    let end = (entity.line_end as usize).min(lines.len());
+    let mut depth = 0i32;
+    let mut body = Vec::new();
+    for line in lines.get(start..end).unwrap_or_default() {
+        let before = depth;
+        depth += line.matches('{').count() as i32 - line.matches('}').count() as i32;
+        if before >= 1 {
+            body.push((before, *line));
+        }
+    }
+    body
+}
+
+// `pub name: Type,` at the top of a struct body, or `struct P(pub i32, f64);`
+fn rust_fields(lines: &[&str], entity: &CodeEntity) -> Vec<Parameter> {
+    let header = lines.get(entity.line_start.saturating_sub(1) as usize).copied().unwrap_or_default();
+    if let Some(at) = find_word(header, &entity.name) {
+        let mut after = header[at + entity.name.len()..].trim_start();
+        if let Some((_, rest)) = split_balanced(after, '<', '>') {
+            after = rest;
+        }
+        if let Some((tuple, _)) = split_balanced(after, '(', ')') {
+            return split_params(tuple, SignatureStyle::Rust)
+                .iter()
+                .enumerate()
+                .map(|(index, item)| Parameter::new(&index.to_string(), Some(strip_rust_visibility(item)), None))
+                .collect();
+        }
+    }
+
+    body_lines(lines, entity)
+        .into_iter()
+        .filter(|(depth, _)| *depth == 1)
+        .filter_map(|(_, line)| {
+            let line = strip_comment(line, SignatureStyle::Rust).trim().trim_end_matches(',');
+            if line.starts_with('#') || line.starts_with('}') {
+                return None;
+            }
+            let (name, type_name) = line.split_once(':')?;
+            let name = strip_rust_visibility(name);
+            name.chars().all(|c| c.is_alphanumeric() || c == '_').then(|| Parameter::new(name, Some(type_name), None))
+        })
+        .collect()
+}
+
+fn strip_rust_visibility(text: &str) -> &str {
+    let text = text.trim();
+    match text.strip_prefix("pub") {
+        Some(rest) if rest.starts_with('(') => split_balanced(rest, '(', ')').map(|(_, after)| after.trim()).unwrap_or(rest),
+        Some(rest) if rest.starts_with(char::is_whitespace) => rest.trim(),
+        _ => text,
+    }
+}
+
+// `Name, Alias Type `json:"x"``, or an embedded `io.Reader`
+fn go_fields(lines: &[&str], entity: &CodeEntity) -> Vec<Parameter> {
+    let mut fields = Vec::new();
+    for (depth, line) in body_lines(lines, entity) {
+        let line = strip_comment(line, SignatureStyle::Go);
+        let line = line.split('`').next().unwrap_or_default().trim();
+        if depth != 1 || line.is_empty() || line.starts_with('}') {
+            continue;
+        }
+        let mut names = Vec::new();
+        let mut rest = line;
+        loop {
+            let end = rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+            names.push(&rest[..end]);
+            rest = rest[end..].trim_start();
+            match rest.strip_prefix(',') {
+                Some(after) => rest = after.trim_start(),
+                None => break,
+            }
+        }
+        if rest.is_empty() || rest.starts_with('.') {
+            let embedded = line.trim_start_matches('*');
+            fields.push(Parameter::new(embedded.rsplit('.').next().unwrap_or(embedded), Some(line), None));
+        } else {
+            fields.extend(names.into_iter().map(|name| Parameter::new(name, Some(rest), None)));
+        }
+    }
+    fields
+}
+
+// Annotated class attributes: `name: str = ""`, as dataclasses and pydantic use
+fn python_fields(lines: &[&str], entity: &CodeEntity) -> Vec<Parameter> {
+    let start = entity.line_start as usize;
+    let end = (entity.line_end as usize).min(lines.len());
+    let body = lines.get(start..end).unwrap_or_default();
+    let indent_of = |line: &str| line.len() - line.trim_start().len();
+    let Some(indent) = body.iter().find(|line| !line.trim().is_empty()).map(|line| indent_of(line)) else { return Vec::new() };
+
+    // Docstrings and other string lines are not attributes: `Args:` is no field
+    let mut open_string: Option<&str> = None;
+    let code: Vec<&str> = body
+        .iter()
+        .copied()
+        .filter(|line| {
+            let text = line.trim();
+            if let Some(quote) = open_string {
+                if text.contains(quote) {
+                    open_string = None;
+                }
+                return false;
+            }
+            if let Some(quote) = ["\"\"\"", "'''"].into_iter().find(|quote| text.trim_start_matches(['r', 'b', 'u', 'f']).starts_with(quote)) {
+                let after = &text[text.find(quote).unwrap_or(0) + 3..];
+                if !after.contains(quote) {
+                    open_string = Some(quote);
+                }
+                return false;
+            }
+            !text.starts_with(['"', '\''])
+        })
+        .collect();
+
+    code.iter()
+        .filter(|line| !line.trim().is_empty() && indent_of(line) == indent)
+        .filter_map(|line| {
+            let line = strip_comment(line, SignatureStyle::Python).trim();
+            let (name, _) = line.split_once(':')?;
+            if !name.chars().all(|c| c.is_alphanumeric() || c == '_') || name.is_empty() {
+                return None;
+            }
+            colon_param(line, SignatureStyle::Python)
+        })
+        .collect()
+}
+
+// Kotlin `class User(val id: Int, var name: String = "")`
+fn constructor_properties(lines: &[&str], entity: &CodeEntity) -> Vec<Parameter> {
+    let text = declaration_text(lines, entity, SignatureStyle::Kotlin);
+    let Some(at) = find_word(&text, &entity.name) else { return Vec::new() };
+    let mut after = text[at + entity.name.len()..].trim_start();
+    if let Some((_, rest)) = split_balanced(after, '<', '>') {
+        after = rest.trim_start();
+    }
+    // `class Foo private constructor(...)`
+    let after = after.split_once("constructor").map(|(_, rest)| rest.trim_start()).unwrap_or(after);
+    let Some((params, _)) = split_balanced(after, '(', ')') else { return Vec::new() };
+    split_params(params, SignatureStyle::Kotlin)
+        .into_iter()
+        .map(strip_annotations)
+        .filter(|item| item.split_whitespace().any(|word| word == "val" || word == "var"))
+        .filter_map(|item| colon_param(item, SignatureStyle::Kotlin))
+        .collect()
+}
+
+/// Fields and properties the parser recorded as members of the type
+fn member_fields(lines: &[&str], entities: &[CodeEntity], entity: &CodeEntity, style: SignatureStyle) -> Vec<Parameter> {
+    entities
+        .iter()
+        .filter(|member| {
+            member.file_path == entity.file_path
+                && member.metadata.get("enclosing") == Some(&entity.name)
+                && member.line_start > entity.line_start
+                && member.line_start <= entity.line_end
+                && matches!(member.entity_type, EntityType::Variable | EntityType::Constant)
+                && matches!(member.metadata.get("kind").map(String::as_str), Some("field" | "property" | "attribute"))
+                && member.metadata.get("static").map(String::as_str) != Some("true")
+        })
+        .filter_map(|member| {
+            let line = lines.get(member.line_start.saturating_sub(1) as usize)?;
+            member_field(line, &member.name, style)
+        })
+        .collect()
+}
+
+fn member_field(line: &str, name: &str, style: SignatureStyle) -> Option<Parameter> {
+    let line = strip_comment(line, style).trim().trim_end_matches([';', ',']);
+    if style == SignatureStyle::Ruby {
+        return Some(Parameter::new(name, None, None));
+    }
+    let at = find_word(line, name)?;
+    let after = line[at + name.len()..].trim_start_matches(['?', '!']).trim_start();
+    let (declaration, default) = split_default(after, style);
+    // C# properties: `int Id { get; set; }`
+    let declaration = declaration.split('{').next().unwrap_or_default().trim();
+
+    let type_name = match style {
+        SignatureStyle::CFamily => Some(c_return_type(&line[..at])),
+        SignatureStyle::Php => {
+            let prefix = line[..at].trim_end().trim_end_matches('$');
+            let words: Vec<&str> = prefix
+                .split_whitespace()
+                .filter(|word| !VISIBILITY.contains(word) && !matches!(*word, "static" | "readonly" | "var"))
+                .collect();
+            Some(words.join(" "))
+        }
+        _ => declaration.strip_prefix(':').map(|type_name| type_name.trim().to_string()),
+    };
+    let name = if style == SignatureStyle::Php { format!("${}", name) } else { name.to_string() };
+    Some(Parameter::new(&name, type_name.as_deref(), default))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CodeParser, Parameter};
+
+    #[test]
+    fn test_function_signatures_across_languages() -> anyhow::Result<()> {
+        let parser = CodeParser::new()?;
+        let signature = |path: &str, source: &str, name: &str| {
+            let (entities, _) = parser.parse_source(path, source).unwrap();
+            let entity = entities.into_iter().find(|e| e.name == name).unwrap_or_else(|| panic!("missing {}", name));
+            entity.signature.unwrap_or_else(|| panic!("no signature for {}", name)).render(name)
+        };
+
+        let rust = "impl Store {\n    pub async fn load<T: Clone>(\n        &mut self,\n        items: &[T], // the items\n        f: impl Fn(u8) -> u8,\n    ) -> Result<Vec<T>> {\n        todo!()\n    }\n}\n";
+        assert_eq!(
+            signature("src/store.rs", rust, "load"),
+            "pub async load<T: Clone>(&mut self, items: &[T], f: impl Fn(u8) -> u8) -> Result<Vec<T>>"
+        );
+
+        let python = "class Service:\n    @staticmethod\n    async def fetch(url: str, *, retries: int = 3, **kwargs) -> dict[str, int]:\n        pass\n";
+        assert_eq!(signature("svc.py", python, "fetch"), "async static fetch(url: str, retries: int = 3, **kwargs) -> dict[str, int]");
+
+        let typescript = "export const format = async (date: Date, opts?: Options): Promise<string> => {\n};\n";
+        assert_eq!(signature("fmt.ts", typescript, "format"), "async format(date: Date, opts: Options) -> Promise<string>");
+
+        let go = "func (s *Server) Handle(w http.ResponseWriter, a, b int) (int, error) {\n}\n";
+        assert_eq!(signature("server.go", go, "Handle"), "Handle(w: http.ResponseWriter, a: int, b: int) -> (int, error)");
+
+        let java = "class Repo {\n    public static <T> List<T> find(final String name, int... ids) throws IOException {\n    }\n}\n";
+        assert_eq!(signature("Repo.java", java, "find"), "public static find<T>(name: String, ids: int...) -> List<T>");
+
+        let php = "<?php\nclass A {\n    public function log(?string $message = null, int ...$codes): void {}\n}\n";
+        assert_eq!(signature("a.php", php, "log"), "public log($message: ?string = null, ...$codes: int) -> void");
+
+        let ruby = "class A\n  def call(a, b = 1, *rest, key:, &blk)\n  end\nend\n";
+        assert_eq!(signature("a.rb", ruby, "call"), "call(a, b = 1, *rest, key, &blk)");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_signatures_with_lifetimes_and_generic_return_types() -> anyhow::Result<()> {
+        let parser = CodeParser::new()?;
+        let signature = |path: &str, source: &str, name: &str| {
+            let (entities, _) = parser.parse_source(path, source).unwrap();
+            let entity = entities.into_iter().find(|e| e.name == name).unwrap_or_else(|| panic!("missing {}", name));
+            entity.signature.unwrap_or_else(|| panic!("no signature for {}", name)).render(name)
+        };
+
+        let rust = "pub(crate) fn go_now(x: &'a str, y: u8) -> &'a str {\n    x\n}\n\nfn split<'a>(a: &'a str, b: &'a str, sep: char) -> Parser<'a> {\n    let quote = '\\'';\n    todo!()\n}\n";
+        assert_eq!(signature("src/lib.rs", rust, "go_now"), "pub(crate) go_now(x: &'a str, y: u8) -> &'a str");
+        assert_eq!(signature("src/lib.rs", rust, "split"), "split<'a>(a: &'a str, b: &'a str, sep: char) -> Parser<'a>");
+
+        // A generic return type is not the method's type parameters
+        let csharp = "class Orders {\n    public async Task<List<Order>> LoadAsync(int page) {\n    }\n}\n";
+        assert_eq!(signature("Orders.cs", csharp, "LoadAsync"), "public async LoadAsync(page: int) -> Task<List<Order>>");
+        let cpp = "std::vector<std::string> names(int limit) {\n}\n";
+        assert_eq!(signature("names.cpp", cpp, "names"), "names(limit: int) -> std::vector<std::string>");
+        let kotlin = "fun <T> List<T>.second(): T {\n    return this[1]\n}\n";
+        assert_eq!(signature("Lists.kt", kotlin, "second"), "second<T>() -> T");
+
+        let c = "#define MAX(a, b) ((a) > (b) ? (a) : (b))\n";
+        assert_eq!(signature("max.h", c, "MAX"), "MAX(a, b)");
+
+        let ruby = "class A\n  def helper; end\n\n  def run(path, strict: true, retries:)\n  end\nend\n";
+        assert_eq!(signature("a.rb", ruby, "helper"), "helper()");
+        assert_eq!(signature("a.rb", ruby, "run"), "run(path, strict = true, retries)");
+
+        let python = "class Service:\n    \"\"\"Runs jobs.\n\n    Args:\n        name: the job\n    \"\"\"\n    name: str\n";
+        let (entities, _) = parser.parse_source("svc.py", python)?;
+        let service = entities.iter().find(|e| e.name == "Service").unwrap();
+        assert_eq!(service.signature.as_ref().unwrap().render("Service"), "Service { name: str }");
+        Ok(())
+    }
+
+    #[test]
+    fn test_type_fields() -> anyhow::Result<()> {
+        let parser = CodeParser::new()?;
+        let fields = |path: &str, source: &str, name: &str| -> Vec<Parameter> {
+            let (entities, _) = parser.parse_source(path, source).unwrap();
+            let entity = entities.into_iter().find(|e| e.name == name).unwrap_or_else(|| panic!("missing {}", name));
+            entity.signature.map(|s| s.fields).unwrap_or_default()
+        };
+        let rendered = |fields: Vec<Parameter>| fields.iter().map(Parameter::render).collect::<Vec<_>>().join(", ");
+
+        let rust = "pub struct User<'a> {\n    /// Id\n    pub id: u64,\n    pub(crate) name: &'a str,\n    tags: HashMap<String, Vec<u8>>,\n}\npub struct Point(pub f64, f64);\n";
+        assert_eq!(rendered(fields("user.rs", rust, "User")), "id: u64, name: &'a str, tags: HashMap<String, Vec<u8>>");
+        assert_eq!(rendered(fields("user.rs", rust, "Point")), "0: f64, 1: f64");
+
+        let python = "@dataclass\nclass User:\n    id: int\n    name: str = \"\"  # display name\n\n    def greet(self):\n        label: str = 'x'\n";
+        assert_eq!(rendered(fields("user.py", python, "User")), "id: int, name: str = \"\"");
+
+        let go = "type User struct {\n\tID, Parent int `json:\"id\"`\n\tName string\n\tio.Reader\n}\n";
+        assert_eq!(rendered(fields("user.go", go, "User")), "ID: int, Parent: int, Name: string, Reader: io.Reader");
+
+        let typescript = "export class User {\n    readonly id: number;\n    name?: string = 'x';\n    static count = 0;\n}\n";
+        assert_eq!(rendered(fields("user.ts", typescript, "User")), "id: number, name: string = 'x'");
+
+        let java = "class User {\n    private final long id;\n    private List<String> tags = new ArrayList<>();\n}\n";
+        assert_eq!(rendered(fields("User.java", java, "User")), "id: long, tags: List<String> = new ArrayList<>()");
+
+        let kotlin = "data class User(val id: Int, var name: String = \"\", other: Int) {\n    val label: String = name\n}\n";
+        assert_eq!(rendered(fields("User.kt", kotlin, "User")), "id: Int, name: String = \"\", label: String = name");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,415 @@
+use super::java::split_annotations;
+use super::parsed::ParsedFile;
+use super::signature::{record_signatures, SignatureStyle};
+use super::scope::{ScopeKind, ScopeTracker};
+use super::{line_entity, split_balanced, split_top_level, strip_generics};
+use crate::{CodeEntity, EntityType, RelationType, Relationship};
+use anyhow::Result;
+use regex::Regex;
+
+pub(crate) struct SwiftParser {
+    import_regex: Regex,
+    type_regex: Regex,
+    extension_regex: Regex,
+    function_regex: Regex,
+    initializer_regex: Regex,
+    property_regex: Regex,
+    case_regex: Regex,
+    typealias_regex: Regex,
+}
+
+impl SwiftParser {
+    pub(crate) fn new() -> Result<Self> {
+        Ok(Self {
+            import_regex: Regex::new(r"^\s*import\s+(?:(?:typealias|struct|class|enum|protocol|let|var|func)\s+)?([\w.]+)")?,
+            type_regex: Regex::new(
+                r"^\s*((?:(?:public|private|fileprivate|internal|open|final|indirect|package)\s+)*)(class|struct|enum|protocol|actor)\s+(\w+)",
+            )?,
+            extension_regex: Regex::new(r"^\s*(?:(?:public|private|fileprivate|internal|package)\s+)?extension\s+([\w.]+)")?,
+            function_regex: Regex::new(
+                r"^\s*((?:(?:public|private|fileprivate|internal|open|final|static|class|override|mutating|nonmutating|convenience|required|dynamic|nonisolated|package)\s+)*)func\s+(\w+|[^\s(<]+)",
+            )?,
+            initializer_regex: Regex::new(
+                r"^\s*((?:(?:public|private|fileprivate|internal|open|convenience|required|override|package)\s+)*)(init[?!]?|deinit)\b",
+            )?,
+            property_regex: Regex::new(
+                r"^\s*((?:(?:public|private|fileprivate|internal|open|final|static|class|override|lazy|weak|unowned|nonisolated|package)(?:\(set\))?\s+)*)(let|var)\s+(\w+)",
+            )?,
+            case_regex: Regex::new(r"^\s*(?:indirect\s+)?case\s+(.+)")?,
+            typealias_regex: Regex::new(r"^\s*(?:(?:public|private|fileprivate|internal|package)\s+)?typealias\s+(\w+)")?,
+        })
+    }
+
+    pub(crate) fn parse(&self, content: &str, file_path: &str) -> Result<(Vec<CodeEntity>, Vec<Relationship>)> {
+        let mut parsed = ParsedFile::default();
+        let mut tracker = ScopeTracker::new();
+        let mut attributes: Vec<String> = Vec::new();
+
+        for (line_num, line) in content.lines().enumerate() {
+            let trimmed = line.trim_start();
+            let enclosing = tracker
+                .current_body()
+                .filter(|scope| scope.kind == ScopeKind::Type)
+                .and_then(|scope| Some((scope.entity?, scope.name.clone())));
+            let is_comment = tracker.in_block_comment() || trimmed.starts_with("//") || trimmed.starts_with("/*") || trimmed.starts_with('*');
+
+            // Only type bodies and the top level hold declarations; function bodies are skipped
+            if !is_comment && (tracker.depth() == 0 || enclosing.is_some()) {
+                let (found, declaration) = split_annotations(line);
+                attributes.extend(found);
+
+                if !declaration.trim().is_empty() {
+                    let parent = enclosing.as_ref().map(|(parent, _)| *parent);
+                    let entity = if let Some(captures) = self.import_regex.captures(declaration).filter(|_| parent.is_none()) {
+                        parsed.push(line_entity(&captures[1], EntityType::Import, file_path, line_num, line));
+                        None
+                    } else if let Some(captures) = self.type_regex.captures(declaration).filter(|c| !is_class_member(&c[2], &c[3])) {
+                        Some(self.parse_type(&mut parsed, &mut tracker, &captures, declaration, parent, file_path, line_num, line))
+                    } else if let Some(captures) = self.extension_regex.captures(declaration) {
+                        let extended = &captures[1];
+                        let index = parsed.push(
+                            line_entity(extended.rsplit('.').next().unwrap_or(extended), EntityType::Class, file_path, line_num, line)
+                                .with_metadata("kind".to_string(), "extension".to_string()),
+                        );
+                        parsed.link_symbol(index, extended, RelationType::Extends);
+                        let rest = &declaration[captures.get(0).map(|m| m.end()).unwrap_or(0)..];
+                        // Conformances added by an extension are always protocols
+                        for protocol in inheritance_list(rest) {
+                            parsed.link_symbol(index, protocol, RelationType::Implements);
+                        }
+                        tracker.expect_body(&parsed.entities[index].name.clone(), index, ScopeKind::Type);
+                        Some(index)
+                    } else if let Some((parent, _)) = &enclosing {
+                        self.parse_member(&mut parsed, &mut tracker, declaration, Some(*parent), file_path, line_num, line)
+                    } else {
+                        self.parse_member(&mut parsed, &mut tracker, declaration, None, file_path, line_num, line)
+                    };
+
+                    if let Some(index) = entity {
+                        if !attributes.is_empty() {
+                            parsed.set_metadata(index, "attributes", attributes.join(","));
+                        }
+                    }
+                    attributes.clear();
+                }
+            }
+
+            for closed in tracker.process_line(line) {
+                parsed.set_line_end(closed, line_num);
+            }
+        }
+
+        record_signatures(&mut parsed.entities, content, SignatureStyle::Swift);
+        Ok(parsed.finish())
+    }
+
+    /// Classes, structs, enums, protocols and actors
+    #[allow(clippy::too_many_arguments)]
+    fn parse_type(
+        &self,
+        parsed: &mut ParsedFile,
+        tracker: &mut ScopeTracker,
+        captures: &regex::Captures,
+        declaration: &str,
+        parent: Option<usize>,
+        file_path: &str,
+        line_num: usize,
+        line: &str,
+    ) -> usize {
+        let keyword = &captures[2];
+        let name = &captures[3];
+        let entity_type = if keyword == "protocol" { EntityType::Interface } else { EntityType::Class };
+
+        let mut entity = line_entity(name, entity_type, file_path, line_num, line)
+            .with_metadata("kind".to_string(), keyword.to_string());
+        if captures[1].split_whitespace().any(|m| m == "final") {
+            entity = entity.with_metadata("final".to_string(), "true".to_string());
+        }
+        let index = parsed.push(entity);
+        if let Some(parent) = parent {
+            parsed.contain(parent, index);
+        }
+        tracker.expect_body(name, index, ScopeKind::Type);
+
+        let rest = &declaration[captures.get(0).map(|m| m.end()).unwrap_or(0)..];
+        for (position, base) in inheritance_list(rest).into_iter().enumerate() {
+            match keyword {
+                "protocol" => {
+                    parsed.link_symbol(index, base, RelationType::Extends);
+                }
+                // Only a class's first inherited type can be a superclass; the syntax
+                // doesn't tell it from a protocol, so resolution settles it by target
+                "class" if position == 0 => {
+                    let guess = if looks_like_protocol(base) { RelationType::Implements } else { RelationType::Extends };
+                    parsed
+                        .link_symbol(index, base, guess)
+                        .metadata
+                        .insert("inherits".to_string(), "either".to_string());
+                }
+                // `enum Suit: String` names the raw value type, not a protocol
+                "enum" if position == 0 && is_raw_value_type(base) => {
+                    parsed.set_metadata(index, "raw_type", base.to_string());
+                }
+                _ => {
+                    parsed.link_symbol(index, base, RelationType::Implements);
+                }
+            }
+        }
+
+        index
+    }
+
+    /// Functions, initializers, properties, enum cases and type aliases
+    #[allow(clippy::too_many_arguments)]
+    fn parse_member(
+        &self,
+        parsed: &mut ParsedFile,
+        tracker: &mut ScopeTracker,
+        declaration: &str,
+        parent: Option<usize>,
+        file_path: &str,
+        line_num: usize,
+        line: &str,
+    ) -> Option<usize> {
+        let in_protocol = parent.map(|p| parsed.entities[p].entity_type == EntityType::Interface).unwrap_or(false);
+
+        let (name, entity_type, kind, modifiers) = if let Some(captures) = self.function_regex.captures(declaration) {
+            let kind = if parent.is_some() { "method" } else { "function" };
+            (captures[2].to_string(), EntityType::Function, kind, captures[1].to_string())
+        } else if let Some(captures) = self.initializer_regex.captures(declaration).filter(|_| parent.is_some()) {
+            let kind = if &captures[2] == "deinit" { "deinitializer" } else { "initializer" };
+            (captures[2].to_string(), EntityType::Function, kind, captures[1].to_string())
+        } else if let Some(captures) = self.property_regex.captures(declaration) {
+            let modifiers = captures[1].to_string();
+            let is_static = modifiers.split_whitespace().any(|m| m == "static" || m == "class");
+            // Type-level and global `let`s are constants; instance `let`s are immutable properties
+            let entity_type = if &captures[2] == "let" && (is_static || parent.is_none()) { EntityType::Constant } else { EntityType::Variable };
+            let kind = if parent.is_some() { "property" } else { "variable" };
+            (captures[3].to_string(), entity_type, kind, modifiers)
+        } else if let Some(captures) = self.case_regex.captures(declaration).filter(|_| parent.is_some()) {
+            let parent = parent?;
+            let mut last = None;
+            for case in split_top_level(&captures[1]) {
+                let name = case.split(['(', '=', ' ']).next().unwrap_or(case);
+                let index = parsed.push(
+                    line_entity(name, EntityType::Constant, file_path, line_num, line)
+                        .with_metadata("kind".to_string(), "case".to_string()),
+                );
+                parsed.contain(parent, index);
+                last = Some(index);
+            }
+            return last;
+        } else if let Some(captures) = self.typealias_regex.captures(declaration) {
+            (captures[1].to_string(), EntityType::Type, "alias", String::new())
+        } else {
+            return None;
+        };
+
+        let mut entity = line_entity(&name, entity_type.clone(), file_path, line_num, line)
+            .with_metadata("kind".to_string(), kind.to_string());
+        for modifier in ["static", "class", "override", "mutating"] {
+            if modifiers.split_whitespace().any(|m| m == modifier) {
+                // `class func` is an overridable type method
+                let key = if modifier == "class" { "static" } else { modifier };
+                entity = entity.with_metadata(key.to_string(), "true".to_string());
+            }
+        }
+        if entity_type == EntityType::Function {
+            let after_parameters = declaration
+                .find('(')
+                .and_then(|start| split_balanced(&declaration[start..], '(', ')'))
+                .map(|(_, rest)| rest)
+                .unwrap_or("");
+            if after_parameters.split_whitespace().any(|word| word == "async") {
+                entity = entity.with_metadata("async".to_string(), "true".to_string());
+            }
+        }
+        let index = parsed.push(entity);
+        if let Some(parent) = parent {
+            parsed.contain(parent, index);
+        }
+
+        // Protocol requirements have no bodies; stored properties open none either
+        if !in_protocol && (entity_type == EntityType::Function || declaration.contains('{')) {
+            tracker.expect_body(&name, index, ScopeKind::Function);
+        }
+        Some(index)
+    }
+}
+
+/// Types after the `:` of a declaration header, without generics or `where` clauses
+fn inheritance_list(rest: &str) -> Vec<&str> {
+    let mut rest = rest.trim_start();
+    if let Some((_, after_generics)) = split_balanced(rest, '<', '>') {
+        rest = after_generics.trim_start();
+    }
+    let Some(list) = rest.strip_prefix(':') else {
+        return Vec::new();
+    };
+    let list = list.split('{').next().unwrap_or(list);
+    let list = list.split(" where ").next().unwrap_or(list);
+    split_top_level(list).into_iter().map(strip_generics).collect()
+}
+
+// Swift API naming guidelines: capabilities end in `able`/`ible`/`ing`, roles in `Protocol`, `Delegate`, `DataSource`
+fn looks_like_protocol(name: &str) -> bool {
+    ["able", "ible", "ing", "Protocol", "Delegate", "DataSource", "Type"]
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+}
+
+// `class func` and `class var` declare overridable type members, not classes
+fn is_class_member(keyword: &str, name: &str) -> bool {
+    keyword == "class" && matches!(name, "func" | "var" | "let" | "subscript")
+}
+
+fn is_raw_value_type(name: &str) -> bool {
+    matches!(
+        name,
+        "String" | "Character" | "Int" | "Int8" | "Int16" | "Int32" | "Int64" | "UInt" | "UInt8" | "UInt16" | "UInt32" | "UInt64" | "Double" | "Float"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_support::{resolve_files, ParsedSource};
+
+    const VIEW_MODEL: &str = r##"import Foundation
+@testable import OrdersKit
+
+protocol OrderLoading: AnyObject {
+    func load(id: UUID) async throws -> Order
+    var isLoading: Bool { get }
+}
+
+@MainActor
+final class OrderViewModel: BaseViewModel, ObservableObject, OrderLoading {
+    static let pageSize = 20
+    @Published private(set) var orders: [Order] = []
+    let title = """
+        Orders {
+        """
+
+    init(service: OrderService) {
+        self.service = service
+    }
+
+    func load(id: UUID) async throws -> Order {
+        let order = try await service.fetch(id)
+        return order
+    }
+
+    class func make() -> OrderViewModel { OrderViewModel(service: .live) }
+}
+
+enum Status: String, Codable {
+    case open, closed
+    case refunded(amount: Decimal)
+}
+
+extension Order: Identifiable where ID == UUID {
+    var displayName: String {
+        "#\(id)"
+    }
+}
+
+typealias Completion = (Result<Order, Error>) -> Void
+"##;
+
+    #[test]
+    fn test_swift_entities() -> Result<()> {
+        let file = ParsedSource::new(&SwiftParser::new()?, "Sources/Orders/OrderViewModel.swift", VIEW_MODEL)?;
+
+        assert_eq!(file.find("OrdersKit").entity_type, EntityType::Import);
+        assert_eq!(file.find("OrderLoading").entity_type, EntityType::Interface);
+        assert_eq!(file.meta("isLoading", "enclosing"), Some("OrderLoading"));
+        assert_eq!(file.edges("OrderLoading"), vec![("extends", "AnyObject")]);
+
+        assert_eq!(file.meta("OrderViewModel", "attributes"), Some("MainActor"));
+        assert_eq!(file.meta("OrderViewModel", "final"), Some("true"));
+        assert_eq!((file.find("OrderViewModel").line_start, file.find("OrderViewModel").line_end), (10, 27));
+        assert_eq!(
+            file.edges("OrderViewModel"),
+            vec![("extends", "BaseViewModel"), ("implements", "ObservableObject"), ("implements", "OrderLoading")]
+        );
+        assert_eq!(file.find("pageSize").entity_type, EntityType::Constant);
+        assert_eq!(file.meta("orders", "attributes"), Some("Published"));
+        assert_eq!(file.meta("title", "kind"), Some("property"));
+        assert_eq!(file.meta("init", "kind"), Some("initializer"));
+        let load = file.entities.iter().find(|e| e.name == "load" && e.metadata.get("enclosing").map(String::as_str) == Some("OrderViewModel")).unwrap();
+        assert_eq!(load.metadata.get("async").map(String::as_str), Some("true"));
+        assert_eq!((load.line_start, load.line_end), (21, 24));
+        assert!(!file.has("order"));
+        assert_eq!(file.meta("make", "static"), Some("true"));
+
+        assert_eq!(file.meta("Status", "raw_type"), Some("String"));
+        assert_eq!(file.edges("Status"), vec![("implements", "Codable")]);
+        assert_eq!(file.meta("refunded", "kind"), Some("case"));
+        assert_eq!(file.members("Status"), vec!["closed", "open", "refunded"]);
+        assert_eq!(file.meta("displayName", "enclosing"), Some("Order"));
+        assert_eq!(file.find("Completion").entity_type, EntityType::Type);
+
+        // `extension Order` is named after the type it extends
+        assert_eq!(file.meta("Order", "kind"), Some("extension"));
+        assert_eq!(file.edges("Order"), vec![("extends", "Order"), ("implements", "Identifiable")]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_swift_generics_actors_and_nested_types() -> Result<()> {
+        let source = r#"struct Stack<Element>: Sequence where Element: Equatable {
+    private var items: [Element] = []
+
+    mutating func push(_ item: Element) {
+        let note = "\(item) {"
+        items.append(item)
+    }
+}
+
+actor Counter {
+    var value = 0
+    func increment() { value += 1 }
+}
+
+enum Tree {
+    indirect case node(Tree, Tree)
+    case leaf
+
+    struct Visitor {}
+}
+"#;
+        let file = ParsedSource::new(&SwiftParser::new()?, "Sources/Stack.swift", source)?;
+        // A struct's inherited types are all protocols; `where` clauses add none
+        assert_eq!(file.edges("Stack"), vec![("implements", "Sequence")]);
+        assert_eq!(file.meta("push", "mutating"), Some("true"));
+        assert_eq!(file.find("push").line_end, 7);
+        assert_eq!(file.find("Stack").line_end, 8);
+        assert_eq!(file.meta("Counter", "kind"), Some("actor"));
+        assert_eq!(file.members("Counter"), vec!["increment", "value"]);
+        assert_eq!(file.members("Tree"), vec!["Visitor", "leaf", "node"]);
+        assert_eq!(file.meta("Visitor", "kind"), Some("struct"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_first_inherited_type_resolves_to_protocol() -> Result<()> {
+        let source = "protocol Repository {}\nclass OrderStore: Repository {}\nextension OrderStore: CustomStringConvertible {}\n";
+        let memory = resolve_files(&SwiftParser::new()?, &[("Sources/Store.swift", source)])?;
+
+        let store = memory.entities.values().find(|e| e.name == "OrderStore" && e.metadata.get("kind").map(String::as_str) == Some("class")).unwrap();
+        let repository = memory.find_entities_by_name("Repository")[0];
+        let conformance = memory.relationships.iter().find(|r| r.to_entity == repository.id).unwrap();
+        assert_eq!(conformance.from_entity, store.id);
+        assert_eq!(conformance.relationship_type, RelationType::Implements);
+
+        // The extension links to the type it extends, never to another extension
+        let extension = memory.entities.values().find(|e| e.metadata.get("kind").map(String::as_str) == Some("extension")).unwrap();
+        assert!(memory.relationships.iter().any(|r| r.from_entity == extension.id && r.to_entity == store.id));
+        assert_eq!(memory.relationships.iter().filter(|r| r.is_resolved() && r.target_name().is_some()).count(), 2);
+
+        Ok(())
+    }
+}
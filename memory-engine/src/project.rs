@@ -0,0 +1,150 @@
+use crate::{ProjectConfig, MEMORY_DIR, CONFIG_FILE};
+use anyhow::{Result, anyhow};
+use std::path::{Component, Path, PathBuf};
+
+/// Environment variable that points commands at a project root
+pub const PROJECT_ENV_VAR: &str = "AIMEMORYENGINE_PROJECT";
+
+/// An initialized project: its root directory and loaded configuration
+#[derive(Debug, Clone)]
+pub struct Project {
+    pub root: PathBuf,
+    pub config: ProjectConfig,
+}
+
+impl Project {
+    /// Open the project rooted at `root`, loading its config
+    pub fn open(root: &Path) -> Result<Self> {
+        let root = root
+            .canonicalize()
+            .map_err(|e| anyhow!("Cannot open project at {}: {}", root.display(), e))?;
+        let config = ProjectConfig::load(&root)?;
+        Ok(Self { root, config })
+    }
+
+    /// Locate the project for a command run from `cwd`.
+    ///
+    /// An explicit root (the `--project` flag) wins, then the
+    /// `AIMEMORYENGINE_PROJECT` environment variable, then the nearest
+    /// ancestor of `cwd` that has been initialized. Returns `None` when no
+    /// initialized project is found.
+    pub fn discover(cwd: &Path, explicit_root: Option<&Path>) -> Result<Option<Self>> {
+        let override_root = explicit_root
+            .map(|p| p.to_path_buf())
+            .or_else(|| std::env::var_os(PROJECT_ENV_VAR).map(PathBuf::from));
+
+        if let Some(root) = override_root {
+            let root = cwd.join(root);
+            return if is_project_root(&root) { Ok(Some(Self::open(&root)?)) } else { Ok(None) };
+        }
+
+        match find_project_root(cwd) {
+            Some(root) => Ok(Some(Self::open(&root)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Absolute path of the memory database
+    pub fn database_path(&self) -> PathBuf {
+        self.config.database_path(&self.root)
+    }
+
+    pub fn memory_dir(&self) -> PathBuf {
+        self.root.join(MEMORY_DIR)
+    }
+
+    /// Express `path` (absolute, or relative to `cwd`) relative to the project
+    /// root with `/` separators, which is how file paths are stored. Paths
+    /// outside the project are returned absolute.
+    pub fn relative_path(&self, cwd: &Path, path: &Path) -> String {
+        let absolute = cwd.join(path);
+        let absolute = absolute.canonicalize().unwrap_or_else(|_| lexically_normalize(&absolute));
+
+        let path = absolute.strip_prefix(&self.root).unwrap_or(&absolute);
+        path.components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+            .replace("//", "/")
+    }
+}
+
+/// Check whether a directory holds an initialized memory.
+///
+/// `~/.aimemoryengine` also exists for the license cache, so the marker
+/// directory alone is not enough: it must contain a config or a database.
+pub fn is_project_root(dir: &Path) -> bool {
+    let memory_dir = dir.join(MEMORY_DIR);
+    memory_dir.join(CONFIG_FILE).is_file() || memory_dir.join("memory.db").is_file()
+}
+
+/// Walk up from `start` to the nearest initialized project root
+pub fn find_project_root(start: &Path) -> Option<PathBuf> {
+    start.ancestors().find(|dir| is_project_root(dir)).map(|dir| dir.to_path_buf())
+}
+
+fn lexically_normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_discover_walks_up() -> Result<()> {
+        let dir = TempDir::new()?;
+        let root = dir.path().canonicalize()?;
+        ProjectConfig::write_default(&root)?;
+        let nested = root.join("src/deeply/nested");
+        fs::create_dir_all(&nested)?;
+
+        let project = Project::discover(&nested, None)?.expect("project should be found");
+        assert_eq!(project.root, root);
+        assert_eq!(project.database_path(), root.join(".aimemoryengine/memory.db"));
+
+        // Explicit roots are not walked up from
+        assert!(Project::discover(&nested, Some(&nested))?.is_none());
+        assert!(Project::discover(&nested, Some(Path::new("../../..")))?.is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_license_directory_is_not_a_project() -> Result<()> {
+        let dir = TempDir::new()?;
+        fs::create_dir_all(dir.path().join(MEMORY_DIR))?;
+        fs::write(dir.path().join(MEMORY_DIR).join("license.json"), "{}")?;
+
+        assert!(find_project_root(&dir.path().join("work")).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_relative_path() -> Result<()> {
+        let dir = TempDir::new()?;
+        ProjectConfig::write_default(dir.path())?;
+        fs::create_dir_all(dir.path().join("src/util"))?;
+        fs::write(dir.path().join("src/util/dates.ts"), "")?;
+
+        let project = Project::open(dir.path())?;
+        let src = project.root.join("src");
+        assert_eq!(project.relative_path(&src, Path::new("util/dates.ts")), "src/util/dates.ts");
+        assert_eq!(project.relative_path(&src, Path::new("./util/../main.ts")), "src/main.ts");
+        assert_eq!(project.relative_path(&project.root, &project.root.join("src/util/dates.ts")), "src/util/dates.ts");
+
+        Ok(())
+    }
+}
@@ -30,6 +30,7 @@ impl RelationType {
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s {
             "calls" => Some(RelationType::Calls),
@@ -75,12 +76,62 @@ impl Relationship {
         }
     }
 
+    /// Relationship to a symbol that may be declared in another file. Until
+    /// `ProjectMemory::resolve_references` links it, `to_entity` holds the symbol name.
+    pub fn to_symbol(from_entity: String, symbol: &str, relationship_type: RelationType) -> Self {
+        Self::new(from_entity, symbol.to_string(), relationship_type)
+            .with_metadata("target_name".to_string(), symbol.to_string())
+            .with_metadata("resolved".to_string(), "false".to_string())
+    }
+
+    /// `Contains` edge from a member to its owner's symbol, for members declared
+    /// apart from their type. Once resolved, it is turned around to point from
+    /// the owner to the member like any other `Contains` edge.
+    pub fn member_of(member: String, owner: &str) -> Self {
+        Self::to_symbol(member, owner, RelationType::Contains).with_metadata("member_of".to_string(), "true".to_string())
+    }
+
     pub fn with_metadata(mut self, key: String, value: String) -> Self {
         self.metadata.insert(key, value);
         self.updated_at = Utc::now();
         self
     }
 
+    /// Symbol name the relationship was created for, if it targets a symbol
+    pub fn target_name(&self) -> Option<&str> {
+        self.metadata.get("target_name").map(|name| name.as_str())
+    }
+
+    /// Whether `to_entity` is an entity id rather than a pending symbol name
+    pub fn is_resolved(&self) -> bool {
+        self.metadata.get("resolved").map(|v| v != "false").unwrap_or(true)
+    }
+
+    /// Point the relationship at a concrete entity
+    pub fn resolve_to(&mut self, entity_id: String) {
+        if self.metadata.contains_key("member_of") {
+            self.to_entity = std::mem::replace(&mut self.from_entity, entity_id);
+        } else {
+            self.to_entity = entity_id;
+        }
+        self.metadata.insert("resolved".to_string(), "true".to_string());
+        self.updated_at = Utc::now();
+    }
+
+    /// Detach the relationship from its target entity, keeping the symbol name
+    pub fn unresolve(&mut self) -> bool {
+        let Some(target_name) = self.target_name().map(|name| name.to_string()) else {
+            return false;
+        };
+        if self.metadata.contains_key("member_of") && self.is_resolved() {
+            self.from_entity = std::mem::take(&mut self.to_entity);
+        }
+        self.to_entity = target_name;
+        self.metadata.insert("resolved".to_string(), "false".to_string());
+        self.updated_at = Utc::now();
+        true
+    }
+
     pub fn get_signature(&self) -> String {
         format!(
             "{}->{}:{}",
@@ -99,6 +150,12 @@ pub struct RelationshipQuery {
     pub relationship_type: Option<RelationType>,
 }
 
+impl Default for RelationshipQuery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl RelationshipQuery {
     pub fn new() -> Self {
         Self {
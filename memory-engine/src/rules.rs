@@ -0,0 +1,260 @@
+use crate::config::build_globset;
+use crate::memory::join_relative;
+use crate::{ArchitectureRule, CodeEntity, EntityType, ProjectMemory, RelationType};
+use anyhow::{anyhow, Result};
+use globset::GlobSet;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// A dependency that breaks an architecture rule
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RuleViolation {
+    pub rule: String,
+    pub file: String,
+    pub line: u32,
+    /// `import`, or the relationship type of the offending edge
+    pub kind: String,
+    /// The imported module or the symbol used
+    pub target: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_file: Option<String>,
+}
+
+/// One rule with its globs compiled
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    name: String,
+    from: Option<GlobSet>,
+    except: GlobSet,
+    deny_files: GlobSet,
+    deny_imports: GlobSet,
+    deny_prefixes: Vec<String>,
+}
+
+impl CompiledRule {
+    fn applies_to(&self, file: &str) -> bool {
+        self.from.as_ref().map(|from| from.is_match(file)).unwrap_or(true) && !self.except.is_match(file)
+    }
+
+    fn denies_file(&self, from_file: &str, target_file: &str) -> bool {
+        target_file != from_file && self.deny_files.is_match(target_file)
+    }
+
+    // `rusqlite` denies `rusqlite`, `rusqlite::Connection` and `rusqlite.x`, but not `rusqlite_extra`
+    fn denies_symbol(&self, symbol: &str) -> bool {
+        self.deny_imports.is_match(symbol)
+            || self.deny_prefixes.iter().any(|prefix| {
+                symbol.strip_prefix(prefix.as_str()).is_some_and(|rest| ["::", ".", "/"].iter().any(|sep| rest.starts_with(sep)))
+            })
+    }
+}
+
+/// The project's architecture rules, ready to check
+#[derive(Debug, Clone)]
+pub struct RuleSet {
+    rules: Vec<CompiledRule>,
+}
+
+impl RuleSet {
+    pub fn new(rules: &[ArchitectureRule]) -> Result<Self> {
+        let rules = rules
+            .iter()
+            .enumerate()
+            .map(|(index, rule)| {
+                let name = if rule.name.is_empty() { format!("rule {}", index + 1) } else { rule.name.clone() };
+                if rule.deny_files.is_empty() && rule.deny_imports.is_empty() {
+                    return Err(anyhow!("Architecture rule '{}' needs `deny_files` or `deny_imports`", name));
+                }
+                Ok(CompiledRule {
+                    from: if rule.from.is_empty() { None } else { Some(build_globset(&rule.from)?) },
+                    except: build_globset(&rule.except)?,
+                    deny_files: build_globset(&rule.deny_files)?,
+                    deny_imports: build_globset(&rule.deny_imports)?,
+                    deny_prefixes: rule.deny_imports.clone(),
+                    name,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Check every import and every call, use, reference and inheritance edge
+    pub fn check(&self, memory: &ProjectMemory) -> Vec<RuleViolation> {
+        let imports = ImportResolver::new(memory);
+        let mut violations = Vec::new();
+        for rule in &self.rules {
+            for import in memory.entities.values().filter(|e| e.entity_type == EntityType::Import) {
+                if !rule.applies_to(&import.file_path) {
+                    continue;
+                }
+                let target_file = imports.target_file(import);
+                let denied = rule.denies_symbol(&import.name)
+                    || target_file.as_deref().is_some_and(|target| rule.denies_file(&import.file_path, target));
+                if denied {
+                    violations.push(RuleViolation {
+                        rule: rule.name.clone(),
+                        file: import.file_path.clone(),
+                        line: import.line_start,
+                        kind: "import".to_string(),
+                        target: import.name.clone(),
+                        target_file,
+                    });
+                }
+            }
+
+            for rel in &memory.relationships {
+                if !matches!(
+                    rel.relationship_type,
+                    RelationType::Calls | RelationType::Uses | RelationType::References | RelationType::Extends | RelationType::Implements | RelationType::Imports
+                ) {
+                    continue;
+                }
+                let Some(from) = memory.entities.get(&rel.from_entity) else { continue };
+                if !rule.applies_to(&from.file_path) {
+                    continue;
+                }
+                let target = memory.entities.get(&rel.to_entity).filter(|_| rel.is_resolved());
+                let symbol = match target {
+                    Some(target) => target.qualified_name.as_str(),
+                    None => rel.target_name().unwrap_or(&rel.to_entity),
+                };
+                let denied = rule.denies_symbol(symbol)
+                    || rel.target_name().is_some_and(|name| rule.denies_symbol(name))
+                    || target.is_some_and(|target| rule.denies_file(&from.file_path, &target.file_path));
+                if denied {
+                    let line = rel.metadata.get("line").and_then(|line| line.parse().ok()).unwrap_or(from.line_start);
+                    violations.push(RuleViolation {
+                        rule: rule.name.clone(),
+                        file: from.file_path.clone(),
+                        line,
+                        kind: rel.relationship_type.as_str().to_string(),
+                        target: rel.target_name().unwrap_or(symbol).to_string(),
+                        target_file: target.map(|target| target.file_path.clone()),
+                    });
+                }
+            }
+        }
+
+        violations.sort_by(|a, b| (&a.file, a.line, &a.rule, &a.target).cmp(&(&b.file, b.line, &b.rule, &b.target)));
+        violations.dedup();
+        violations
+    }
+}
+
+/// Finds the project file an import entity refers to
+pub(crate) struct ImportResolver<'a> {
+    by_qualified_name: HashMap<&'a str, &'a str>,
+    files: HashSet<&'a str>,
+}
+
+impl<'a> ImportResolver<'a> {
+    pub(crate) fn new(memory: &'a ProjectMemory) -> Self {
+        let mut by_qualified_name: HashMap<&str, &str> = HashMap::new();
+        for entity in memory.entities.values() {
+            if !matches!(entity.entity_type, EntityType::Import | EntityType::Export) {
+                by_qualified_name.entry(entity.qualified_name.as_str()).or_insert(entity.file_path.as_str());
+            }
+        }
+        let files = memory.entities.values().map(|e| e.file_path.as_str()).collect();
+        Self { by_qualified_name, files }
+    }
+
+    /// A declaration with the imported qualified name (or inside that module),
+    /// or a relative path next to the importing file
+    pub(crate) fn target_file(&self, import: &CodeEntity) -> Option<String> {
+        let name = import.name.as_str();
+        if name.starts_with("./") || name.starts_with("../") {
+            let dir = import.file_path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+            let path = join_relative(dir, name);
+            let found = std::iter::once(path.clone())
+                .chain(self.files.iter().filter(|f| f.starts_with(&format!("{}.", path)) || f.starts_with(&format!("{}/index.", path))).map(|f| f.to_string()))
+                .find(|candidate| self.files.contains(candidate.as_str()));
+            return Some(found.unwrap_or(path));
+        }
+
+        // `crate::infra::db::Pool` or the module `crate::infra::db` itself
+        let mut prefix = name;
+        loop {
+            if let Some(file) = self.by_qualified_name.get(prefix) {
+                return Some(file.to_string());
+            }
+            match prefix.rfind(['.', ':']) {
+                Some(at) => prefix = prefix[..at].trim_end_matches(':'),
+                None => break,
+            }
+            if prefix.is_empty() {
+                break;
+            }
+        }
+        let module_prefixes = [format!("{}::", name), format!("{}.", name), format!("{}#", name)];
+        self.by_qualified_name
+            .iter()
+            .find(|(qualified, _)| module_prefixes.iter().any(|prefix| qualified.starts_with(prefix.as_str())))
+            .map(|(_, file)| file.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CodeParser, ProjectConfig};
+
+    #[test]
+    fn test_rules_catch_imports_and_calls() -> Result<()> {
+        let config = ProjectConfig::from_toml_str(
+            r#"
+[[rules]]
+name = "domain is independent"
+from = ["src/domain/**"]
+deny_files = ["src/infra/**"]
+
+[[rules]]
+name = "only storage uses rusqlite"
+except = ["**/storage.rs"]
+deny_imports = ["rusqlite"]
+"#,
+        )?;
+        let parser = CodeParser::new()?;
+        let mut memory = ProjectMemory::new("/test".to_string());
+        let files = [
+            ("src/infra/db.rs", "pub struct Pool;\nimpl Pool {\n    pub fn connect() -> Pool { Pool }\n}\n"),
+            ("src/domain/order.rs", "use crate::infra::db::Pool;\n\nfn place() {\n    Pool::connect();\n}\n"),
+            ("src/storage.rs", "use rusqlite::Connection;\n"),
+            ("src/cache.rs", "use rusqlite::params;\nuse rusqlite_extra::Thing;\n"),
+            ("src/web/app.js", "import { db } from '../infra/db';\n"),
+        ];
+        for (path, source) in files {
+            let (entities, relationships) = parser.parse_source(path, source)?;
+            entities.into_iter().for_each(|e| memory.add_entity(e));
+            relationships.into_iter().for_each(|r| memory.add_relationship(r));
+        }
+        memory.resolve_references();
+
+        let rules = RuleSet::new(&config.rules)?;
+        let found: Vec<String> = rules
+            .check(&memory)
+            .iter()
+            .map(|v| format!("{}:{} {} {} ({})", v.file, v.line, v.kind, v.target, v.rule))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                "src/cache.rs:1 import rusqlite::params (only storage uses rusqlite)",
+                "src/domain/order.rs:1 import crate::infra::db::Pool (domain is independent)",
+                "src/domain/order.rs:4 references Pool (domain is independent)",
+                "src/domain/order.rs:4 calls Pool::connect (domain is independent)",
+            ]
+        );
+
+        assert!(RuleSet::new(&[ArchitectureRule { name: "empty".to_string(), ..Default::default() }]).is_err());
+        Ok(())
+    }
+}
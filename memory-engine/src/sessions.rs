@@ -0,0 +1,180 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+/// Kinds of entries recorded during an AI interaction session
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum SessionEntryKind {
+    Decision, // A choice that was made and should be remembered
+    Question, // Something the assistant or user asked
+    Answer,   // The answer given to a question
+    Note,     // Free-form context
+}
+
+impl SessionEntryKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionEntryKind::Decision => "decision",
+            SessionEntryKind::Question => "question",
+            SessionEntryKind::Answer => "answer",
+            SessionEntryKind::Note => "note",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "decision" => Some(SessionEntryKind::Decision),
+            "question" => Some(SessionEntryKind::Question),
+            "answer" => Some(SessionEntryKind::Answer),
+            "note" => Some(SessionEntryKind::Note),
+            _ => None,
+        }
+    }
+}
+
+/// A single thing that happened during a session, linked to the code it concerns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionEntry {
+    pub id: String,
+    pub session_id: String,
+    pub kind: SessionEntryKind,
+    pub content: String,
+    pub entities: Vec<String>, // Entity names the entry is about
+    pub files: Vec<String>,    // File paths the entry is about
+    pub created_at: DateTime<Utc>,
+}
+
+impl SessionEntry {
+    pub fn new(session_id: String, kind: SessionEntryKind, content: String) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            session_id,
+            kind,
+            content,
+            entities: Vec::new(),
+            files: Vec::new(),
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn with_entity(mut self, entity_name: String) -> Self {
+        if !self.entities.contains(&entity_name) {
+            self.entities.push(entity_name);
+        }
+        self
+    }
+
+    pub fn with_file(mut self, file_path: String) -> Self {
+        if !self.files.contains(&file_path) {
+            self.files.push(file_path);
+        }
+        self
+    }
+
+    /// Case-insensitive keyword match against the entry text and its links
+    pub fn matches_keyword(&self, keyword: &str) -> bool {
+        let keyword = keyword.to_lowercase();
+        self.content.to_lowercase().contains(&keyword)
+            || self.entities.iter().any(|e| e.to_lowercase().contains(&keyword))
+            || self.files.iter().any(|f| f.to_lowercase().contains(&keyword))
+    }
+}
+
+/// A recorded conversation between a developer and an AI assistant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub id: String,
+    pub title: String,
+    pub assistant: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub entries: Vec<SessionEntry>,
+}
+
+impl Session {
+    pub fn new(title: String) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            title,
+            assistant: None,
+            started_at: Utc::now(),
+            ended_at: None,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn with_assistant(mut self, assistant: String) -> Self {
+        self.assistant = Some(assistant);
+        self
+    }
+
+    /// Append an entry to this session and return it for further linking
+    pub fn record(&mut self, kind: SessionEntryKind, content: String) -> &mut SessionEntry {
+        let entry = SessionEntry::new(self.id.clone(), kind, content);
+        self.entries.push(entry);
+        self.entries.last_mut().unwrap()
+    }
+
+    pub fn end(&mut self) {
+        self.ended_at = Some(Utc::now());
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.ended_at.is_none()
+    }
+
+    /// Get the entries of a given kind, in the order they were recorded
+    pub fn entries_of_kind(&self, kind: &SessionEntryKind) -> Vec<&SessionEntry> {
+        self.entries.iter().filter(|e| e.kind == *kind).collect()
+    }
+
+    pub fn mentions_entity(&self, entity_name: &str) -> bool {
+        self.entries.iter().any(|e| e.entities.iter().any(|n| n == entity_name))
+    }
+
+    pub fn mentions_file(&self, file_path: &str) -> bool {
+        self.entries.iter().any(|e| e.files.iter().any(|f| f == file_path))
+    }
+
+    pub fn matches_keyword(&self, keyword: &str) -> bool {
+        self.title.to_lowercase().contains(&keyword.to_lowercase())
+            || self.entries.iter().any(|e| e.matches_keyword(keyword))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_recording() {
+        let mut session = Session::new("Refactor storage".to_string());
+        session.record(SessionEntryKind::Question, "Why is save_memory clearing everything?".to_string());
+        let decision = session.record(SessionEntryKind::Decision, "Keep full rewrites for now".to_string());
+        decision.entities.push("save_memory".to_string());
+        decision.files.push("memory-engine/src/storage.rs".to_string());
+
+        assert_eq!(session.entries.len(), 2);
+        assert!(session.entries.iter().all(|e| e.session_id == session.id));
+        assert_eq!(session.entries_of_kind(&SessionEntryKind::Decision).len(), 1);
+        assert!(session.mentions_entity("save_memory"));
+        assert!(session.mentions_file("memory-engine/src/storage.rs"));
+        assert!(!session.mentions_entity("load_memory"));
+        assert!(session.is_active());
+    }
+
+    #[test]
+    fn test_keyword_matching() {
+        let entry = SessionEntry::new("s1".to_string(), SessionEntryKind::Note, "Parser uses regexes".to_string())
+            .with_entity("CodeParser".to_string())
+            .with_entity("CodeParser".to_string());
+
+        assert_eq!(entry.entities.len(), 1);
+        assert!(entry.matches_keyword("REGEX"));
+        assert!(entry.matches_keyword("codeparser"));
+        assert!(!entry.matches_keyword("sqlite"));
+        assert_eq!(SessionEntryKind::from_str("answer"), Some(SessionEntryKind::Answer));
+        assert_eq!(SessionEntryKind::from_str("chat"), None);
+    }
+}
@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+
+/// A parameter of a function, or a field of a struct or class
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Parameter {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+}
+
+impl Parameter {
+    pub fn new(name: &str, type_name: Option<&str>, default: Option<&str>) -> Self {
+        let non_empty = |text: Option<&str>| text.map(str::trim).filter(|t| !t.is_empty()).map(str::to_string);
+        Self {
+            name: name.trim().to_string(),
+            type_name: non_empty(type_name),
+            default: non_empty(default),
+        }
+    }
+
+    /// `name: Type = default`, leaving out the parts that are unknown
+    pub fn render(&self) -> String {
+        let mut text = self.name.clone();
+        if let Some(type_name) = &self.type_name {
+            text = if text.is_empty() { type_name.clone() } else { format!("{}: {}", text, type_name) };
+        }
+        if let Some(default) = &self.default {
+            text.push_str(" = ");
+            text.push_str(default);
+        }
+        text
+    }
+}
+
+/// The declared shape of a function or type, as written in the source
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Signature {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<String>,
+    /// Keywords such as `async`, `static`, `const`, `abstract` or `override`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub modifiers: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generics: Option<String>,
+    /// `None` for types; `Some(vec![])` for a function without parameters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<Vec<Parameter>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub return_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<Parameter>,
+}
+
+impl Signature {
+    /// One-line, language-neutral rendering, e.g. `pub async load<T>(items: &[T]) -> usize`
+    pub fn render(&self, name: &str) -> String {
+        let mut text = String::new();
+        for word in self.visibility.iter().chain(&self.modifiers) {
+            text.push_str(word);
+            text.push(' ');
+        }
+        text.push_str(name);
+        if let Some(generics) = &self.generics {
+            text.push_str(&format!("<{}>", generics));
+        }
+        if let Some(params) = &self.params {
+            let params: Vec<String> = params.iter().map(Parameter::render).collect();
+            text.push_str(&format!("({})", params.join(", ")));
+        }
+        if let Some(return_type) = &self.return_type {
+            text.push_str(" -> ");
+            text.push_str(return_type);
+        }
+        if !self.fields.is_empty() {
+            let fields: Vec<String> = self.fields.iter().map(Parameter::render).collect();
+            text.push_str(&format!(" {{ {} }}", fields.join(", ")));
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_rendering() {
+        let function = Signature {
+            visibility: Some("pub".to_string()),
+            modifiers: vec!["async".to_string()],
+            generics: Some("T: Clone".to_string()),
+            params: Some(vec![Parameter::new("items", Some("&[T]"), None), Parameter::new("limit", Some("usize"), Some("10"))]),
+            return_type: Some("Vec<T>".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(function.render("load"), "pub async load<T: Clone>(items: &[T], limit: usize = 10) -> Vec<T>");
+
+        let record = Signature {
+            fields: vec![Parameter::new("id", Some("u64"), None), Parameter::new("name", None, Some("\"\""))],
+            ..Default::default()
+        };
+        assert_eq!(record.render("User"), "User { id: u64, name = \"\" }");
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{ProjectMemory, CodeEntity, Relationship, EntityType, RelationType};
+use crate::{ProjectMemory, CodeEntity, Relationship, EntityType, RelationType, Session, SessionEntry, SessionEntryKind, DecisionRecord, DecisionStatus};
 use anyhow::Result;
 use rusqlite::{Connection, params, Transaction};
 use std::collections::HashMap;
@@ -24,10 +24,19 @@ impl MemoryStorage {
                 column_end INTEGER NOT NULL,
                 metadata TEXT,
                 created_at TEXT NOT NULL,
-                updated_at TEXT NOT NULL
+                updated_at TEXT NOT NULL,
+                signature TEXT,
+                qualified_name TEXT,
+                metrics TEXT,
+                centrality TEXT
             )",
             [],
         )?;
+        // Databases created before signatures were recorded lack the column
+        add_column_if_missing(&conn, "entities", "signature", "TEXT")?;
+        add_column_if_missing(&conn, "entities", "qualified_name", "TEXT")?;
+        add_column_if_missing(&conn, "entities", "metrics", "TEXT")?;
+        add_column_if_missing(&conn, "entities", "centrality", "TEXT")?;
 
         // Create relationships table
         conn.execute(
@@ -53,12 +62,78 @@ impl MemoryStorage {
             [],
         )?;
 
+        // Create sessions tables for AI interaction history
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS sessions (
+                id TEXT PRIMARY KEY,
+                title TEXT NOT NULL,
+                assistant TEXT,
+                started_at TEXT NOT NULL,
+                ended_at TEXT
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS session_entries (
+                id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                content TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Links from session entries to the entities and files they mention
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS session_links (
+                entry_id TEXT NOT NULL,
+                session_id TEXT NOT NULL,
+                link_type TEXT NOT NULL,
+                target TEXT NOT NULL,
+                PRIMARY KEY (entry_id, link_type, target)
+            )",
+            [],
+        )?;
+
+        // Create architecture decision record tables
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS decisions (
+                id TEXT PRIMARY KEY,
+                number INTEGER,
+                title TEXT NOT NULL,
+                status TEXT NOT NULL,
+                context TEXT NOT NULL,
+                decision TEXT NOT NULL,
+                consequences TEXT NOT NULL,
+                source_path TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS decision_links (
+                decision_id TEXT NOT NULL,
+                link_type TEXT NOT NULL,
+                target TEXT NOT NULL,
+                PRIMARY KEY (decision_id, link_type, target)
+            )",
+            [],
+        )?;
+
         // Create indexes for performance
         conn.execute("CREATE INDEX IF NOT EXISTS idx_entities_file ON entities(file_path)", [])?;
         conn.execute("CREATE INDEX IF NOT EXISTS idx_entities_type ON entities(entity_type)", [])?;
         conn.execute("CREATE INDEX IF NOT EXISTS idx_entities_name ON entities(name)", [])?;
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_entities_qualified ON entities(qualified_name)", [])?;
         conn.execute("CREATE INDEX IF NOT EXISTS idx_relationships_from ON relationships(from_entity)", [])?;
         conn.execute("CREATE INDEX IF NOT EXISTS idx_relationships_to ON relationships(to_entity)", [])?;
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_session_entries_session ON session_entries(session_id)", [])?;
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_session_links_target ON session_links(link_type, target)", [])?;
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_decisions_source ON decisions(source_path)", [])?;
 
         Ok(Self { conn })
     }
@@ -97,44 +172,7 @@ impl MemoryStorage {
         let mut memory = ProjectMemory::new(project_path.to_string());
 
         // Load entities
-        let mut stmt = self.conn.prepare(
-            "SELECT id, name, entity_type, file_path, line_start, line_end, column_start, column_end, metadata, created_at, updated_at FROM entities"
-        )?;
-
-        let entity_iter = stmt.query_map([], |row| {
-            let entity_type_str: String = row.get(2)?;
-            let entity_type = EntityType::from_str(&entity_type_str).unwrap_or(EntityType::Function);
-
-            let metadata_json: String = row.get(8)?;
-            let metadata: HashMap<String, String> = serde_json::from_str(&metadata_json).unwrap_or_default();
-
-            let created_at_str: String = row.get(9)?;
-            let updated_at_str: String = row.get(10)?;
-
-            let mut entity = CodeEntity::new(
-                row.get(1)?,
-                entity_type,
-                row.get(3)?,
-                row.get(4)?,
-                row.get(5)?,
-                row.get(6)?,
-                row.get(7)?,
-            );
-
-            entity.id = row.get(0)?;
-            entity.metadata = metadata;
-            entity.created_at = chrono::DateTime::parse_from_rfc3339(&created_at_str)
-                .unwrap_or_else(|_| chrono::Utc::now().into())
-                .with_timezone(&chrono::Utc);
-            entity.updated_at = chrono::DateTime::parse_from_rfc3339(&updated_at_str)
-                .unwrap_or_else(|_| chrono::Utc::now().into())
-                .with_timezone(&chrono::Utc);
-
-            Ok(entity)
-        })?;
-
-        for entity in entity_iter {
-            let entity = entity?;
+        for entity in self.load_entities_where("1 = 1", params![])? {
             memory.entities.insert(entity.id.clone(), entity);
         }
 
@@ -192,13 +230,16 @@ impl MemoryStorage {
     // Helper methods for transaction-based operations
     fn save_entity_in_tx(&self, tx: &Transaction, entity: &CodeEntity) -> Result<()> {
         let metadata_json = serde_json::to_string(&entity.metadata)?;
+        let signature_json = entity.signature.as_ref().map(serde_json::to_string).transpose()?;
+        let metrics_json = entity.metrics.as_ref().map(serde_json::to_string).transpose()?;
+        let centrality_json = entity.centrality.as_ref().map(serde_json::to_string).transpose()?;
         let created_at = entity.created_at.to_rfc3339();
         let updated_at = entity.updated_at.to_rfc3339();
 
         tx.execute(
             "INSERT OR REPLACE INTO entities
-             (id, name, entity_type, file_path, line_start, line_end, column_start, column_end, metadata, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+             (id, name, entity_type, file_path, line_start, line_end, column_start, column_end, metadata, created_at, updated_at, signature, qualified_name, metrics, centrality)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
             params![
                 entity.id,
                 entity.name,
@@ -210,7 +251,11 @@ impl MemoryStorage {
                 entity.column_end,
                 metadata_json,
                 created_at,
-                updated_at
+                updated_at,
+                signature_json,
+                entity.qualified_name,
+                metrics_json,
+                centrality_json
             ],
         )?;
 
@@ -242,89 +287,73 @@ impl MemoryStorage {
 
     // Query methods for specific use cases
     pub fn find_entities_by_file(&self, file_path: &str) -> Result<Vec<CodeEntity>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, name, entity_type, file_path, line_start, line_end, column_start, column_end, metadata, created_at, updated_at
-             FROM entities WHERE file_path = ?1 ORDER BY line_start"
-        )?;
-
-        let entity_iter = stmt.query_map([file_path], |row| {
-            let entity_type_str: String = row.get(2)?;
-            let entity_type = EntityType::from_str(&entity_type_str).unwrap_or(EntityType::Function);
-
-            let metadata_json: String = row.get(8)?;
-            let metadata: HashMap<String, String> = serde_json::from_str(&metadata_json).unwrap_or_default();
-
-            let created_at_str: String = row.get(9)?;
-            let updated_at_str: String = row.get(10)?;
-
-            let mut entity = CodeEntity::new(
-                row.get(1)?,
-                entity_type,
-                row.get(3)?,
-                row.get(4)?,
-                row.get(5)?,
-                row.get(6)?,
-                row.get(7)?,
-            );
-
-            entity.id = row.get(0)?;
-            entity.metadata = metadata;
-            entity.created_at = chrono::DateTime::parse_from_rfc3339(&created_at_str)
-                .unwrap_or_else(|_| chrono::Utc::now().into())
-                .with_timezone(&chrono::Utc);
-            entity.updated_at = chrono::DateTime::parse_from_rfc3339(&updated_at_str)
-                .unwrap_or_else(|_| chrono::Utc::now().into())
-                .with_timezone(&chrono::Utc);
-
-            Ok(entity)
-        })?;
+        self.load_entities_where("file_path = ?1 ORDER BY line_start", params![file_path])
+    }
 
-        let mut entities = Vec::new();
-        for entity in entity_iter {
-            entities.push(entity?);
-        }
+    pub fn find_entities_by_name(&self, pattern: &str) -> Result<Vec<CodeEntity>> {
+        let search_pattern = format!("%{}%", pattern);
+        self.load_entities_where("name LIKE ?1 ORDER BY name", params![search_pattern])
+    }
 
-        Ok(entities)
+    /// Find entities whose doc comment or docstring mentions a term
+    pub fn find_entities_by_doc(&self, term: &str) -> Result<Vec<CodeEntity>> {
+        let search_pattern = format!("%{}%", term);
+        self.load_entities_where(
+            "json_extract(metadata, '$.doc') LIKE ?1
+                OR json_extract(metadata, '$.doc_params') LIKE ?1
+                OR json_extract(metadata, '$.doc_returns') LIKE ?1
+             ORDER BY name",
+            params![search_pattern],
+        )
     }
 
-    pub fn find_entities_by_name(&self, pattern: &str) -> Result<Vec<CodeEntity>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, name, entity_type, file_path, line_start, line_end, column_start, column_end, metadata, created_at, updated_at
-             FROM entities WHERE name LIKE ?1 ORDER BY name"
-        )?;
+    /// Find entities by their full qualified name, e.g. `crate::storage::MemoryStorage::new`
+    pub fn find_entities_by_qualified_name(&self, qualified_name: &str) -> Result<Vec<CodeEntity>> {
+        self.load_entities_where("qualified_name = ?1 ORDER BY file_path, line_start", params![qualified_name])
+    }
 
-        let search_pattern = format!("%{}%", pattern);
-        let entity_iter = stmt.query_map([search_pattern], |row| {
-            let entity_type_str: String = row.get(2)?;
-            let entity_type = EntityType::from_str(&entity_type_str).unwrap_or(EntityType::Function);
+    /// Find entities whose qualified name ends with whole segments, e.g. `MemoryStorage::new` or `Service.run`
+    pub fn find_entities_by_suffix(&self, suffix: &str) -> Result<Vec<CodeEntity>> {
+        self.load_entities_where(
+            "qualified_name = ?1
+                OR substr(qualified_name, -length(?1) - 1) IN ('.' || ?1, '#' || ?1)
+                OR substr(qualified_name, -length(?1) - 2) = '::' || ?1
+             ORDER BY qualified_name",
+            params![suffix],
+        )
+    }
 
-            let metadata_json: String = row.get(8)?;
-            let metadata: HashMap<String, String> = serde_json::from_str(&metadata_json).unwrap_or_default();
+    /// Find entities declared inside a scope: a module, package, file or type
+    pub fn find_entities_in_scope(&self, scope: &str) -> Result<Vec<CodeEntity>> {
+        self.load_entities_where(
+            "substr(qualified_name, 1, length(?1) + 1) IN (?1 || '.', ?1 || '#')
+                OR substr(qualified_name, 1, length(?1) + 2) = ?1 || '::'
+             ORDER BY qualified_name",
+            params![scope],
+        )
+    }
 
-            let created_at_str: String = row.get(9)?;
-            let updated_at_str: String = row.get(10)?;
+    /// Functions and types that were measured while parsing
+    pub fn find_entities_with_metrics(&self) -> Result<Vec<CodeEntity>> {
+        self.load_entities_where("metrics IS NOT NULL ORDER BY file_path, line_start", params![])
+    }
 
-            let mut entity = CodeEntity::new(
-                row.get(1)?,
-                entity_type,
-                row.get(3)?,
-                row.get(4)?,
-                row.get(5)?,
-                row.get(6)?,
-                row.get(7)?,
-            );
+    /// Entities scored by centrality during the last analysis, most central first
+    pub fn find_entities_with_centrality(&self) -> Result<Vec<CodeEntity>> {
+        self.load_entities_where(
+            "centrality IS NOT NULL ORDER BY json_extract(centrality, '$.pagerank') DESC, file_path, line_start",
+            params![],
+        )
+    }
 
-            entity.id = row.get(0)?;
-            entity.metadata = metadata;
-            entity.created_at = chrono::DateTime::parse_from_rfc3339(&created_at_str)
-                .unwrap_or_else(|_| chrono::Utc::now().into())
-                .with_timezone(&chrono::Utc);
-            entity.updated_at = chrono::DateTime::parse_from_rfc3339(&updated_at_str)
-                .unwrap_or_else(|_| chrono::Utc::now().into())
-                .with_timezone(&chrono::Utc);
+    fn load_entities_where(&self, clause: &str, query_params: &[&dyn rusqlite::ToSql]) -> Result<Vec<CodeEntity>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT id, name, entity_type, file_path, line_start, line_end, column_start, column_end, metadata, created_at, updated_at, signature, qualified_name, metrics, centrality
+             FROM entities WHERE {}",
+            clause
+        ))?;
 
-            Ok(entity)
-        })?;
+        let entity_iter = stmt.query_map(query_params, entity_from_row)?;
 
         let mut entities = Vec::new();
         for entity in entity_iter {
@@ -355,6 +384,362 @@ impl MemoryStorage {
 
         Ok((entity_count, relationship_count, file_count))
     }
+
+    // Session methods for AI interaction history
+    pub fn save_session(&self, session: &Session) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+
+        tx.execute(
+            "INSERT OR REPLACE INTO sessions (id, title, assistant, started_at, ended_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                session.id,
+                session.title,
+                session.assistant,
+                session.started_at.to_rfc3339(),
+                session.ended_at.map(|t| t.to_rfc3339())
+            ],
+        )?;
+
+        for entry in &session.entries {
+            self.save_session_entry_in_tx(&tx, entry)?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn add_session_entry(&self, entry: &SessionEntry) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        self.save_session_entry_in_tx(&tx, entry)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn save_session_entry_in_tx(&self, tx: &Transaction, entry: &SessionEntry) -> Result<()> {
+        tx.execute(
+            "INSERT OR REPLACE INTO session_entries (id, session_id, kind, content, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                entry.id,
+                entry.session_id,
+                entry.kind.as_str(),
+                entry.content,
+                entry.created_at.to_rfc3339()
+            ],
+        )?;
+
+        tx.execute("DELETE FROM session_links WHERE entry_id = ?1", [&entry.id])?;
+        let links = entry.entities.iter().map(|e| ("entity", e))
+            .chain(entry.files.iter().map(|f| ("file", f)));
+        for (link_type, target) in links {
+            tx.execute(
+                "INSERT OR IGNORE INTO session_links (entry_id, session_id, link_type, target)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![entry.id, entry.session_id, link_type, target],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Load a session by id or unique id prefix
+    pub fn load_session(&self, id_or_prefix: &str) -> Result<Option<Session>> {
+        let mut sessions = self.load_sessions_where(
+            "substr(id, 1, length(?1)) = ?1 ORDER BY started_at DESC LIMIT 2",
+            params![id_or_prefix],
+        )?;
+
+        match sessions.len() {
+            1 => Ok(sessions.pop()),
+            0 => Ok(None),
+            _ => Err(anyhow::anyhow!("Session id prefix '{}' is ambiguous", id_or_prefix)),
+        }
+    }
+
+    pub fn list_sessions(&self, limit: usize) -> Result<Vec<Session>> {
+        self.load_sessions_where("1 = 1 ORDER BY started_at DESC LIMIT ?1", params![limit as i64])
+    }
+
+    /// Find sessions with entries linked to the given entity name
+    pub fn find_sessions_by_entity(&self, entity_name: &str) -> Result<Vec<Session>> {
+        self.load_sessions_where(
+            "id IN (SELECT session_id FROM session_links WHERE link_type = 'entity' AND target = ?1)
+             ORDER BY started_at DESC",
+            params![entity_name],
+        )
+    }
+
+    /// Find sessions with entries linked to the given file path
+    pub fn find_sessions_by_file(&self, file_path: &str) -> Result<Vec<Session>> {
+        self.load_sessions_where(
+            "id IN (SELECT session_id FROM session_links WHERE link_type = 'file' AND target = ?1)
+             ORDER BY started_at DESC",
+            params![file_path],
+        )
+    }
+
+    /// Find sessions whose title, entries or links mention a keyword
+    pub fn search_sessions(&self, keyword: &str) -> Result<Vec<Session>> {
+        let search_pattern = format!("%{}%", keyword);
+        self.load_sessions_where(
+            "title LIKE ?1
+                OR id IN (SELECT session_id FROM session_entries WHERE content LIKE ?1)
+                OR id IN (SELECT session_id FROM session_links WHERE target LIKE ?1)
+             ORDER BY started_at DESC",
+            params![search_pattern],
+        )
+    }
+
+    fn load_sessions_where(&self, clause: &str, query_params: &[&dyn rusqlite::ToSql]) -> Result<Vec<Session>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT id, title, assistant, started_at, ended_at FROM sessions WHERE {}",
+            clause
+        ))?;
+
+        let session_iter = stmt.query_map(query_params, |row| {
+            let started_at_str: String = row.get(3)?;
+            let ended_at_str: Option<String> = row.get(4)?;
+
+            let mut session = Session::new(row.get(1)?);
+            session.id = row.get(0)?;
+            session.assistant = row.get(2)?;
+            session.started_at = chrono::DateTime::parse_from_rfc3339(&started_at_str)
+                .unwrap_or_else(|_| chrono::Utc::now().into())
+                .with_timezone(&chrono::Utc);
+            session.ended_at = ended_at_str
+                .and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok())
+                .map(|t| t.with_timezone(&chrono::Utc));
+
+            Ok(session)
+        })?;
+
+        let mut sessions = Vec::new();
+        for session in session_iter {
+            let mut session = session?;
+            session.entries = self.load_session_entries(&session.id)?;
+            sessions.push(session);
+        }
+
+        Ok(sessions)
+    }
+
+    fn load_session_entries(&self, session_id: &str) -> Result<Vec<SessionEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, session_id, kind, content, created_at FROM session_entries
+             WHERE session_id = ?1 ORDER BY created_at, rowid"
+        )?;
+
+        let entry_iter = stmt.query_map([session_id], |row| {
+            let kind_str: String = row.get(2)?;
+            let kind = SessionEntryKind::from_str(&kind_str).unwrap_or(SessionEntryKind::Note);
+            let created_at_str: String = row.get(4)?;
+
+            let mut entry = SessionEntry::new(row.get(1)?, kind, row.get(3)?);
+            entry.id = row.get(0)?;
+            entry.created_at = chrono::DateTime::parse_from_rfc3339(&created_at_str)
+                .unwrap_or_else(|_| chrono::Utc::now().into())
+                .with_timezone(&chrono::Utc);
+
+            Ok(entry)
+        })?;
+
+        let mut entries = Vec::new();
+        for entry in entry_iter {
+            entries.push(entry?);
+        }
+
+        let mut stmt = self.conn.prepare(
+            "SELECT entry_id, link_type, target FROM session_links WHERE session_id = ?1 ORDER BY rowid"
+        )?;
+        let link_iter = stmt.query_map([session_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+        })?;
+
+        for link in link_iter {
+            let (entry_id, link_type, target) = link?;
+            if let Some(entry) = entries.iter_mut().find(|e| e.id == entry_id) {
+                match link_type.as_str() {
+                    "entity" => entry.entities.push(target),
+                    _ => entry.files.push(target),
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+
+    // Architecture decision record methods
+
+    /// Save a decision record; re-importing the same source file updates it in place.
+    /// Returns the id the record was stored under.
+    pub fn save_decision(&self, record: &DecisionRecord) -> Result<String> {
+        let existing_id: Option<String> = match &record.source_path {
+            Some(source_path) => self.conn.query_row(
+                "SELECT id FROM decisions WHERE source_path = ?1",
+                [source_path],
+                |row| row.get(0),
+            ).ok(),
+            None => None,
+        };
+        let id = existing_id.unwrap_or_else(|| record.id.clone());
+
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT OR REPLACE INTO decisions
+             (id, number, title, status, context, decision, consequences, source_path, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                id,
+                record.number,
+                record.title,
+                record.status.as_str(),
+                record.context,
+                record.decision,
+                record.consequences,
+                record.source_path,
+                record.created_at.to_rfc3339(),
+                chrono::Utc::now().to_rfc3339()
+            ],
+        )?;
+
+        tx.execute("DELETE FROM decision_links WHERE decision_id = ?1", [&id])?;
+        let links = record.entities.iter().map(|e| ("entity", e))
+            .chain(record.files.iter().map(|f| ("file", f)));
+        for (link_type, target) in links {
+            tx.execute(
+                "INSERT OR IGNORE INTO decision_links (decision_id, link_type, target) VALUES (?1, ?2, ?3)",
+                params![id, link_type, target],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(id)
+    }
+
+    pub fn load_decisions(&self) -> Result<Vec<DecisionRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, number, title, status, context, decision, consequences, source_path, created_at, updated_at
+             FROM decisions ORDER BY number IS NULL, number, title"
+        )?;
+
+        let decision_iter = stmt.query_map([], |row| {
+            let status_str: String = row.get(3)?;
+            let created_at_str: String = row.get(8)?;
+            let updated_at_str: String = row.get(9)?;
+
+            let mut record = DecisionRecord::new(row.get(2)?)
+                .with_status(DecisionStatus::from_str(&status_str).unwrap_or(DecisionStatus::Proposed));
+            record.id = row.get(0)?;
+            record.number = row.get(1)?;
+            record.context = row.get(4)?;
+            record.decision = row.get(5)?;
+            record.consequences = row.get(6)?;
+            record.source_path = row.get(7)?;
+            record.created_at = chrono::DateTime::parse_from_rfc3339(&created_at_str)
+                .unwrap_or_else(|_| chrono::Utc::now().into())
+                .with_timezone(&chrono::Utc);
+            record.updated_at = chrono::DateTime::parse_from_rfc3339(&updated_at_str)
+                .unwrap_or_else(|_| chrono::Utc::now().into())
+                .with_timezone(&chrono::Utc);
+
+            Ok(record)
+        })?;
+
+        let mut records = Vec::new();
+        for record in decision_iter {
+            records.push(record?);
+        }
+
+        let mut stmt = self.conn.prepare("SELECT decision_id, link_type, target FROM decision_links ORDER BY rowid")?;
+        let link_iter = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+        })?;
+
+        for link in link_iter {
+            let (decision_id, link_type, target) = link?;
+            if let Some(record) = records.iter_mut().find(|r| r.id == decision_id) {
+                match link_type.as_str() {
+                    "entity" => record.entities.push(target),
+                    _ => record.files.push(target),
+                }
+            }
+        }
+
+        Ok(records)
+    }
+
+    /// Load a decision by ADR number or id prefix
+    pub fn load_decision(&self, number_or_id: &str) -> Result<Option<DecisionRecord>> {
+        let number = number_or_id.trim_start_matches(|c: char| !c.is_ascii_digit()).parse::<u32>().ok();
+        let decisions = self.load_decisions()?;
+
+        if let Some(record) = decisions.iter().find(|record| number.is_some() && record.number == number) {
+            return Ok(Some(record.clone()));
+        }
+
+        Ok(decisions.into_iter().find(|record| record.id.starts_with(number_or_id)))
+    }
+
+    /// Find the decisions that govern an entity, either by name or by file
+    pub fn find_decisions_for_entity(&self, entity: &CodeEntity) -> Result<Vec<DecisionRecord>> {
+        Ok(self.load_decisions()?
+            .into_iter()
+            .filter(|record| record.governs(entity))
+            .collect())
+    }
+}
+
+fn entity_from_row(row: &rusqlite::Row) -> rusqlite::Result<CodeEntity> {
+    let entity_type_str: String = row.get(2)?;
+    let entity_type = EntityType::from_str(&entity_type_str).unwrap_or(EntityType::Function);
+
+    let metadata_json: String = row.get(8)?;
+    let metadata: HashMap<String, String> = serde_json::from_str(&metadata_json).unwrap_or_default();
+    let signature_json: Option<String> = row.get(11)?;
+    let qualified_name: Option<String> = row.get(12)?;
+    let metrics_json: Option<String> = row.get(13)?;
+    let centrality_json: Option<String> = row.get(14)?;
+
+    let created_at_str: String = row.get(9)?;
+    let updated_at_str: String = row.get(10)?;
+
+    let mut entity = CodeEntity::new(
+        row.get(1)?,
+        entity_type,
+        row.get(3)?,
+        row.get(4)?,
+        row.get(5)?,
+        row.get(6)?,
+        row.get(7)?,
+    );
+
+    entity.id = row.get(0)?;
+    entity.metadata = metadata;
+    entity.signature = signature_json.and_then(|json| serde_json::from_str(&json).ok());
+    entity.metrics = metrics_json.and_then(|json| serde_json::from_str(&json).ok());
+    entity.centrality = centrality_json.and_then(|json| serde_json::from_str(&json).ok());
+    // Rows saved before qualified names were recorded fall back to the plain name
+    if let Some(qualified_name) = qualified_name.filter(|q| !q.is_empty()) {
+        entity.qualified_name = qualified_name;
+    }
+    entity.created_at = chrono::DateTime::parse_from_rfc3339(&created_at_str)
+        .unwrap_or_else(|_| chrono::Utc::now().into())
+        .with_timezone(&chrono::Utc);
+    entity.updated_at = chrono::DateTime::parse_from_rfc3339(&updated_at_str)
+        .unwrap_or_else(|_| chrono::Utc::now().into())
+        .with_timezone(&chrono::Utc);
+
+    Ok(entity)
+}
+
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt.query_map([], |row| row.get::<_, String>(1))?.any(|name| name.map(|name| name == column).unwrap_or(false));
+    if !exists {
+        conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])?;
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -384,12 +769,23 @@ mod tests {
         // Create and populate memory
         let mut memory = ProjectMemory::new("/test/project".to_string());
 
-        let entity1 = CodeEntity::new(
+        let mut entity1 = CodeEntity::new(
             "testFunction".to_string(),
             EntityType::Function,
             "test.js".to_string(),
             10, 20, 0, 15
         );
+        entity1.signature = Some(crate::Signature {
+            params: Some(vec![crate::Parameter::new("id", Some("string"), None)]),
+            return_type: Some("User".to_string()),
+            ..Default::default()
+        });
+        entity1.metrics = Some(crate::EntityMetrics {
+            lines_of_code: 9,
+            cyclomatic_complexity: 3,
+            ..Default::default()
+        });
+        entity1.centrality = Some(crate::Centrality { pagerank: 0.25, in_degree: 2, betweenness: 0.1 });
 
         let entity2 = CodeEntity::new(
             "TestClass".to_string(),
@@ -420,6 +816,11 @@ mod tests {
             // Verify entities were loaded correctly
             let entities: Vec<_> = loaded_memory.entities.values().collect();
             assert!(entities.iter().any(|e| e.name == "testFunction"));
+            let function = entities.iter().find(|e| e.name == "testFunction").unwrap();
+            assert_eq!(function.signature, entity1.signature);
+            assert_eq!(function.metrics, entity1.metrics);
+            assert_eq!(function.centrality, entity1.centrality);
+            assert_eq!(storage.find_entities_with_centrality()?.len(), 1);
             assert!(entities.iter().any(|e| e.name == "TestClass"));
         }
 
@@ -462,4 +863,129 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_find_entities_by_doc() -> Result<()> {
+        let temp_file = NamedTempFile::new()?;
+        let storage = MemoryStorage::new(temp_file.path().to_str().unwrap())?;
+
+        let mut memory = ProjectMemory::new("/test".to_string());
+        memory.add_entity(
+            CodeEntity::new("retry".to_string(), EntityType::Function, "net.rs".to_string(), 1, 5, 0, 0)
+                .with_metadata("doc".to_string(), "Retry with exponential backoff.".to_string()),
+        );
+        memory.add_entity(
+            CodeEntity::new("connect".to_string(), EntityType::Function, "net.rs".to_string(), 7, 9, 0, 0)
+                .with_metadata("doc_params".to_string(), "timeout: seconds before backoff".to_string()),
+        );
+        memory.add_entity(CodeEntity::new("backoff".to_string(), EntityType::Variable, "net.rs".to_string(), 11, 11, 0, 0));
+        storage.save_memory(&memory)?;
+
+        let names: Vec<_> = storage.find_entities_by_doc("backoff")?.into_iter().map(|e| e.name).collect();
+        assert_eq!(names, vec!["connect", "retry"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_entities_by_qualified_name() -> Result<()> {
+        let temp_file = NamedTempFile::new()?;
+        let storage = MemoryStorage::new(temp_file.path().to_str().unwrap())?;
+
+        let mut memory = ProjectMemory::new("/test".to_string());
+        for (name, qualified_name) in [
+            ("new", "crate::storage::MemoryStorage::new"),
+            ("new", "crate::storage::renew"),
+            ("run", "pkg.module.Service.run"),
+            ("formatDate", "src/utils.ts#formatDate"),
+        ] {
+            let mut entity = CodeEntity::new(name.to_string(), EntityType::Function, "src/lib.rs".to_string(), 1, 1, 0, 0);
+            entity.qualified_name = qualified_name.to_string();
+            memory.add_entity(entity);
+        }
+        storage.save_memory(&memory)?;
+
+        let qualified = |entities: Vec<CodeEntity>| entities.into_iter().map(|e| e.qualified_name).collect::<Vec<_>>();
+        assert_eq!(qualified(storage.find_entities_by_qualified_name("pkg.module.Service.run")?), vec!["pkg.module.Service.run"]);
+        assert_eq!(qualified(storage.find_entities_by_suffix("new")?), vec!["crate::storage::MemoryStorage::new"]);
+        assert_eq!(qualified(storage.find_entities_by_suffix("formatDate")?), vec!["src/utils.ts#formatDate"]);
+        assert_eq!(
+            qualified(storage.find_entities_in_scope("crate::storage")?),
+            vec!["crate::storage::MemoryStorage::new", "crate::storage::renew"]
+        );
+        assert_eq!(qualified(storage.find_entities_in_scope("src/utils.ts")?), vec!["src/utils.ts#formatDate"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_persistence() -> Result<()> {
+        let temp_file = NamedTempFile::new()?;
+        let storage = MemoryStorage::new(temp_file.path().to_str().unwrap())?;
+
+        let mut session = Session::new("Storage review".to_string()).with_assistant("assistant".to_string());
+        session.record(SessionEntryKind::Question, "Should saves be incremental?".to_string());
+        let decision = session.record(SessionEntryKind::Decision, "Use a transaction per save".to_string());
+        decision.entities.push("save_memory".to_string());
+        decision.files.push("src/storage.rs".to_string());
+        storage.save_session(&session)?;
+
+        // Entries added later are attached to the same session
+        let answer = SessionEntry::new(session.id.clone(), SessionEntryKind::Answer, "Not yet".to_string())
+            .with_entity("load_memory".to_string());
+        storage.add_session_entry(&answer)?;
+
+        let loaded = storage.load_session(&session.id[..8])?.expect("session should load by prefix");
+        assert_eq!(loaded.title, "Storage review");
+        assert_eq!(loaded.entries.len(), 3);
+        assert!(loaded.mentions_entity("save_memory"));
+        assert!(loaded.mentions_file("src/storage.rs"));
+        // `%` and `_` are matched literally, not as wildcards
+        assert!(storage.load_session("%")?.is_none());
+        assert!(storage.load_session(&format!("{}_", &session.id[..7]))?.is_none());
+
+        assert_eq!(storage.find_sessions_by_entity("load_memory")?.len(), 1);
+        assert_eq!(storage.find_sessions_by_file("src/storage.rs")?.len(), 1);
+        assert_eq!(storage.search_sessions("transaction")?.len(), 1);
+        assert!(storage.search_sessions("websocket")?.is_empty());
+
+        // Saving project memory must not wipe the session history
+        storage.save_memory(&ProjectMemory::new("/test".to_string()))?;
+        assert_eq!(storage.list_sessions(10)?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decision_persistence() -> Result<()> {
+        let temp_file = NamedTempFile::new()?;
+        let storage = MemoryStorage::new(temp_file.path().to_str().unwrap())?;
+
+        let mut record = DecisionRecord::new("Use SQLite".to_string())
+            .with_status(DecisionStatus::Accepted)
+            .with_entity("MemoryStorage".to_string())
+            .with_file("src/storage/**".to_string());
+        record.number = Some(1);
+        record.source_path = Some("docs/adr/0001-use-sqlite.md".to_string());
+        let id = storage.save_decision(&record)?;
+
+        // Re-importing the same file updates the existing record
+        let mut reimported = record.clone();
+        reimported.id = "different".to_string();
+        reimported.status = DecisionStatus::Superseded;
+        assert_eq!(storage.save_decision(&reimported)?, id);
+
+        let decisions = storage.load_decisions()?;
+        assert_eq!(decisions.len(), 1);
+        assert_eq!(decisions[0].status, DecisionStatus::Superseded);
+        assert_eq!(decisions[0].entities, vec!["MemoryStorage".to_string()]);
+        assert!(storage.load_decision("1")?.is_some());
+
+        let governed = CodeEntity::new("open".to_string(), EntityType::Function, "src/storage/db.rs".to_string(), 1, 1, 0, 0);
+        let ungoverned = CodeEntity::new("open".to_string(), EntityType::Function, "src/cli.rs".to_string(), 1, 1, 0, 0);
+        assert_eq!(storage.find_decisions_for_entity(&governed)?.len(), 1);
+        assert!(storage.find_decisions_for_entity(&ungoverned)?.is_empty());
+
+        Ok(())
+    }
 }
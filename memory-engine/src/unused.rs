@@ -0,0 +1,251 @@
+use crate::config::build_globset;
+use crate::memory::symbol_base_name;
+use crate::{CodeEntity, EntityType, ProjectMemory, RelationType, UnusedConfig};
+use anyhow::Result;
+use globset::GlobSet;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// How sure the report is that nothing reaches an entity. The graph is built
+/// from name matching, so a missing edge is evidence rather than proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    Low,
+    Medium,
+    High,
+}
+
+impl Confidence {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Confidence::Low => "low",
+            Confidence::Medium => "medium",
+            Confidence::High => "high",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "low" => Some(Confidence::Low),
+            "medium" => Some(Confidence::Medium),
+            "high" => Some(Confidence::High),
+            _ => None,
+        }
+    }
+}
+
+/// A function, type or constant nothing in the graph calls, uses or references
+#[derive(Debug, Clone, Serialize)]
+pub struct UnusedEntity {
+    #[serde(flatten)]
+    pub entity: CodeEntity,
+    pub confidence: Confidence,
+    pub reason: String,
+}
+
+/// Compiled `[unused]` patterns: what counts as an entry point or a test
+#[derive(Debug, Clone)]
+pub struct EntryPoints {
+    names: GlobSet,
+    attributes: GlobSet,
+    decorators: GlobSet,
+    test_files: GlobSet,
+}
+
+impl EntryPoints {
+    pub fn new(config: &UnusedConfig) -> Result<Self> {
+        let (mut names, mut attributes, mut decorators) = (Vec::new(), Vec::new(), Vec::new());
+        for pattern in &config.entry_points {
+            if let Some(attribute) = pattern.strip_prefix("#[").and_then(|p| p.strip_suffix(']')) {
+                attributes.push(attribute.to_string());
+            } else if let Some(decorator) = pattern.strip_prefix('@') {
+                decorators.push(decorator.to_string());
+            } else {
+                names.push(pattern.clone());
+            }
+        }
+        Ok(Self {
+            names: build_globset(&names)?,
+            attributes: build_globset(&attributes)?,
+            decorators: build_globset(&decorators)?,
+            test_files: build_globset(&config.test_files)?,
+        })
+    }
+
+    pub fn is_entry_point(&self, entity: &CodeEntity) -> bool {
+        let listed = |key: &str, globs: &GlobSet| {
+            entity.metadata.get(key).map(|list| list.split(',').any(|item| globs.is_match(item.trim()))).unwrap_or(false)
+        };
+        self.names.is_match(&entity.name)
+            || self.names.is_match(&entity.qualified_name)
+            || listed("attributes", &self.attributes)
+            || listed("decorators", &self.decorators)
+            || listed("annotations", &self.decorators)
+    }
+
+    pub fn is_test_file(&self, file_path: &str) -> bool {
+        self.test_files.is_match(file_path)
+    }
+}
+
+/// Find functions, types and constants with no incoming `Calls`, `Uses` or
+/// `References` edges that are not exported, entry points or tests. Code at
+/// file level counts as a use of what it names.
+pub fn find_unused(memory: &ProjectMemory, entry_points: &EntryPoints) -> Vec<UnusedEntity> {
+    let mut used: HashSet<&str> = HashSet::new();
+    let mut unresolved: HashSet<&str> = HashSet::new();
+    let mut parents: HashMap<&str, &str> = HashMap::new();
+    for rel in &memory.relationships {
+        match rel.relationship_type {
+            RelationType::Contains if rel.is_resolved() => {
+                parents.insert(rel.to_entity.as_str(), rel.from_entity.as_str());
+            }
+            RelationType::Calls | RelationType::Uses | RelationType::References | RelationType::Extends | RelationType::Implements => {
+                if !rel.is_resolved() {
+                    unresolved.extend(rel.target_name().map(symbol_base_name));
+                } else if rel.from_entity != rel.to_entity && !is_derived_edge(memory, rel) {
+                    used.insert(rel.to_entity.as_str());
+                }
+            }
+            _ => {}
+        }
+    }
+    // A type whose members are used is used as well
+    for id in used.clone() {
+        let mut current = id;
+        while let Some(&parent) = parents.get(current) {
+            if !used.insert(parent) {
+                break;
+            }
+            current = parent;
+        }
+    }
+
+    let ancestors = |id: &str| {
+        let mut chain = Vec::new();
+        let mut current = id;
+        while let Some(parent) = parents.get(current).and_then(|parent| memory.entities.get(*parent)) {
+            if chain.len() > memory.entities.len() {
+                break;
+            }
+            chain.push(parent);
+            current = &parent.id;
+        }
+        chain
+    };
+
+    let mut unused: Vec<UnusedEntity> = memory
+        .entities
+        .values()
+        .filter(|e| matches!(e.entity_type, EntityType::Function | EntityType::Class | EntityType::Interface | EntityType::Type | EntityType::Constant))
+        // `impl` blocks and extensions add to a type rather than declare one
+        .filter(|e| !matches!(e.metadata.get("kind").map(String::as_str), Some("impl" | "extension")))
+        .filter(|e| !used.contains(e.id.as_str()) && !e.metadata.contains_key("file_level_use"))
+        .filter(|e| !is_exported(e) && !entry_points.is_entry_point(e))
+        .filter_map(|entity| {
+            let ancestors = ancestors(&entity.id);
+            if is_test(entity) || ancestors.iter().any(|a| is_test(a)) || entry_points.is_test_file(&entity.file_path) {
+                return None;
+            }
+            // Trait and interface implementations are reached through the trait
+            let implements = ancestors.first().map(|p| p.metadata.contains_key("trait")).unwrap_or(false)
+                || entity.metadata.get("annotations").map(|a| a.split(',').any(|a| a == "Override")).unwrap_or(false)
+                || entity.signature.as_ref().map(|s| s.modifiers.iter().any(|m| m == "override")).unwrap_or(false);
+            if implements {
+                return None;
+            }
+
+            let is_member = ancestors.first().map(|p| p.entity_type != EntityType::Module || p.metadata.contains_key("self_type")).unwrap_or(false);
+            let (confidence, reason) = if unresolved.contains(entity.name.as_str()) {
+                (Confidence::Low, format!("a call or reference to `{}` matched several entities and was not linked", entity.name))
+            } else if is_member || is_dynamic(&entity.file_path) {
+                (Confidence::Medium, "no incoming edges, but methods and dynamic code can be reached indirectly".to_string())
+            } else {
+                (Confidence::High, "no calls, uses or references found".to_string())
+            };
+            Some(UnusedEntity { entity: entity.clone(), confidence, reason })
+        })
+        .collect();
+
+    unused.sort_by(|a, b| (&a.entity.file_path, a.entity.line_start).cmp(&(&b.entity.file_path, b.entity.line_start)));
+    unused
+}
+
+// `impl Display for Foo` and Swift extensions add to a type without using it or its traits
+fn is_derived_edge(memory: &ProjectMemory, rel: &crate::Relationship) -> bool {
+    matches!(rel.relationship_type, RelationType::Extends | RelationType::Implements)
+        && memory
+            .entities
+            .get(&rel.from_entity)
+            .and_then(|from| from.metadata.get("kind"))
+            .map(|kind| kind == "impl" || kind == "extension")
+            .unwrap_or(false)
+}
+
+pub(crate) fn is_exported(entity: &CodeEntity) -> bool {
+    let visibility = entity
+        .metadata
+        .get("visibility")
+        .or_else(|| entity.signature.as_ref().and_then(|s| s.visibility.as_ref()))
+        .map(String::as_str);
+    let go_exported = entity.file_path.ends_with(".go") && entity.name.starts_with(|c: char| c.is_uppercase());
+    matches!(visibility, Some("pub" | "public" | "open" | "export")) || go_exported
+}
+
+fn is_test(entity: &CodeEntity) -> bool {
+    entity.metadata.get("test").map(|t| t == "true").unwrap_or(false)
+        || entity.metadata.get("cfg").map(|cfg| cfg.contains("test")).unwrap_or(false)
+        || (entity.entity_type == EntityType::Function && (entity.name.starts_with("test_") || entity.name.starts_with("Test")))
+}
+
+// Languages where calls are often made by name at runtime
+fn is_dynamic(file_path: &str) -> bool {
+    let extension = Path::new(file_path).extension().and_then(|e| e.to_str()).unwrap_or("");
+    matches!(extension, "py" | "rb" | "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "php")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CodeParser;
+
+    #[test]
+    fn test_find_unused_with_confidence() -> Result<()> {
+        let parser = CodeParser::new()?;
+        let mut memory = ProjectMemory::new("/test".to_string());
+        let files = [
+            ("src/main.rs", "fn main() {\n    run();\n}\n\nfn run() {\n    helper();\n    close();\n}\n\nfn helper() {}\n\nfn orphan() {}\n\npub fn api() {}\n"),
+            ("src/store.rs", "struct Store;\nimpl Store {\n    fn save(&self) {}\n}\n\nfn flush() {\n    x.save();\n}\n\nfn close() {}\n"),
+            ("src/other.rs", "fn close() {}\n"),
+            ("src/lib_test.rs", "fn fixture() {}\n"),
+            ("src/checks.rs", "#[test]\nfn checks_run() {}\n\n#[cfg(test)]\nmod tests {\n    fn setup() {}\n}\n"),
+        ];
+        for (path, source) in files {
+            let (entities, relationships) = parser.parse_source(path, source)?;
+            entities.into_iter().for_each(|e| memory.add_entity(e));
+            relationships.into_iter().for_each(|r| memory.add_relationship(r));
+        }
+        memory.resolve_references();
+
+        let entry_points = EntryPoints::new(&UnusedConfig::default())?;
+        let report: Vec<String> = find_unused(&memory, &entry_points)
+            .iter()
+            .map(|u| format!("{} {} {}", u.entity.file_path, u.entity.name, u.confidence.as_str()))
+            .collect();
+        assert_eq!(
+            report,
+            vec![
+                "src/main.rs orphan high",
+                "src/other.rs close low",
+                // `x.save()` may or may not be this `save`
+                "src/store.rs save low",
+                "src/store.rs flush high",
+                "src/store.rs close low"
+            ]
+        );
+        Ok(())
+    }
+}